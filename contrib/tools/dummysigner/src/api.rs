@@ -1,6 +1,9 @@
 use revault_tx::bitcoin::{
     blockdata::transaction::OutPoint,
-    util::{bip32::ChildNumber, psbt::PartiallySignedTransaction},
+    util::{
+        bip32::{ChildNumber, DerivationPath},
+        psbt::PartiallySignedTransaction,
+    },
     Amount,
 };
 use serde::{Deserialize, Serialize};
@@ -15,6 +18,17 @@ pub enum Request {
     DelegateBatch(DelegateBatch),
 }
 
+/// Requests about the signer keys, answered without user confirmation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum KeyRequest {
+    Fingerprint,
+    Xpub {
+        #[serde(with = "bitcoin_derivation_path")]
+        derivation_path: DerivationPath,
+    },
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct SecureBatch {
     pub deposits: Vec<UTXO>,
@@ -98,6 +112,20 @@ mod bitcoin_derivation_index {
     }
 }
 
+mod bitcoin_derivation_path {
+    use revault_tx::bitcoin::util::bip32::DerivationPath;
+    use serde::{self, Deserialize, Deserializer};
+    use std::str::FromStr;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DerivationPath, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)
+            .and_then(|s| DerivationPath::from_str(&s).map_err(serde::de::Error::custom))
+    }
+}
+
 mod bitcoin_psbt {
     use revault_tx::bitcoin::{consensus::encode, util::psbt::PartiallySignedTransaction};
     use serde::{self, Deserialize, Deserializer, Serializer};
@@ -45,9 +45,11 @@ impl Application for App {
     type Flags = Config;
 
     fn new(cfg: Config) -> (App, Command<Message>) {
+        let network = cfg.network();
         (
             App {
                 signer: sign::Signer::new(
+                    network,
                     cfg.descriptors.map(|d| sign::Descriptors {
                         deposit_descriptor: d.deposit_descriptor,
                         unvault_descriptor: d.unvault_descriptor,
@@ -77,6 +79,17 @@ impl Application for App {
                 Command::none()
             }
             Message::Server(server::ServerMessage::Request(msg)) => {
+                if let Ok(req) = serde_json::from_value::<api::KeyRequest>(msg.clone()) {
+                    let res = self.answer_key_request(req);
+                    if let AppStatus::Connected { writer, .. } = &self.status {
+                        return Command::perform(
+                            server::respond(writer.clone(), res),
+                            server::ServerMessage::Responded,
+                        )
+                        .map(Message::Server);
+                    }
+                    return Command::none();
+                }
                 if let AppStatus::Connected { method, writer, .. } = &mut self.status {
                     match serde_json::from_value(msg) {
                         Ok(req) => {
@@ -287,6 +300,27 @@ impl Application for App {
     }
 }
 
+impl App {
+    /// Key requests are answered with the first key of the configuration.
+    fn answer_key_request(&self, req: api::KeyRequest) -> serde_json::Value {
+        let key = match self.keys.first() {
+            Some(key) => key,
+            None => return json!({"error": "no key"}),
+        };
+        match req {
+            api::KeyRequest::Fingerprint => {
+                json!({"fingerprint": self.signer.fingerprint(&key.xpriv).to_string()})
+            }
+            api::KeyRequest::Xpub { derivation_path } => {
+                match self.signer.xpub(&key.xpriv, &derivation_path) {
+                    Ok(xpub) => json!({"xpub": xpub.to_string()}),
+                    Err(e) => json!({"error": format!("{:?}", e)}),
+                }
+            }
+        }
+    }
+}
+
 pub struct Key {
     name: String,
     xpriv: ExtendedPrivKey,
@@ -315,18 +349,21 @@ pub enum Method {
     SignSpendTx {
         keys: Vec<Key>,
         target: api::SpendTransaction,
+        transactions: Vec<(String, sign::PsbtInspection)>,
         signed: bool,
         view: view::SignSpendTxView,
     },
     SignUnvaultTx {
         keys: Vec<Key>,
         target: api::UnvaultTransaction,
+        transactions: Vec<(String, sign::PsbtInspection)>,
         signed: bool,
         view: view::SignUnvaultTxView,
     },
     SignRevocationTxs {
         keys: Vec<Key>,
         target: api::RevocationTransactions,
+        transactions: Vec<(String, sign::PsbtInspection)>,
         signed: bool,
         view: view::SignRevocationTxsView,
     },
@@ -334,6 +371,7 @@ pub enum Method {
         keys: Vec<Key>,
         target: Vec<api::RevocationTransactions>,
         total_amount: u64,
+        transactions: Vec<(String, sign::PsbtInspection)>,
         signed: bool,
         view: view::SecureBatchView,
     },
@@ -341,6 +379,7 @@ pub enum Method {
         keys: Vec<Key>,
         target: Vec<api::UnvaultTransaction>,
         total_amount: u64,
+        transactions: Vec<(String, sign::PsbtInspection)>,
         signed: bool,
         view: view::DelegateBatchView,
     },
@@ -369,8 +408,13 @@ impl Method {
                     keys[0].selected = true;
                 }
 
+                let transactions = vec![(
+                    "Spend transaction".to_string(),
+                    signer.inspect(&target.spend_tx),
+                )];
                 Method::SignSpendTx {
                     keys,
+                    transactions,
                     target,
                     signed: false,
                     view: view::SignSpendTxView::new(),
@@ -393,8 +437,13 @@ impl Method {
                     keys[0].selected = true;
                 }
 
+                let transactions = vec![(
+                    "Unvault transaction".to_string(),
+                    signer.inspect(&target.unvault_tx),
+                )];
                 Method::SignUnvaultTx {
                     keys,
+                    transactions,
                     target,
                     signed: false,
                     view: view::SignUnvaultTxView::new(),
@@ -417,8 +466,10 @@ impl Method {
                     keys[0].selected = true;
                 }
 
+                let transactions = revocation_transactions(signer, &target);
                 Method::SignRevocationTxs {
                     keys,
+                    transactions,
                     target,
                     signed: false,
                     view: view::SignRevocationTxsView::new(),
@@ -466,9 +517,14 @@ impl Method {
                     keys[0].selected = true;
                 }
 
+                let transactions = target
+                    .iter()
+                    .flat_map(|txs| revocation_transactions(signer, txs))
+                    .collect();
                 Method::SecureBatch {
                     total_amount,
                     keys,
+                    transactions,
                     target,
                     signed: false,
                     view: view::SecureBatchView::new(),
@@ -512,9 +568,19 @@ impl Method {
                     keys[0].selected = true;
                 }
 
+                let transactions = target
+                    .iter()
+                    .map(|tx| {
+                        (
+                            "Unvault transaction".to_string(),
+                            signer.inspect(&tx.unvault_tx),
+                        )
+                    })
+                    .collect();
                 Method::DelegateBatch {
                     total_amount,
                     keys,
+                    transactions,
                     target,
                     signed: false,
                     view: view::DelegateBatchView::new(),
@@ -528,10 +594,12 @@ impl Method {
             Self::SignSpendTx {
                 view,
                 target,
+                transactions,
                 signed,
                 keys,
             } => view.render(
                 &target,
+                transactions,
                 *signed,
                 keys.iter()
                     .enumerate()
@@ -542,10 +610,12 @@ impl Method {
             Self::SignUnvaultTx {
                 view,
                 target,
+                transactions,
                 signed,
                 keys,
             } => view.render(
                 &target,
+                transactions,
                 *signed,
                 keys.iter()
                     .enumerate()
@@ -556,10 +626,12 @@ impl Method {
             Self::SignRevocationTxs {
                 view,
                 target,
+                transactions,
                 signed,
                 keys,
             } => view.render(
                 &target,
+                transactions,
                 *signed,
                 keys.iter()
                     .enumerate()
@@ -571,11 +643,13 @@ impl Method {
                 view,
                 total_amount,
                 target,
+                transactions,
                 signed,
                 keys,
             } => view.render(
                 *total_amount,
                 target.len(),
+                transactions,
                 *signed,
                 keys.iter()
                     .enumerate()
@@ -587,11 +661,13 @@ impl Method {
                 total_amount,
                 view,
                 target,
+                transactions,
                 signed,
                 keys,
             } => view.render(
                 *total_amount,
                 target.len(),
+                transactions,
                 *signed,
                 keys.iter()
                     .enumerate()
@@ -602,3 +678,25 @@ impl Method {
         }
     }
 }
+
+/// The cancel transactions only differ by their feerate, the one with the
+/// lowest feerate is inspected.
+fn revocation_transactions(
+    signer: &sign::Signer,
+    txs: &api::RevocationTransactions,
+) -> Vec<(String, sign::PsbtInspection)> {
+    vec![
+        (
+            "Emergency transaction".to_string(),
+            signer.inspect(&txs.emergency_tx),
+        ),
+        (
+            "Unvault emergency transaction".to_string(),
+            signer.inspect(&txs.emergency_unvault_tx),
+        ),
+        (
+            "Cancel transaction (lowest of 5 feerates)".to_string(),
+            signer.inspect(&txs.cancel_txs[0]),
+        ),
+    ]
+}
@@ -4,7 +4,7 @@ use std::str::FromStr;
 use serde::{de, Deserialize, Deserializer};
 
 use revault_tx::{
-    bitcoin::util::bip32::ExtendedPrivKey,
+    bitcoin::{util::bip32::ExtendedPrivKey, Network},
     scripts::{CpfpDescriptor, DepositDescriptor, EmergencyAddress, UnvaultDescriptor},
 };
 
@@ -46,6 +46,17 @@ impl Config {
             emergency_address: None,
         }
     }
+
+    /// Network of the signed transactions, the one of the emergency address
+    /// or of the keys otherwise.
+    pub fn network(&self) -> Network {
+        self.emergency_address
+            .as_ref()
+            .map(|address| address.address().network)
+            .or_else(|| self.keys.first().map(|key| key.xpriv.network))
+            .unwrap_or(Network::Bitcoin)
+    }
+
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
         std::fs::read(path)
             .map_err(|e| match e.kind() {
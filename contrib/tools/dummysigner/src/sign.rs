@@ -4,21 +4,22 @@ use revault_tx::{
         secp256k1,
         util::{
             bip143::SigHashCache,
-            bip32::{ChildNumber, ExtendedPrivKey},
+            bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint},
             psbt::PartiallySignedTransaction,
         },
-        Amount, SigHashType,
+        Address, Amount, Network, Script, SigHashType, Txid,
     },
     scripts::{CpfpDescriptor, DepositDescriptor, EmergencyAddress, UnvaultDescriptor},
     transactions::{transaction_chain, RevaultTransaction, UnvaultTransaction},
     txins::DepositTxIn,
-    txouts::DepositTxOut,
+    txouts::{CpfpTxOut, DepositTxOut, RevaultTxOut, UnvaultTxOut},
 };
 
 #[derive(Debug)]
 pub struct Error(String);
 
 pub struct Signer {
+    network: Network,
     descriptors: Option<Descriptors>,
     emergency_address: Option<EmergencyAddress>,
     curve: secp256k1::Secp256k1<secp256k1::All>,
@@ -32,10 +33,12 @@ pub struct Descriptors {
 
 impl Signer {
     pub fn new(
+        network: Network,
         descriptors: Option<Descriptors>,
         emergency_address: Option<EmergencyAddress>,
     ) -> Signer {
         Self {
+            network,
             descriptors,
             emergency_address,
             curve: secp256k1::Secp256k1::new(),
@@ -50,6 +53,22 @@ impl Signer {
         self.emergency_address.is_some()
     }
 
+    pub fn fingerprint(&self, key: &ExtendedPrivKey) -> Fingerprint {
+        key.fingerprint(&self.curve)
+    }
+
+    /// Derive the extended public key of the given key at the derivation path.
+    pub fn xpub(
+        &self,
+        key: &ExtendedPrivKey,
+        derivation_path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, Error> {
+        let xpriv = key
+            .derive_priv(&self.curve, derivation_path)
+            .map_err(|e| Error(e.to_string()))?;
+        Ok(ExtendedPubKey::from_private(&self.curve, &xpriv))
+    }
+
     pub fn requires_key_for_psbt(
         &self,
         key: &ExtendedPrivKey,
@@ -190,6 +209,152 @@ impl Signer {
 
         Ok(unvault_tx.into_psbt())
     }
+
+    /// Decodes what the signature of the psbt commits to, the outputs of the
+    /// wallet are recognized with the descriptors at the derivation indexes
+    /// of the psbt keys.
+    pub fn inspect(&self, psbt: &PartiallySignedTransaction) -> PsbtInspection {
+        let tx = &psbt.global.unsigned_tx;
+        let mut scripts: Vec<(Script, &'static str)> = Vec::new();
+        if let Some(descriptors) = &self.descriptors {
+            let mut indexes: Vec<ChildNumber> = psbt
+                .inputs
+                .iter()
+                .flat_map(|input| input.bip32_derivation.values())
+                .chain(
+                    psbt.outputs
+                        .iter()
+                        .flat_map(|output| output.bip32_derivation.values()),
+                )
+                .filter_map(|(_, path)| path.as_ref().last().copied())
+                .filter(|index| !index.is_hardened())
+                .collect();
+            indexes.sort();
+            indexes.dedup();
+            let amount = Amount::from_sat(0);
+            for index in indexes {
+                scripts.push((
+                    DepositTxOut::new(
+                        amount,
+                        &descriptors.deposit_descriptor.derive(index, &self.curve),
+                    )
+                    .into_txout()
+                    .script_pubkey,
+                    "deposit",
+                ));
+                scripts.push((
+                    UnvaultTxOut::new(
+                        amount,
+                        &descriptors.unvault_descriptor.derive(index, &self.curve),
+                    )
+                    .into_txout()
+                    .script_pubkey,
+                    "unvault",
+                ));
+                scripts.push((
+                    CpfpTxOut::new(
+                        amount,
+                        &descriptors.cpfp_descriptor.derive(index, &self.curve),
+                    )
+                    .into_txout()
+                    .script_pubkey,
+                    "cpfp",
+                ));
+            }
+        }
+        if let Some(address) = &self.emergency_address {
+            scripts.push((address.address().script_pubkey(), "emergency"));
+        }
+        let kind = |script: &Script| {
+            scripts
+                .iter()
+                .find(|(s, _)| s == script)
+                .map(|(_, kind)| *kind)
+        };
+
+        let inputs: Vec<InspectedInput> = tx
+            .input
+            .iter()
+            .zip(psbt.inputs.iter())
+            .map(|(txin, input)| {
+                let mut signed_by = Vec::new();
+                for key in input.partial_sigs.keys() {
+                    if let Some((fingerprint, _)) = input.bip32_derivation.get(key) {
+                        if !signed_by.contains(fingerprint) {
+                            signed_by.push(*fingerprint);
+                        }
+                    }
+                }
+                InspectedInput {
+                    previous_output: txin.previous_output,
+                    amount: input
+                        .witness_utxo
+                        .as_ref()
+                        .map(|utxo| Amount::from_sat(utxo.value)),
+                    sequence: txin.sequence,
+                    kind: input
+                        .witness_utxo
+                        .as_ref()
+                        .and_then(|utxo| kind(&utxo.script_pubkey)),
+                    signed_by,
+                }
+            })
+            .collect();
+
+        let outputs = tx
+            .output
+            .iter()
+            .map(|output| InspectedOutput {
+                address: Address::from_script(&output.script_pubkey, self.network)
+                    .map(|address| address.to_string())
+                    .unwrap_or_else(|| output.script_pubkey.to_string()),
+                amount: Amount::from_sat(output.value),
+                kind: kind(&output.script_pubkey),
+            })
+            .collect();
+
+        let fee = inputs
+            .iter()
+            .map(|input| input.amount.map(|amount| amount.as_sat()))
+            .sum::<Option<u64>>()
+            .and_then(|total| total.checked_sub(tx.output.iter().map(|o| o.value).sum()))
+            .map(Amount::from_sat);
+
+        PsbtInspection {
+            txid: tx.txid(),
+            locktime: tx.lock_time,
+            unsigned_weight: tx.get_weight(),
+            inputs,
+            outputs,
+            fee,
+        }
+    }
+}
+
+pub struct InspectedInput {
+    pub previous_output: OutPoint,
+    pub amount: Option<Amount>,
+    pub sequence: u32,
+    /// None if the spent output is not one of the wallet.
+    pub kind: Option<&'static str>,
+    pub signed_by: Vec<Fingerprint>,
+}
+
+pub struct InspectedOutput {
+    pub address: String,
+    pub amount: Amount,
+    /// None for an external recipient.
+    pub kind: Option<&'static str>,
+}
+
+pub struct PsbtInspection {
+    pub txid: Txid,
+    pub locktime: u32,
+    /// Weight of the transaction without the witnesses.
+    pub unsigned_weight: usize,
+    pub inputs: Vec<InspectedInput>,
+    pub outputs: Vec<InspectedOutput>,
+    pub fee: Option<Amount>,
 }
 
 pub struct RevocationTransactions {
@@ -212,6 +377,7 @@ mod tests {
     fn derive_revocation_txs() {
         let cfg = Config::from_file(&PathBuf::from("examples/examples_cfg.toml")).unwrap();
         let signer = Signer::new(
+            cfg.network(),
             cfg.descriptors.map(|d| Descriptors {
                 deposit_descriptor: d.deposit_descriptor,
                 unvault_descriptor: d.unvault_descriptor,
@@ -249,6 +415,7 @@ mod tests {
     fn derive_unvault_tx() {
         let cfg = Config::from_file(&PathBuf::from("examples/examples_cfg.toml")).unwrap();
         let signer = Signer::new(
+            cfg.network(),
             cfg.descriptors.map(|d| Descriptors {
                 deposit_descriptor: d.deposit_descriptor,
                 unvault_descriptor: d.unvault_descriptor,
@@ -273,4 +440,51 @@ mod tests {
             "cHNidP8BAIkCAAAAAUeuD/NEqc88sk3DoBrKoVKjXbN2xW8Jr/4GO5q87JqJAQAAAAD9////AriGJgcAAAAAIgAgSOjPZes2prPdrcgiv+IG1sjXyTCc4KDr9+C9F+xk6LwwdQAAAAAAACIAIAjkMa8elv7dHUmYpDATWBtmMmpv9yyKFawMunvGQ1AMAAAAAAABASsADicHAAAAACIAIHXyaRd0yBZ3gxhGsCgiAOKIssWXELWPdDGD1JJVB9vFAQVHUiECWC3tv0T0ZWTl2M2wZ1NtYOvjTNHRgBz/Ubv516wom0MhA0cE3stVtaqI/9HvXQY2YkjBMU4ZZVETb/FOq4u6SkkOUq4iBgJYLe2/RPRlZOXYzbBnU21g6+NM0dGAHP9Ru/nXrCibQwjWfX/pAAAAACIGA0cE3stVtaqI/9HvXQY2YkjBMU4ZZVETb/FOq4u6SkkOCHKpXyIAAAAAACICAgKTOrEDfq0KpKeFjG1J1nBeH7O8X2awCRive58A7NUmCCUdYAkAAAAAIgICWC3tv0T0ZWTl2M2wZ1NtYOvjTNHRgBz/Ubv516wom0MI1n1/6QAAAAAiAgNHBN7LVbWqiP/R710GNmJIwTFOGWVRE2/xTquLukpJDghyqV8iAAAAAAAiAgJQcvTgdleKXInUHXXx7VKav5LxzWq2Nrjot7sPMwJpQwj73ezNAAAAAAA="
         );
     }
+
+    #[test]
+    fn inspect_unvault_tx() {
+        let cfg = Config::from_file(&PathBuf::from("examples/examples_cfg.toml")).unwrap();
+        let signer = Signer::new(
+            cfg.network(),
+            cfg.descriptors.map(|d| Descriptors {
+                deposit_descriptor: d.deposit_descriptor,
+                unvault_descriptor: d.unvault_descriptor,
+                cpfp_descriptor: d.cpfp_descriptor,
+            }),
+            cfg.emergency_address,
+        );
+
+        let outpoint = OutPoint::from_str(
+            "899aecbc9a3b06feaf096fc576b35da352a1ca1aa0c34db23ccfa944f30fae47:1",
+        )
+        .unwrap();
+        let unvault_tx = signer
+            .derive_unvault_tx(
+                outpoint,
+                Amount::from_sat(120000000),
+                ChildNumber::from_normal_idx(0).unwrap(),
+            )
+            .unwrap();
+
+        let inspection = signer.inspect(&unvault_tx);
+        assert_eq!(inspection.inputs.len(), 1);
+        assert_eq!(inspection.inputs[0].previous_output, outpoint);
+        assert_eq!(inspection.inputs[0].kind, Some("deposit"));
+        assert!(inspection.inputs[0].signed_by.is_empty());
+        let kinds: Vec<Option<&str>> = inspection.outputs.iter().map(|o| o.kind).collect();
+        assert_eq!(kinds, vec![Some("unvault"), Some("cpfp")]);
+        // The network is the one of the regtest emergency address.
+        assert!(inspection.outputs[0].address.starts_with("bcrt1"));
+        let outputs_value: u64 = unvault_tx
+            .global
+            .unsigned_tx
+            .output
+            .iter()
+            .map(|o| o.value)
+            .sum();
+        assert_eq!(
+            inspection.fee,
+            Some(Amount::from_sat(120000000 - outputs_value))
+        );
+    }
 }
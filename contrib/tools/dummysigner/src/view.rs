@@ -1,12 +1,12 @@
 use iced::{
-    alignment, button, container, Button, Checkbox, Color, Column, Container, Element, Length, Row,
-    Text,
+    alignment, button, container, scrollable, Button, Checkbox, Color, Column, Container, Element,
+    Length, Row, Scrollable, Text,
 };
 use std::net::SocketAddr;
 
 use revault_tx::bitcoin::Amount;
 
-use crate::api;
+use crate::{api, sign::PsbtInspection};
 
 #[derive(Debug, Clone)]
 pub enum ViewMessage {
@@ -41,6 +41,7 @@ pub fn connected<'a>(addr: &SocketAddr) -> Element<'a, ViewMessage> {
 pub struct SignSpendTxView {
     cancel_button: button::State,
     confirm_button: button::State,
+    scroll: scrollable::State,
 }
 
 impl SignSpendTxView {
@@ -48,12 +49,14 @@ impl SignSpendTxView {
         Self {
             cancel_button: button::State::new(),
             confirm_button: button::State::new(),
+            scroll: scrollable::State::new(),
         }
     }
 
     pub fn render<'a>(
         &'a mut self,
         _req: &api::SpendTransaction,
+        transactions: &[(String, PsbtInspection)],
         signed: bool,
         keys: Vec<Element<'a, ViewMessage>>,
         can_confirm: bool,
@@ -73,17 +76,21 @@ impl SignSpendTxView {
         }
 
         Container::new(
-            Column::new()
-                .push(Text::new("Send funds"))
-                .push(Text::new("Select keys to sign the transaction with"))
-                .push(Column::with_children(keys).spacing(10))
-                .push(confirmation_footer(
-                    &mut self.cancel_button,
-                    &mut self.confirm_button,
-                    can_confirm,
-                ))
-                .spacing(20)
-                .align_items(alignment::Alignment::Center),
+            Scrollable::new(&mut self.scroll).push(
+                Column::new()
+                    .push(Text::new("Send funds"))
+                    .push(Text::new("Select keys to sign the transaction with"))
+                    .push(Column::with_children(keys).spacing(10))
+                    .push(transactions_view(transactions))
+                    .push(confirmation_footer(
+                        &mut self.cancel_button,
+                        &mut self.confirm_button,
+                        can_confirm,
+                    ))
+                    .spacing(20)
+                    .padding(20)
+                    .align_items(alignment::Alignment::Center),
+            ),
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -96,6 +103,7 @@ impl SignSpendTxView {
 pub struct SignUnvaultTxView {
     cancel_button: button::State,
     confirm_button: button::State,
+    scroll: scrollable::State,
 }
 
 impl SignUnvaultTxView {
@@ -103,12 +111,14 @@ impl SignUnvaultTxView {
         Self {
             cancel_button: button::State::new(),
             confirm_button: button::State::new(),
+            scroll: scrollable::State::new(),
         }
     }
 
     pub fn render<'a>(
         &'a mut self,
         _req: &api::UnvaultTransaction,
+        transactions: &[(String, PsbtInspection)],
         signed: bool,
         keys: Vec<Element<'a, ViewMessage>>,
         can_confirm: bool,
@@ -128,17 +138,21 @@ impl SignUnvaultTxView {
         }
 
         Container::new(
-            Column::new()
-                .push(Text::new("Delegate vault"))
-                .push(Text::new("Select keys to sign unvault transaction with"))
-                .push(Column::with_children(keys).spacing(10))
-                .push(confirmation_footer(
-                    &mut self.cancel_button,
-                    &mut self.confirm_button,
-                    can_confirm,
-                ))
-                .spacing(20)
-                .align_items(alignment::Alignment::Center),
+            Scrollable::new(&mut self.scroll).push(
+                Column::new()
+                    .push(Text::new("Delegate vault"))
+                    .push(Text::new("Select keys to sign unvault transaction with"))
+                    .push(Column::with_children(keys).spacing(10))
+                    .push(transactions_view(transactions))
+                    .push(confirmation_footer(
+                        &mut self.cancel_button,
+                        &mut self.confirm_button,
+                        can_confirm,
+                    ))
+                    .spacing(20)
+                    .padding(20)
+                    .align_items(alignment::Alignment::Center),
+            ),
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -151,6 +165,7 @@ impl SignUnvaultTxView {
 pub struct SignRevocationTxsView {
     cancel_button: button::State,
     confirm_button: button::State,
+    scroll: scrollable::State,
 }
 
 impl SignRevocationTxsView {
@@ -158,12 +173,14 @@ impl SignRevocationTxsView {
         Self {
             cancel_button: button::State::new(),
             confirm_button: button::State::new(),
+            scroll: scrollable::State::new(),
         }
     }
 
     pub fn render<'a>(
         &'a mut self,
         _req: &api::RevocationTransactions,
+        transactions: &[(String, PsbtInspection)],
         signed: bool,
         keys: Vec<Element<'a, ViewMessage>>,
         can_confirm: bool,
@@ -183,19 +200,23 @@ impl SignRevocationTxsView {
         }
 
         Container::new(
-            Column::new()
-                .push(Text::new("Secure deposit"))
-                .push(Text::new(
-                    "Select keys to sign revocation transactions with",
-                ))
-                .push(Column::with_children(keys).spacing(10))
-                .push(confirmation_footer(
-                    &mut self.cancel_button,
-                    &mut self.confirm_button,
-                    can_confirm,
-                ))
-                .spacing(20)
-                .align_items(alignment::Alignment::Center),
+            Scrollable::new(&mut self.scroll).push(
+                Column::new()
+                    .push(Text::new("Secure deposit"))
+                    .push(Text::new(
+                        "Select keys to sign revocation transactions with",
+                    ))
+                    .push(Column::with_children(keys).spacing(10))
+                    .push(transactions_view(transactions))
+                    .push(confirmation_footer(
+                        &mut self.cancel_button,
+                        &mut self.confirm_button,
+                        can_confirm,
+                    ))
+                    .spacing(20)
+                    .padding(20)
+                    .align_items(alignment::Alignment::Center),
+            ),
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -208,6 +229,7 @@ impl SignRevocationTxsView {
 pub struct SecureBatchView {
     cancel_button: button::State,
     confirm_button: button::State,
+    scroll: scrollable::State,
 }
 
 impl SecureBatchView {
@@ -215,6 +237,7 @@ impl SecureBatchView {
         Self {
             cancel_button: button::State::new(),
             confirm_button: button::State::new(),
+            scroll: scrollable::State::new(),
         }
     }
 
@@ -222,6 +245,7 @@ impl SecureBatchView {
         &'a mut self,
         total_amount: u64,
         total_deposits: usize,
+        transactions: &[(String, PsbtInspection)],
         signed: bool,
         keys: Vec<Element<'a, ViewMessage>>,
         can_confirm: bool,
@@ -245,23 +269,27 @@ impl SecureBatchView {
         }
 
         Container::new(
-            Column::new()
-                .push(Text::new(format!(
-                    "Vault {} deposits for a total of {} BTC",
-                    total_deposits,
-                    Amount::from_sat(total_amount).as_btc()
-                )))
-                .push(Text::new(
-                    "Select keys to sign the revocation transactions with:",
-                ))
-                .push(Column::with_children(keys).spacing(10))
-                .push(confirmation_footer(
-                    &mut self.cancel_button,
-                    &mut self.confirm_button,
-                    can_confirm,
-                ))
-                .spacing(20)
-                .align_items(alignment::Alignment::Center),
+            Scrollable::new(&mut self.scroll).push(
+                Column::new()
+                    .push(Text::new(format!(
+                        "Vault {} deposits for a total of {} BTC",
+                        total_deposits,
+                        Amount::from_sat(total_amount).as_btc()
+                    )))
+                    .push(Text::new(
+                        "Select keys to sign the revocation transactions with:",
+                    ))
+                    .push(Column::with_children(keys).spacing(10))
+                    .push(transactions_view(transactions))
+                    .push(confirmation_footer(
+                        &mut self.cancel_button,
+                        &mut self.confirm_button,
+                        can_confirm,
+                    ))
+                    .spacing(20)
+                    .padding(20)
+                    .align_items(alignment::Alignment::Center),
+            ),
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -274,6 +302,7 @@ impl SecureBatchView {
 pub struct DelegateBatchView {
     cancel_button: button::State,
     confirm_button: button::State,
+    scroll: scrollable::State,
 }
 
 impl DelegateBatchView {
@@ -281,6 +310,7 @@ impl DelegateBatchView {
         Self {
             cancel_button: button::State::new(),
             confirm_button: button::State::new(),
+            scroll: scrollable::State::new(),
         }
     }
 
@@ -288,6 +318,7 @@ impl DelegateBatchView {
         &'a mut self,
         total_amount: u64,
         total_vaults: usize,
+        transactions: &[(String, PsbtInspection)],
         signed: bool,
         keys: Vec<Element<'a, ViewMessage>>,
         can_confirm: bool,
@@ -311,21 +342,25 @@ impl DelegateBatchView {
         }
 
         Container::new(
-            Column::new()
-                .push(Text::new(format!(
-                    "Delegate {} vaults for a total of {} BTC",
-                    total_vaults,
-                    Amount::from_sat(total_amount).as_btc(),
-                )))
-                .push(Text::new("Select keys to sign unvault transactions with"))
-                .push(Column::with_children(keys).spacing(10))
-                .push(confirmation_footer(
-                    &mut self.cancel_button,
-                    &mut self.confirm_button,
-                    can_confirm,
-                ))
-                .spacing(20)
-                .align_items(alignment::Alignment::Center),
+            Scrollable::new(&mut self.scroll).push(
+                Column::new()
+                    .push(Text::new(format!(
+                        "Delegate {} vaults for a total of {} BTC",
+                        total_vaults,
+                        Amount::from_sat(total_amount).as_btc(),
+                    )))
+                    .push(Text::new("Select keys to sign unvault transactions with"))
+                    .push(Column::with_children(keys).spacing(10))
+                    .push(transactions_view(transactions))
+                    .push(confirmation_footer(
+                        &mut self.cancel_button,
+                        &mut self.confirm_button,
+                        can_confirm,
+                    ))
+                    .spacing(20)
+                    .padding(20)
+                    .align_items(alignment::Alignment::Center),
+            ),
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -335,6 +370,83 @@ impl DelegateBatchView {
     }
 }
 
+/// Lists what the signatures commit to for each transaction to sign.
+fn transactions_view<'a>(transactions: &[(String, PsbtInspection)]) -> Column<'a, ViewMessage> {
+    transactions
+        .iter()
+        .fold(Column::new().spacing(20), |col, (title, inspection)| {
+            col.push(psbt_view(title, inspection))
+        })
+}
+
+pub fn psbt_view<'a>(title: &str, inspection: &PsbtInspection) -> Element<'a, ViewMessage> {
+    let mut col = Column::new()
+        .spacing(5)
+        .push(Text::new(title))
+        .push(Text::new(inspection.txid.to_string()).size(15))
+        .push(Text::new(format!("{} inputs:", inspection.inputs.len())));
+    for input in &inspection.inputs {
+        let signed_by = if input.signed_by.is_empty() {
+            "nobody".to_string()
+        } else {
+            input
+                .signed_by
+                .iter()
+                .map(|fingerprint| fingerprint.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        col = col.push(
+            Text::new(format!(
+                "  {} ({}) {} BTC, sequence {:#x}, signed by {}",
+                input.previous_output,
+                input.kind.unwrap_or("not of the wallet"),
+                input
+                    .amount
+                    .map(|amount| amount.as_btc().to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                input.sequence,
+                signed_by,
+            ))
+            .size(15),
+        );
+    }
+
+    col = col.push(Text::new(format!("{} outputs:", inspection.outputs.len())));
+    for output in &inspection.outputs {
+        col = col.push(
+            Text::new(format!(
+                "  {} ({}) {} BTC",
+                output.address,
+                output.kind.unwrap_or("external recipient"),
+                output.amount.as_btc(),
+            ))
+            .size(15),
+        );
+    }
+
+    // The witnesses are not known before the signature, the feerate is
+    // computed over the unsigned transaction and is then an upper bound.
+    let vbytes = (inspection.unsigned_weight as u64 + 3) / 4;
+    col.push(
+        Text::new(format!(
+            "Fee: {}, unsigned weight: {} WU, locktime: {}",
+            inspection
+                .fee
+                .map(|fee| format!(
+                    "{} BTC (at most {} sats/vbyte)",
+                    fee.as_btc(),
+                    fee.as_sat() / vbytes.max(1)
+                ))
+                .unwrap_or_else(|| "unknown".to_string()),
+            inspection.unsigned_weight,
+            inspection.locktime,
+        ))
+        .size(15),
+    )
+    .into()
+}
+
 pub fn key_view(name: &str, selected: bool) -> Element<'static, KeyMessage> {
     Container::new(Checkbox::new(selected, name, KeyMessage::Selected)).into()
 }
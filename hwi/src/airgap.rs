@@ -0,0 +1,409 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bitcoin::{
+    base64,
+    consensus::encode,
+    util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{DeviceDescriptor, DeviceKind, HWIError, Transport, HWI};
+
+/// Interval between two reads of the import directory.
+pub const AIRGAP_DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Magic bytes of a binary BIP174 PSBT.
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+/// Encoding of the exported PSBT files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PsbtFormat {
+    #[default]
+    Base64,
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AirgapConfig {
+    /// Directory the unsigned PSBTs are written to.
+    pub export_dir: PathBuf,
+    /// Directory watched for the signed PSBTs, it can be the export directory.
+    pub import_dir: PathBuf,
+    /// Encoding of the exported PSBTs, the signed ones can be in either encoding.
+    #[serde(default)]
+    pub format: PsbtFormat,
+    /// Master key fingerprint of the signing device, which cannot give it itself.
+    pub fingerprint: Option<Fingerprint>,
+    /// Seconds to wait for the signed PSBT before giving up, forever if None.
+    pub timeout: Option<u64>,
+}
+
+/// AirgapSigner reaches a device never connected to the computer: the PSBT is
+/// written to the export directory, carried to the device (SD card...) and the
+/// signed PSBT is expected in the import directory, in a file whose name starts
+/// with the txid of the transaction, like `<txid>-signed.psbt`.
+#[derive(Debug, Clone)]
+pub struct AirgapSigner {
+    config: AirgapConfig,
+    poll_interval: Duration,
+}
+
+impl AirgapSigner {
+    pub fn new(config: AirgapConfig) -> Self {
+        Self {
+            config,
+            poll_interval: AIRGAP_DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn descriptor(&self) -> DeviceDescriptor {
+        DeviceDescriptor {
+            kind: DeviceKind::Airgap,
+            fingerprint: self.config.fingerprint,
+            transport: Transport::Files(self.config.export_dir.to_string_lossy().to_string()),
+        }
+    }
+
+    /// Path of the exported PSBT, named after the txid of its transaction.
+    pub fn export_path(&self, psbt: &Psbt) -> PathBuf {
+        self.config
+            .export_dir
+            .join(format!("{}.psbt", psbt.global.unsigned_tx.txid()))
+    }
+
+    pub async fn export(&self, psbt: &Psbt) -> Result<PathBuf, AirgapError> {
+        let bytes = encode::serialize(psbt);
+        let content = match self.config.format {
+            PsbtFormat::Base64 => base64::encode(&bytes).into_bytes(),
+            PsbtFormat::Binary => bytes,
+        };
+        let path = self.export_path(psbt);
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| AirgapError::Io(format!("{}: {}", path.display(), e)))?;
+        Ok(path)
+    }
+
+    /// Exports the PSBT and waits for its signed version in the import directory.
+    /// The files already in the import directory at export time, left by a
+    /// previous signing attempt, are ignored unless their length changes.
+    /// A signed file which cannot be parsed may still be written, it is read
+    /// again until the timeout.
+    pub async fn sign(&self, psbt: &Psbt) -> Result<Psbt, AirgapError> {
+        let previous = self.signed_files(psbt).await?;
+        let exported = self.export(psbt).await?;
+        let start = Instant::now();
+        let mut parse_error = None;
+        loop {
+            let signed = self
+                .signed_files(psbt)
+                .await?
+                .into_iter()
+                .find(|file| file.0 != exported && !previous.contains(file));
+            if let Some((path, _)) = signed {
+                match read_psbt(&path).await {
+                    Ok(signed) => return merge_signatures(psbt, signed),
+                    Err(AirgapError::Format(e)) => parse_error = Some(AirgapError::Format(e)),
+                    Err(e) => return Err(e),
+                }
+            }
+
+            if let Some(timeout) = self.config.timeout {
+                if start.elapsed() >= Duration::from_secs(timeout) {
+                    return Err(parse_error.unwrap_or(AirgapError::Timeout));
+                }
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Lists the files of the import directory named after the transaction,
+    /// with their length.
+    async fn signed_files(&self, psbt: &Psbt) -> Result<Vec<(PathBuf, u64)>, AirgapError> {
+        let txid = psbt.global.unsigned_tx.txid().to_string();
+        let import_dir = &self.config.import_dir;
+        let mut entries = tokio::fs::read_dir(import_dir)
+            .await
+            .map_err(|e| AirgapError::Io(format!("{}: {}", import_dir.display(), e)))?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| AirgapError::Io(e.to_string()))?
+        {
+            if !entry
+                .file_name()
+                .to_str()
+                .map(|name| name.starts_with(&txid))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let len = entry
+                .metadata()
+                .await
+                .map_err(|e| AirgapError::Io(format!("{}: {}", entry.path().display(), e)))?
+                .len();
+            files.push((entry.path(), len));
+        }
+        Ok(files)
+    }
+}
+
+/// Reads a PSBT file either encoded in base64 or binary.
+async fn read_psbt(path: &Path) -> Result<Psbt, AirgapError> {
+    let content = tokio::fs::read(path)
+        .await
+        .map_err(|e| AirgapError::Io(format!("{}: {}", path.display(), e)))?;
+    let bytes = if content.starts_with(PSBT_MAGIC) {
+        content
+    } else {
+        let text = String::from_utf8(content)
+            .map_err(|_| AirgapError::Format(format!("{}: not a PSBT", path.display())))?;
+        base64::decode(text.trim()).map_err(|e| AirgapError::Format(e.to_string()))?
+    };
+    encode::deserialize(&bytes).map_err(|e| AirgapError::Format(e.to_string()))
+}
+
+/// Appends the signatures of the signed PSBT to the original one, the signing
+/// device may have stripped the other fields.
+fn merge_signatures(psbt: &Psbt, mut signed: Psbt) -> Result<Psbt, AirgapError> {
+    if signed.global.unsigned_tx != psbt.global.unsigned_tx {
+        return Err(AirgapError::TransactionMismatch);
+    }
+
+    let mut psbt = psbt.clone();
+    let mut has_signed = false;
+    for (input, signed_input) in psbt.inputs.iter_mut().zip(signed.inputs.iter_mut()) {
+        for (key, sig) in std::mem::take(&mut signed_input.partial_sigs) {
+            if input.partial_sigs.insert(key, sig).is_none() {
+                has_signed = true;
+            }
+        }
+    }
+
+    if !has_signed {
+        return Err(AirgapError::DeviceDidNotSign);
+    }
+
+    Ok(psbt)
+}
+
+#[derive(Debug)]
+pub enum AirgapError {
+    DeviceDidNotSign,
+    TransactionMismatch,
+    Timeout,
+    Format(String),
+    Io(String),
+}
+
+impl std::fmt::Display for AirgapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::DeviceDidNotSign => write!(f, "The imported psbt has no new signature"),
+            Self::TransactionMismatch => write!(
+                f,
+                "The imported psbt does not match the exported unsigned transaction"
+            ),
+            Self::Timeout => write!(f, "No signed psbt was imported in time"),
+            Self::Format(e) => write!(f, "Failed to parse the imported psbt: {}", e),
+            Self::Io(e) => write!(f, "Air-gapped signer error: {}", e),
+        }
+    }
+}
+
+impl From<AirgapError> for HWIError {
+    fn from(e: AirgapError) -> HWIError {
+        match e {
+            AirgapError::DeviceDidNotSign => HWIError::DeviceDidNotSign,
+            e => HWIError::Device(e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl HWI for AirgapSigner {
+    /// The device is reachable as long as the directories are, an SD card
+    /// may be unmounted.
+    async fn is_connected(&mut self) -> Result<(), HWIError> {
+        for dir in [&self.config.export_dir, &self.config.import_dir] {
+            match tokio::fs::metadata(dir).await {
+                Ok(metadata) if metadata.is_dir() => {}
+                _ => return Err(HWIError::DeviceDisconnected),
+            }
+        }
+        Ok(())
+    }
+
+    async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
+        self.sign(tx).await.map_err(|e| e.into())
+    }
+
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.config.fingerprint.ok_or(HWIError::UnimplementedMethod)
+    }
+}
+
+#[cfg(feature = "revault")]
+mod revault {
+    use super::AirgapSigner;
+    use crate::app::revault::{NoRevaultApp, RevaultHWI};
+
+    impl From<AirgapSigner> for Box<dyn RevaultHWI + Send> {
+        fn from(s: AirgapSigner) -> Box<dyn RevaultHWI + Send> {
+            Box::new(s)
+        }
+    }
+
+    impl NoRevaultApp for AirgapSigner {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{PublicKey, Transaction, TxIn};
+    use std::str::FromStr;
+
+    const PUBKEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    fn unsigned_psbt(lock_time: u32) -> Psbt {
+        Psbt::from_unsigned_tx(Transaction {
+            version: 2,
+            lock_time,
+            input: vec![TxIn::default()],
+            output: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    fn signed_psbt(psbt: &Psbt) -> Psbt {
+        let mut signed = psbt.clone();
+        signed.inputs[0]
+            .partial_sigs
+            .insert(PublicKey::from_str(PUBKEY).unwrap(), vec![1, 2, 3]);
+        signed
+    }
+
+    fn signer(name: &str, format: PsbtFormat) -> AirgapSigner {
+        let dir = std::env::temp_dir().join(format!(
+            "revault_hwi_airgap_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let export_dir = dir.join("export");
+        let import_dir = dir.join("import");
+        std::fs::create_dir_all(&export_dir).unwrap();
+        std::fs::create_dir_all(&import_dir).unwrap();
+        AirgapSigner::new(AirgapConfig {
+            export_dir,
+            import_dir,
+            format,
+            fingerprint: None,
+            timeout: Some(1),
+        })
+        .with_poll_interval(Duration::from_millis(10))
+    }
+
+    fn import(signer: &AirgapSigner, name: &str, content: &[u8]) {
+        std::fs::write(signer.config.import_dir.join(name), content).unwrap();
+    }
+
+    /// Imports the file once the signer has exported the psbt.
+    fn import_later(signer: &AirgapSigner, name: &str, content: Vec<u8>, delay: u64) {
+        let path = signer.config.import_dir.join(name);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            tokio::fs::write(path, content).await.unwrap();
+        });
+    }
+
+    #[tokio::test]
+    async fn test_airgap_sign() {
+        let psbt = unsigned_psbt(0);
+        let txid = psbt.global.unsigned_tx.txid();
+
+        let mut signer = signer("sign", PsbtFormat::Binary);
+        signer.is_connected().await.unwrap();
+        import_later(
+            &signer,
+            &format!("{}-signed.psbt", txid),
+            base64::encode(&encode::serialize(&signed_psbt(&psbt))).into_bytes(),
+            100,
+        );
+
+        let signed = signer.sign_tx(&psbt).await.unwrap();
+        assert_eq!(signed.inputs[0].partial_sigs.len(), 1);
+
+        let exported = std::fs::read(signer.export_path(&psbt)).unwrap();
+        assert_eq!(exported, encode::serialize(&psbt));
+    }
+
+    #[tokio::test]
+    async fn test_airgap_sign_partial_and_stale_files() {
+        let psbt = unsigned_psbt(0);
+        let txid = psbt.global.unsigned_tx.txid();
+        let signed = base64::encode(&encode::serialize(&signed_psbt(&psbt))).into_bytes();
+
+        // A file left by a previous attempt is ignored.
+        let signer = signer("stale", PsbtFormat::Base64);
+        import(&signer, &format!("{}-signed.psbt", txid), &signed);
+        assert!(matches!(
+            signer.sign(&psbt).await,
+            Err(AirgapError::Timeout)
+        ));
+
+        // A file still being written is read again.
+        let name = format!("{}-signed-again.psbt", txid);
+        import_later(&signer, &name, signed[..signed.len() / 2].to_vec(), 100);
+        import_later(&signer, &name, signed, 300);
+        let signed = signer.sign(&psbt).await.unwrap();
+        assert_eq!(signed.inputs[0].partial_sigs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_airgap_sign_errors() {
+        let psbt = unsigned_psbt(0);
+        let txid = psbt.global.unsigned_tx.txid();
+
+        let signer = signer("errors", PsbtFormat::Base64);
+        assert!(matches!(
+            signer.sign(&psbt).await,
+            Err(AirgapError::Timeout)
+        ));
+
+        let exported = std::fs::read_to_string(signer.export_path(&psbt)).unwrap();
+        assert_eq!(exported, base64::encode(&encode::serialize(&psbt)));
+
+        import_later(
+            &signer,
+            &format!("{}.psbt", txid),
+            encode::serialize(&psbt),
+            100,
+        );
+        assert!(matches!(
+            signer.sign(&psbt).await,
+            Err(AirgapError::DeviceDidNotSign)
+        ));
+
+        import_later(
+            &signer,
+            &format!("{}-mismatch.psbt", txid),
+            encode::serialize(&signed_psbt(&unsigned_psbt(1))),
+            100,
+        );
+        assert!(matches!(
+            signer.sign(&psbt).await,
+            Err(AirgapError::TransactionMismatch)
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bitcoin::{
+    base64,
+    consensus::encode,
+    util::{
+        bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+    Network,
+};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::process::Command;
+
+use super::{DeviceDescriptor, DeviceKind, HWIError, Transport, HWI};
+
+/// Default name of the HWI executable, looked up in the PATH.
+pub const HWI_DEFAULT_EXECUTABLE: &str = "hwi";
+
+/// Error codes returned by the HWI command-line tool.
+/// See https://github.com/bitcoin-core/HWI/blob/master/hwilib/errors.py
+const HWI_DEVICE_CONN_ERROR: i64 = -3;
+const HWI_NOT_IMPLEMENTED: i64 = -8;
+const HWI_UNAVAILABLE_ACTION: i64 = -9;
+
+/// Device as listed by the `enumerate` command.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceInfo {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub model: Option<String>,
+    pub path: String,
+    pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub needs_pin_sent: bool,
+    #[serde(default)]
+    pub needs_passphrase_sent: bool,
+    pub error: Option<String>,
+}
+
+impl DeviceInfo {
+    /// A device is ready if it is unlocked and can already give its fingerprint.
+    pub fn is_ready(&self) -> bool {
+        self.error.is_none()
+            && !self.needs_pin_sent
+            && !self.needs_passphrase_sent
+            && self.fingerprint.is_some()
+    }
+
+    pub fn kind(&self) -> DeviceKind {
+        match self.kind.as_ref() {
+            "ledger" => DeviceKind::Ledger,
+            "coldcard" => DeviceKind::Coldcard,
+            "trezor" => DeviceKind::Trezor,
+            _ => DeviceKind::Other(self.kind.clone()),
+        }
+    }
+}
+
+/// HWIBridge talks to Ledger, Coldcard, Trezor and any other device supported by
+/// the bitcoin-core HWI command-line tool, following its JSON protocol.
+#[derive(Debug, Clone)]
+pub struct HWIBridge {
+    executable: PathBuf,
+    network: Network,
+    device: DeviceInfo,
+}
+
+impl HWIBridge {
+    pub fn new<P: AsRef<Path>>(executable: P, network: Network, device: DeviceInfo) -> Self {
+        Self {
+            executable: executable.as_ref().to_path_buf(),
+            network,
+            device,
+        }
+    }
+
+    /// Lists every device the HWI executable can reach.
+    pub async fn enumerate<P: AsRef<Path>>(executable: P) -> Result<Vec<DeviceInfo>, BridgeError> {
+        let res = run(executable.as_ref(), &["enumerate".to_string()]).await?;
+        serde_json::from_value(res).map_err(|e| BridgeError::Device(e.to_string()))
+    }
+
+    /// Connects to the first ready device listed by the HWI executable.
+    pub async fn try_connect<P: AsRef<Path>>(
+        executable: P,
+        network: Network,
+    ) -> Result<Self, BridgeError> {
+        let device = Self::enumerate(executable.as_ref())
+            .await?
+            .into_iter()
+            .find(|d| d.is_ready())
+            .ok_or(BridgeError::DeviceNotFound)?;
+        Ok(Self::new(executable, network, device))
+    }
+
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+
+    pub fn descriptor(&self) -> DeviceDescriptor {
+        DeviceDescriptor {
+            kind: self.device.kind(),
+            fingerprint: self
+                .device
+                .fingerprint
+                .as_ref()
+                .and_then(|fg| Fingerprint::from_str(fg).ok()),
+            transport: Transport::Hwi(self.device.path.clone()),
+        }
+    }
+
+    pub async fn sign(&self, psbt: &Psbt) -> Result<Psbt, BridgeError> {
+        let mut res = self
+            .request(&["signtx", &base64::encode(&encode::serialize(psbt))])
+            .await?;
+        let signed: SignedPsbt =
+            serde_json::from_value(res.take()).map_err(|e| BridgeError::Device(e.to_string()))?;
+
+        let has_signed = psbt
+            .inputs
+            .iter()
+            .zip(signed.psbt.inputs.iter())
+            .any(|(before, after)| before.partial_sigs.len() < after.partial_sigs.len());
+
+        if !has_signed {
+            return Err(BridgeError::DeviceDidNotSign);
+        }
+
+        Ok(signed.psbt)
+    }
+
+    pub async fn xpub(&self, path: &DerivationPath) -> Result<ExtendedPubKey, BridgeError> {
+        let mut res = self.request(&["getxpub", &path.to_string()]).await?;
+        let xpub: Xpub =
+            serde_json::from_value(res.take()).map_err(|e| BridgeError::Device(e.to_string()))?;
+        ExtendedPubKey::from_str(&xpub.xpub).map_err(|e| BridgeError::Device(e.to_string()))
+    }
+
+    async fn request(&self, command: &[&str]) -> Result<Value, BridgeError> {
+        let mut args = vec![
+            "--device-type".to_string(),
+            self.device.kind.clone(),
+            "--device-path".to_string(),
+            self.device.path.clone(),
+            "--chain".to_string(),
+            chain(self.network).to_string(),
+        ];
+        args.extend(command.iter().map(|arg| arg.to_string()));
+        run(&self.executable, &args).await
+    }
+}
+
+/// Runs the HWI executable with the given arguments and parses its JSON output.
+async fn run(executable: &Path, args: &[String]) -> Result<Value, BridgeError> {
+    let output = Command::new(executable)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| BridgeError::Executable(e.to_string()))?;
+
+    let res: Value = serde_json::from_slice(&output.stdout).map_err(|e| {
+        BridgeError::Device(format!(
+            "Failed to parse HWI output: {} (stderr: {})",
+            e,
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    })?;
+
+    if let Some(error) = res.get("error") {
+        let message = error.as_str().unwrap_or("unknown error").to_string();
+        return Err(match res.get("code").and_then(|c| c.as_i64()) {
+            Some(HWI_DEVICE_CONN_ERROR) => BridgeError::DeviceNotFound,
+            Some(HWI_NOT_IMPLEMENTED) | Some(HWI_UNAVAILABLE_ACTION) => {
+                BridgeError::UnimplementedMethod
+            }
+            _ => BridgeError::Device(message),
+        });
+    }
+
+    Ok(res)
+}
+
+fn chain(network: Network) -> &'static str {
+    match network {
+        Network::Bitcoin => "main",
+        Network::Testnet => "test",
+        Network::Regtest => "regtest",
+        Network::Signet => "signet",
+    }
+}
+
+#[derive(Deserialize)]
+struct SignedPsbt {
+    #[serde(deserialize_with = "deserialize_psbt")]
+    psbt: Psbt,
+}
+
+#[derive(Deserialize)]
+struct Xpub {
+    xpub: String,
+}
+
+fn deserialize_psbt<'de, D>(deserializer: D) -> Result<Psbt, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    let bytes: Vec<u8> = base64::decode(&s).map_err(serde::de::Error::custom)?;
+    encode::deserialize(&bytes).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug)]
+pub enum BridgeError {
+    UnimplementedMethod,
+    DeviceNotFound,
+    DeviceDidNotSign,
+    Executable(String),
+    Device(String),
+}
+
+impl std::fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::UnimplementedMethod => write!(f, "Unimplemented method for HWI device"),
+            Self::DeviceNotFound => write!(f, "HWI device not found"),
+            Self::DeviceDidNotSign => write!(f, "HWI device did not sign the psbt"),
+            Self::Executable(e) => write!(f, "Failed to run HWI executable: {}", e),
+            Self::Device(e) => write!(f, "HWI error: {}", e),
+        }
+    }
+}
+
+impl From<BridgeError> for HWIError {
+    fn from(e: BridgeError) -> HWIError {
+        match e {
+            BridgeError::UnimplementedMethod => HWIError::UnimplementedMethod,
+            BridgeError::DeviceNotFound => HWIError::DeviceNotFound,
+            BridgeError::DeviceDidNotSign => HWIError::DeviceDidNotSign,
+            BridgeError::Executable(e) => HWIError::Device(e),
+            BridgeError::Device(e) => HWIError::Device(e),
+        }
+    }
+}
+
+#[async_trait]
+impl HWI for HWIBridge {
+    async fn is_connected(&mut self) -> Result<(), HWIError> {
+        let devices = Self::enumerate(&self.executable)
+            .await
+            .map_err(|_| HWIError::DeviceDisconnected)?;
+        if devices
+            .iter()
+            .any(|d| d.is_ready() && d.fingerprint == self.device.fingerprint)
+        {
+            Ok(())
+        } else {
+            Err(HWIError::DeviceDisconnected)
+        }
+    }
+
+    async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
+        self.sign(tx).await.map_err(|e| e.into())
+    }
+
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.descriptor()
+            .fingerprint
+            .ok_or_else(|| HWIError::Device("HWI device did not give its fingerprint".to_string()))
+    }
+
+    async fn get_xpub(&mut self, path: &DerivationPath) -> Result<ExtendedPubKey, HWIError> {
+        self.xpub(path).await.map_err(|e| e.into())
+    }
+}
+
+#[cfg(feature = "revault")]
+mod revault {
+    use super::HWIBridge;
+    use crate::app::revault::{NoRevaultApp, RevaultHWI};
+
+    impl From<HWIBridge> for Box<dyn RevaultHWI + Send> {
+        fn from(s: HWIBridge) -> Box<dyn RevaultHWI + Send> {
+            Box::new(s)
+        }
+    }
+
+    impl NoRevaultApp for HWIBridge {}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use bitcoin::{Transaction, TxIn};
+    use std::os::unix::fs::PermissionsExt;
+
+    const XPUB: &str = "tpubD6NzVbkrYhZ4WmzFjvQrp7sDa4ECUxTi9oby8K4FZkd3XCBtEdKwUiQyYJaxiJo5y42gyDWEczrFpozEjeLxMPxjf2WtkfcbpUdfvNnozWF";
+
+    fn unsigned_psbt() -> Psbt {
+        Psbt::from_unsigned_tx(Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn::default()],
+            output: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    /// Writes a stand-in HWI executable answering with fixed JSON.
+    fn fake_hwi(name: &str, psbt: &Psbt) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "revault_hwi_bridge_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let script = format!(
+            r#"#!/bin/sh
+case "$*" in
+  *enumerate*)
+    echo '[{{"type": "trezor", "model": "trezor_t", "path": "webusb:000:1:1", "needs_pin_sent": true, "needs_passphrase_sent": false}},
+           {{"type": "coldcard", "model": "coldcard", "path": "0001:0005:00", "fingerprint": "f5acc2fd", "needs_pin_sent": false, "needs_passphrase_sent": false}}]' ;;
+  *signtx*)
+    echo '{{"psbt": "{}", "signed": false}}' ;;
+  *getxpub*)
+    echo '{{"xpub": "{}"}}' ;;
+  *)
+    echo '{{"error": "Not implemented", "code": -8}}' ;;
+esac
+"#,
+            base64::encode(&encode::serialize(psbt)),
+            XPUB
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_enumerate_and_connect() {
+        let executable = fake_hwi("connect", &unsigned_psbt());
+        let devices = HWIBridge::enumerate(&executable).await.unwrap();
+        assert_eq!(devices.len(), 2);
+        assert!(!devices[0].is_ready());
+        assert!(devices[1].is_ready());
+
+        let mut bridge = HWIBridge::try_connect(&executable, Network::Testnet)
+            .await
+            .unwrap();
+        assert_eq!(bridge.device().kind, "coldcard");
+        assert_eq!(bridge.device().fingerprint, Some("f5acc2fd".to_string()));
+        assert_eq!(
+            bridge.descriptor(),
+            DeviceDescriptor {
+                kind: DeviceKind::Coldcard,
+                fingerprint: Some(Fingerprint::from_str("f5acc2fd").unwrap()),
+                transport: Transport::Hwi("0001:0005:00".to_string()),
+            }
+        );
+        assert!(bridge.is_connected().await.is_ok());
+
+        let xpub = bridge
+            .get_xpub(&DerivationPath::from_str("m/48'/1'/0'/2'").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(xpub.to_string(), XPUB);
+        assert_eq!(
+            bridge.get_fingerprint().await.unwrap(),
+            Fingerprint::from_str("f5acc2fd").unwrap()
+        );
+        std::fs::remove_file(executable).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sign_not_signed() {
+        let psbt = unsigned_psbt();
+        let executable = fake_hwi("sign", &psbt);
+        let mut bridge = HWIBridge::try_connect(&executable, Network::Testnet)
+            .await
+            .unwrap();
+        assert!(matches!(
+            bridge.sign_tx(&psbt).await,
+            Err(HWIError::DeviceDidNotSign)
+        ));
+        std::fs::remove_file(executable).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_executable() {
+        assert!(matches!(
+            HWIBridge::try_connect("/nonexistent/hwi", Network::Testnet).await,
+            Err(BridgeError::Executable(_))
+        ));
+    }
+}
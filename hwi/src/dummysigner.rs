@@ -1,6 +1,14 @@
+use std::str::FromStr;
+
 use bitcoin::{
-    base64, blockdata::transaction::OutPoint, consensus::encode,
-    util::psbt::PartiallySignedTransaction as Psbt, Amount,
+    base64,
+    blockdata::transaction::OutPoint,
+    consensus::encode,
+    util::{
+        bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+    Amount,
 };
 
 use async_trait::async_trait;
@@ -77,6 +85,33 @@ impl DummySigner {
         Ok(())
     }
 
+    pub async fn fingerprint(&mut self) -> Result<Fingerprint, DummySignerError> {
+        let res = self.send(json!({"request": "fingerprint"})).await?;
+        if res.get("error") == Some(&json!("request unknown")) {
+            return Err(DummySignerError::UnimplementedMethod);
+        }
+
+        let res: FingerprintResponse =
+            serde_json::from_value(res).map_err(|e| DummySignerError::Device(e.to_string()))?;
+        Fingerprint::from_str(&res.fingerprint).map_err(|e| DummySignerError::Device(e.to_string()))
+    }
+
+    pub async fn xpub(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, DummySignerError> {
+        let res = self
+            .send(json!({"request": "xpub", "derivation_path": path.to_string()}))
+            .await?;
+        if res.get("error") == Some(&json!("request unknown")) {
+            return Err(DummySignerError::UnimplementedMethod);
+        }
+
+        let res: XpubResponse =
+            serde_json::from_value(res).map_err(|e| DummySignerError::Device(e.to_string()))?;
+        ExtendedPubKey::from_str(&res.xpub).map_err(|e| DummySignerError::Device(e.to_string()))
+    }
+
     pub async fn sign_revocation_txs(
         &mut self,
         emergency_tx: &Psbt,
@@ -195,6 +230,16 @@ impl DummySigner {
     }
 }
 
+#[derive(Deserialize)]
+pub struct FingerprintResponse {
+    pub fingerprint: String,
+}
+
+#[derive(Deserialize)]
+pub struct XpubResponse {
+    pub xpub: String,
+}
+
 #[derive(Deserialize)]
 pub struct RevocationTransactions {
     #[serde(deserialize_with = "deserialize_psbt_array")]
@@ -324,6 +369,12 @@ impl HWI for DummySigner {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign_spend_tx(tx).await.map_err(|e| e.into())
     }
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.fingerprint().await.map_err(|e| e.into())
+    }
+    async fn get_xpub(&mut self, path: &DerivationPath) -> Result<ExtendedPubKey, HWIError> {
+        self.xpub(path).await.map_err(|e| e.into())
+    }
 }
 
 #[cfg(feature = "revault")]
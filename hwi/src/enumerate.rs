@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use bitcoin::Network;
+
+use crate::{app::revault::RevaultHWI, DeviceDescriptor};
+
+#[cfg(any(feature = "dummysigner", feature = "specter"))]
+use crate::{DeviceKind, Transport};
+
+/// Lists every device reachable with the enabled backends. The devices are returned
+/// connected, so that the caller can keep the one it picks.
+/// The HWI executable is only queried if a path is given.
+#[allow(unused_variables)]
+pub async fn enumerate(
+    network: Network,
+    hwi_path: Option<&Path>,
+) -> Vec<(DeviceDescriptor, Box<dyn RevaultHWI + Send>)> {
+    let mut devices: Vec<(DeviceDescriptor, Box<dyn RevaultHWI + Send>)> = Vec::new();
+
+    #[cfg(feature = "dummysigner")]
+    {
+        use crate::dummysigner::{DummySigner, DUMMYSIGNER_DEFAULT_ADDRESS};
+        if let Ok(mut device) = DummySigner::try_connect(DUMMYSIGNER_DEFAULT_ADDRESS).await {
+            let fingerprint = device.fingerprint().await.ok();
+            devices.push((
+                DeviceDescriptor {
+                    kind: DeviceKind::DummySigner,
+                    fingerprint,
+                    transport: Transport::Tcp(DUMMYSIGNER_DEFAULT_ADDRESS.to_string()),
+                },
+                device.into(),
+            ));
+        }
+    }
+
+    #[cfg(feature = "specter")]
+    {
+        use crate::specter::{Specter, SPECTER_SIMULATOR_DEFAULT_ADDRESS};
+        use bitcoin::util::bip32::Fingerprint;
+        use std::str::FromStr;
+
+        if let Ok(mut device) =
+            Specter::try_connect_simulator(SPECTER_SIMULATOR_DEFAULT_ADDRESS).await
+        {
+            let fingerprint = device
+                .fingerprint()
+                .await
+                .ok()
+                .and_then(|fg| Fingerprint::from_str(&fg).ok());
+            devices.push((
+                DeviceDescriptor {
+                    kind: DeviceKind::SpecterSimulator,
+                    fingerprint,
+                    transport: Transport::Tcp(SPECTER_SIMULATOR_DEFAULT_ADDRESS.to_string()),
+                },
+                device.into(),
+            ));
+        }
+
+        if let Ok(port) = Specter::get_serial_port() {
+            if let Ok(mut device) = Specter::try_connect_serial() {
+                let fingerprint = device
+                    .fingerprint()
+                    .await
+                    .ok()
+                    .and_then(|fg| Fingerprint::from_str(&fg).ok());
+                devices.push((
+                    DeviceDescriptor {
+                        kind: DeviceKind::Specter,
+                        fingerprint,
+                        transport: Transport::Serial(port),
+                    },
+                    device.into(),
+                ));
+            }
+        }
+    }
+
+    #[cfg(feature = "bridge")]
+    {
+        use crate::bridge::HWIBridge;
+        if let Some(path) = hwi_path {
+            if let Ok(infos) = HWIBridge::enumerate(path).await {
+                for info in infos.into_iter().filter(|info| info.is_ready()) {
+                    let device = HWIBridge::new(path, network, info);
+                    devices.push((device.descriptor(), device.into()));
+                }
+            }
+        }
+    }
+
+    devices
+}
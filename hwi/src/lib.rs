@@ -1,13 +1,25 @@
-use bitcoin::util::psbt::PartiallySignedTransaction as Psbt;
+use bitcoin::util::{
+    bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+    psbt::PartiallySignedTransaction as Psbt,
+};
 
 pub mod app;
 
+#[cfg(feature = "revault")]
+pub mod enumerate;
+
 #[cfg(feature = "dummysigner")]
 pub mod dummysigner;
 
 #[cfg(feature = "specter")]
 pub mod specter;
 
+#[cfg(feature = "bridge")]
+pub mod bridge;
+
+#[cfg(feature = "airgap")]
+pub mod airgap;
+
 use async_trait::async_trait;
 use std::fmt::Debug;
 
@@ -32,6 +44,68 @@ impl std::fmt::Display for HWIError {
     }
 }
 
+/// Kind of hardware wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceKind {
+    DummySigner,
+    Specter,
+    SpecterSimulator,
+    Ledger,
+    Coldcard,
+    Trezor,
+    /// Device exchanging the PSBTs through files.
+    Airgap,
+    Other(String),
+}
+
+impl std::fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::DummySigner => write!(f, "DummySigner"),
+            Self::Specter => write!(f, "Specter"),
+            Self::SpecterSimulator => write!(f, "Specter simulator"),
+            Self::Ledger => write!(f, "Ledger"),
+            Self::Coldcard => write!(f, "Coldcard"),
+            Self::Trezor => write!(f, "Trezor"),
+            Self::Airgap => write!(f, "Air-gapped signer"),
+            Self::Other(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// How a device is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// Address of a tcp socket.
+    Tcp(String),
+    /// Name of a serial port.
+    Serial(String),
+    /// Device path given by the HWI executable.
+    Hwi(String),
+    /// Directory the PSBTs are exported to.
+    Files(String),
+}
+
+impl std::fmt::Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp {}", address),
+            Self::Serial(port) => write!(f, "serial {}", port),
+            Self::Hwi(path) => write!(f, "usb {}", path),
+            Self::Files(dir) => write!(f, "files {}", dir),
+        }
+    }
+}
+
+/// DeviceDescriptor describes a reachable device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceDescriptor {
+    pub kind: DeviceKind,
+    /// Master key fingerprint, None if the device did not give it.
+    pub fingerprint: Option<Fingerprint>,
+    pub transport: Transport,
+}
+
 /// HWI is the common Hardware Wallet Interface.
 #[async_trait]
 pub trait HWI: Debug {
@@ -39,4 +113,12 @@ pub trait HWI: Debug {
     async fn is_connected(&mut self) -> Result<(), HWIError>;
     /// Sign a partially signed bitcoin transaction (PSBT).
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError>;
+    /// Get the fingerprint of the device master key.
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        Err(HWIError::UnimplementedMethod)
+    }
+    /// Get the extended public key derived at the given path from the device master key.
+    async fn get_xpub(&mut self, _path: &DerivationPath) -> Result<ExtendedPubKey, HWIError> {
+        Err(HWIError::UnimplementedMethod)
+    }
 }
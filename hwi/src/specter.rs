@@ -1,4 +1,13 @@
-use bitcoin::{base64, consensus::encode, util::psbt::PartiallySignedTransaction as Psbt};
+use std::str::FromStr;
+
+use bitcoin::{
+    base64,
+    consensus::encode,
+    util::{
+        bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+};
 
 use serialport::{available_ports, SerialPortType};
 use tokio::io::AsyncBufReadExt;
@@ -21,6 +30,16 @@ impl<T: Unpin + AsyncWrite + AsyncRead> Specter<T> {
         self.request("\r\n\r\nfingerprint\r\n").await
     }
 
+    pub async fn xpub(&mut self, path: &DerivationPath) -> Result<ExtendedPubKey, SpecterError> {
+        let xpub = self.request(&format!("\r\n\r\nxpub {}\r\n", path)).await?;
+        ExtendedPubKey::from_str(&xpub).map_err(|e| SpecterError::Device(e.to_string()))
+    }
+
+    async fn master_fingerprint(&mut self) -> Result<Fingerprint, SpecterError> {
+        let fingerprint = self.fingerprint().await?;
+        Fingerprint::from_str(&fingerprint).map_err(|e| SpecterError::Device(e.to_string()))
+    }
+
     pub async fn sign(&mut self, psbt: &Psbt) -> Result<Psbt, SpecterError> {
         let mut new_psbt: Psbt = self
             .request(&format!(
@@ -109,6 +128,12 @@ impl HWI for Specter<TcpStream> {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign(tx).await.map_err(|e| e.into())
     }
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.master_fingerprint().await.map_err(|e| e.into())
+    }
+    async fn get_xpub(&mut self, path: &DerivationPath) -> Result<ExtendedPubKey, HWIError> {
+        self.xpub(path).await.map_err(|e| e.into())
+    }
 }
 
 const SPECTER_VID: u16 = 61525;
@@ -155,6 +180,12 @@ impl HWI for Specter<SerialStream> {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign(tx).await.map_err(|e| e.into())
     }
+    async fn get_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.master_fingerprint().await.map_err(|e| e.into())
+    }
+    async fn get_xpub(&mut self, path: &DerivationPath) -> Result<ExtendedPubKey, HWIError> {
+        self.xpub(path).await.map_err(|e| e.into())
+    }
 }
 
 #[derive(Debug)]
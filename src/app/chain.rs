@@ -0,0 +1,74 @@
+//! Turns the blockheight of the wallet into the number of confirmations of
+//! the transactions and the countdown of the unvault timelock.
+
+use std::time::Duration;
+
+/// Expected time between two blocks.
+pub const BLOCK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Number of confirmations after which a transaction is considered settled.
+pub const SETTLED_CONFIRMATIONS: u32 = 6;
+
+/// Returns the number of confirmations of a transaction mined at the given
+/// height, 0 if the transaction is not mined.
+pub fn confirmations(tx_blockheight: Option<u32>, blockheight: i32) -> u32 {
+    match tx_blockheight {
+        Some(height) if blockheight >= 0 && height <= blockheight as u32 => {
+            blockheight as u32 - height + 1
+        }
+        _ => 0,
+    }
+}
+
+/// Returns the number of blocks left before the unvault output can be
+/// spent, None if the unvault transaction is not confirmed.
+pub fn blocks_before_timelock(
+    unvault_blockheight: Option<u32>,
+    csv: u32,
+    blockheight: i32,
+) -> Option<u32> {
+    unvault_blockheight.map(|height| (height + csv).saturating_sub(blockheight.max(0) as u32))
+}
+
+/// Returns an estimate of the time needed to mine the given number of blocks.
+pub fn estimated_time(blocks: u32) -> String {
+    let minutes = blocks as u64 * BLOCK_INTERVAL.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("~{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("~{}h {}min", hours, minutes)
+    } else {
+        format!("~{}min", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmations() {
+        assert_eq!(confirmations(None, 100), 0);
+        assert_eq!(confirmations(Some(100), 100), 1);
+        assert_eq!(confirmations(Some(95), 100), 6);
+        // The wallet blockheight is not updated yet.
+        assert_eq!(confirmations(Some(101), 100), 0);
+    }
+
+    #[test]
+    fn test_blocks_before_timelock() {
+        assert_eq!(blocks_before_timelock(None, 6, 100), None);
+        assert_eq!(blocks_before_timelock(Some(100), 6, 100), Some(6));
+        assert_eq!(blocks_before_timelock(Some(100), 6, 104), Some(2));
+        assert_eq!(blocks_before_timelock(Some(100), 6, 120), Some(0));
+    }
+
+    #[test]
+    fn test_estimated_time() {
+        assert_eq!(estimated_time(0), "~0min");
+        assert_eq!(estimated_time(4), "~40min");
+        assert_eq!(estimated_time(15), "~2h 30min");
+        assert_eq!(estimated_time(144), "~1d 0h");
+    }
+}
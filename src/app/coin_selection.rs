@@ -0,0 +1,269 @@
+//! Automatic selection of the active vaults spent by a manager spend
+//! transaction. The selection must cover the amount of the recipients and
+//! the fee, which grows with the number of selected vaults.
+
+/// Above this number of explored combinations, the least change search
+/// keeps the best selection found so far.
+const MAX_TRIES: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    FewestInputs,
+    OldestFirst,
+    LeastChange,
+}
+
+impl SelectionStrategy {
+    pub const ALL: [SelectionStrategy; 3] = [
+        SelectionStrategy::FewestInputs,
+        SelectionStrategy::OldestFirst,
+        SelectionStrategy::LeastChange,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::FewestInputs => {
+                "The biggest vaults are spent first, the transaction is the smallest and pays the least fees"
+            }
+            Self::OldestFirst => "The vaults deposited first are spent first",
+            Self::LeastChange => {
+                "The vaults whose total is the closest to the amount to send are spent, \
+                less funds go back to a new deposit"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::FewestInputs => write!(f, "Fewest inputs"),
+            Self::OldestFirst => write!(f, "Oldest first"),
+            Self::LeastChange => write!(f, "Least change"),
+        }
+    }
+}
+
+/// Candidate is a vault which can be spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    /// Amount of the unvault output in satoshis.
+    pub amount: u64,
+    /// Height of the deposit, None if it is not known.
+    pub blockheight: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// Indexes of the selected candidates.
+    pub indexes: Vec<usize>,
+    pub input_amount: u64,
+    pub fee: u64,
+    pub change: u64,
+}
+
+impl Selection {
+    fn new(candidates: &[Candidate], indexes: Vec<usize>, target: u64, fee: u64) -> Self {
+        let input_amount = indexes.iter().map(|i| candidates[*i].amount).sum::<u64>();
+        Self {
+            indexes,
+            input_amount,
+            fee,
+            change: input_amount - target - fee,
+        }
+    }
+}
+
+/// Selects the candidates covering the target amount and the fee of the
+/// transaction, `fee` gives the fee for a number of inputs.
+/// Returns None if all the candidates together do not cover it.
+pub fn select(
+    strategy: SelectionStrategy,
+    candidates: &[Candidate],
+    target: u64,
+    fee: impl Fn(usize) -> u64,
+) -> Option<Selection> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    match strategy {
+        SelectionStrategy::FewestInputs => {
+            order.sort_by(|a, b| candidates[*b].amount.cmp(&candidates[*a].amount));
+            accumulate(candidates, &order, target, &fee)
+        }
+        SelectionStrategy::OldestFirst => {
+            // The vaults with an unknown height are spent last.
+            order.sort_by_key(|i| candidates[*i].blockheight.unwrap_or(u32::MAX));
+            accumulate(candidates, &order, target, &fee)
+        }
+        SelectionStrategy::LeastChange => least_change(candidates, target, &fee),
+    }
+}
+
+/// Takes the candidates in order until they cover the target and the fee.
+fn accumulate(
+    candidates: &[Candidate],
+    order: &[usize],
+    target: u64,
+    fee: &impl Fn(usize) -> u64,
+) -> Option<Selection> {
+    let mut indexes = Vec::new();
+    let mut amount = 0;
+    for i in order {
+        indexes.push(*i);
+        amount += candidates[*i].amount;
+        let fee = fee(indexes.len());
+        if amount >= target + fee {
+            return Some(Selection::new(candidates, indexes, target, fee));
+        }
+    }
+    None
+}
+
+/// Explores the combinations of candidates, from the biggest ones, and keeps
+/// the one with the least change.
+fn least_change(
+    candidates: &[Candidate],
+    target: u64,
+    fee: &impl Fn(usize) -> u64,
+) -> Option<Selection> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by(|a, b| candidates[*b].amount.cmp(&candidates[*a].amount));
+
+    let mut search = Search {
+        candidates,
+        order: &order,
+        target,
+        fee,
+        current: Vec::new(),
+        best: None,
+        tries: 0,
+    };
+    let available = order.iter().map(|i| candidates[*i].amount).sum();
+    search.explore(0, 0, available);
+    search
+        .best
+        .map(|(indexes, fee)| Selection::new(candidates, indexes, target, fee))
+}
+
+struct Search<'a, F: Fn(usize) -> u64> {
+    candidates: &'a [Candidate],
+    order: &'a [usize],
+    target: u64,
+    fee: &'a F,
+    current: Vec<usize>,
+    /// Best selection found and its fee.
+    best: Option<(Vec<usize>, u64)>,
+    tries: usize,
+}
+
+impl<'a, F: Fn(usize) -> u64> Search<'a, F> {
+    /// `amount` is the total of the current selection and `available` the
+    /// total of the candidates left from the position.
+    fn explore(&mut self, position: usize, amount: u64, available: u64) {
+        self.tries += 1;
+        if self.tries > MAX_TRIES || self.best_change() == Some(0) {
+            return;
+        }
+
+        if !self.current.is_empty() {
+            let fee = (self.fee)(self.current.len());
+            if amount >= self.target + fee {
+                let change = amount - self.target - fee;
+                if self.best_change().map(|best| change < best).unwrap_or(true) {
+                    self.best = Some((self.current.clone(), fee));
+                }
+                // Another candidate would only add change.
+                return;
+            }
+        }
+
+        if position == self.order.len()
+            || amount + available < self.target + (self.fee)(self.current.len() + 1)
+        {
+            return;
+        }
+
+        let i = self.order[position];
+        let value = self.candidates[i].amount;
+        self.current.push(i);
+        self.explore(position + 1, amount + value, available - value);
+        self.current.pop();
+        self.explore(position + 1, amount, available - value);
+    }
+
+    fn best_change(&self) -> Option<u64> {
+        self.best.as_ref().map(|(indexes, fee)| {
+            indexes
+                .iter()
+                .map(|i| self.candidates[*i].amount)
+                .sum::<u64>()
+                - self.target
+                - fee
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<Candidate> {
+        vec![
+            Candidate {
+                amount: 5_000,
+                blockheight: Some(30),
+            },
+            Candidate {
+                amount: 20_000,
+                blockheight: Some(20),
+            },
+            Candidate {
+                amount: 3_000,
+                blockheight: None,
+            },
+            Candidate {
+                amount: 8_000,
+                blockheight: Some(10),
+            },
+        ]
+    }
+
+    fn fee(inputs: usize) -> u64 {
+        100 * inputs as u64 + 100
+    }
+
+    #[test]
+    fn test_fewest_inputs() {
+        let selection =
+            select(SelectionStrategy::FewestInputs, &candidates(), 21_000, fee).unwrap();
+        assert_eq!(selection.indexes, vec![1, 3]);
+        assert_eq!(selection.input_amount, 28_000);
+        assert_eq!(selection.fee, 300);
+        assert_eq!(selection.change, 6_700);
+
+        assert!(select(SelectionStrategy::FewestInputs, &candidates(), 36_000, fee).is_none());
+    }
+
+    #[test]
+    fn test_oldest_first() {
+        let selection = select(SelectionStrategy::OldestFirst, &candidates(), 9_000, fee).unwrap();
+        assert_eq!(selection.indexes, vec![3, 1]);
+
+        let selection = select(SelectionStrategy::OldestFirst, &candidates(), 33_000, fee).unwrap();
+        assert_eq!(selection.indexes, vec![3, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_least_change() {
+        // 5_000 + 3_000 covers it with 300 of fees and no change.
+        let selection = select(SelectionStrategy::LeastChange, &candidates(), 7_700, fee).unwrap();
+        assert_eq!(selection.indexes, vec![0, 2]);
+        assert_eq!(selection.change, 0);
+
+        let selection = select(SelectionStrategy::LeastChange, &candidates(), 12_000, fee).unwrap();
+        assert_eq!(selection.input_amount, 13_000);
+        assert_eq!(selection.change, 700);
+
+        assert!(select(SelectionStrategy::LeastChange, &candidates(), 36_000, fee).is_none());
+        assert!(select(SelectionStrategy::LeastChange, &[], 1, fee).is_none());
+    }
+}
@@ -1,5 +1,12 @@
+use revault_hwi::airgap::AirgapConfig;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{
+    app::notification::NotificationKind,
+    conversion::{Denomination, FilePrice, Price, PriceSource, StaticPrice},
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -9,17 +16,83 @@ pub struct Config {
     pub log_level: Option<String>,
     /// Use iced debug feature if true.
     pub debug: Option<bool>,
+    /// Path to an HWI-compatible executable used to reach
+    /// Ledger, Coldcard and Trezor devices.
+    pub hwi_path: Option<PathBuf>,
+    /// Air-gapped signer exchanging the PSBTs through an export
+    /// and an import directory.
+    pub airgap_signer: Option<AirgapConfig>,
+    /// Directory of the last emergency kit export.
+    pub emergency_kit_path: Option<PathBuf>,
+    /// Unit used to display the amounts, can be "btc", "mbtc", "sats".
+    pub unit: Option<Denomination>,
+    /// Source of the bitcoin price used to display the fiat value of the amounts.
+    pub price_source: Option<PriceSourceConfig>,
+    /// Interval in seconds between two reads of a price file.
+    pub price_interval: Option<u64>,
+    /// Interval in seconds between two polls of the daemon for new blocks,
+    /// vault and spend transaction changes.
+    pub watcher_interval: Option<u64>,
+    /// Events notified to the user, the critical vault movements if None.
+    pub notifications: Option<NotificationsConfig>,
 }
 
 pub const DEFAULT_FILE_NAME: &str = "revault_gui.toml";
 
+/// Default interval in seconds of the daemon watcher.
+pub const DEFAULT_WATCHER_INTERVAL: u64 = 30;
+
+/// Default interval in seconds between two reads of a price file.
+pub const DEFAULT_PRICE_INTERVAL: u64 = 300;
+
 impl Config {
     pub fn new(revaultd_config_path: PathBuf) -> Self {
         Self {
             revaultd_config_path,
             log_level: None,
             debug: None,
+            hwi_path: None,
+            airgap_signer: None,
+            emergency_kit_path: None,
+            unit: None,
+            price_source: None,
+            price_interval: None,
+            watcher_interval: None,
+            notifications: None,
+        }
+    }
+
+    /// Overrides the hwi path of the configuration if one is given.
+    pub fn with_hwi_path(mut self, hwi_path: Option<PathBuf>) -> Self {
+        if hwi_path.is_some() {
+            self.hwi_path = hwi_path;
         }
+        self
+    }
+
+    pub fn watcher_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.watcher_interval
+                .filter(|secs| *secs > 0)
+                .unwrap_or(DEFAULT_WATCHER_INTERVAL),
+        )
+    }
+
+    /// Returns the interval between two reads of the price source, None if
+    /// the price is fixed and never needs to be read again.
+    pub fn price_interval(&self) -> Option<Duration> {
+        match self.price_source {
+            Some(PriceSourceConfig::File { .. }) => Some(Duration::from_secs(
+                self.price_interval
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(DEFAULT_PRICE_INTERVAL),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn notifications(&self) -> NotificationsConfig {
+        self.notifications.clone().unwrap_or_default()
     }
 
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
@@ -36,6 +109,12 @@ impl Config {
         Ok(config)
     }
 
+    pub fn to_file(&self, path: &Path) -> Result<(), ConfigError> {
+        let content = toml::to_string(self).map_err(|e| ConfigError::Unexpected(e.to_string()))?;
+        std::fs::write(path, content.as_bytes())
+            .map_err(|e| ConfigError::Unexpected(format!("Writing configuration file: {}", e)))
+    }
+
     pub fn default_path() -> Result<PathBuf, ConfigError> {
         let mut datadir = default_datadir().map_err(|_| {
             ConfigError::Unexpected("Could not locate the default datadir directory.".to_owned())
@@ -53,6 +132,41 @@ impl Config {
     }
 }
 
+/// Where the bitcoin price comes from, either a fixed price or a toml file
+/// with the `currency` and `rate` fields.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PriceSourceConfig {
+    Static(Price),
+    File { path: PathBuf },
+}
+
+impl PriceSourceConfig {
+    pub fn source(&self) -> Box<dyn PriceSource + Send + Sync> {
+        match self {
+            Self::Static(price) => Box::new(StaticPrice(price.clone())),
+            Self::File { path } => Box::new(FilePrice::new(path)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NotificationsConfig {
+    /// Raise desktop notifications, the events are always kept
+    /// in the notification center of the application.
+    pub desktop: bool,
+    pub kinds: Vec<NotificationKind>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            kinds: NotificationKind::CRITICAL.to_vec(),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum ConfigError {
     NotFound,
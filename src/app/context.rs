@@ -1,37 +1,54 @@
 use std::fs::OpenOptions;
 use std::future::Future;
 use std::io::Write;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use bitcoin::util::psbt::PartiallySignedTransaction as Psbt;
+use bitcoin::util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt};
 
 use revaultd::config::Config as DaemonConfig;
 use revaultd::revault_tx::miniscript::DescriptorPublicKey;
 
-use revault_hwi::{app::revault::RevaultHWI, HWIError};
+use revault_hwi::{app::revault::RevaultHWI, DeviceDescriptor};
 
 use crate::{
-    app::{config, error::Error, menu::Menu},
+    app::{
+        chain, config,
+        error::Error,
+        inspector::PsbtInspection,
+        label::LabelStore,
+        menu::Menu,
+        notification::{notifications, now, DesktopNotifier, NotificationCenter, NotificationSink},
+        verify::TransactionRebuilder,
+        watcher::WatcherEvent,
+    },
     conversion::Converter,
     daemon::Daemon,
     revault::Role,
 };
 
-pub type HardwareWallet =
-    Box<dyn Future<Output = Result<Box<dyn RevaultHWI + Send>, HWIError>> + Send + Sync>;
+/// HardwareWallets resolves to every reachable device, connected.
+pub type HardwareWallets =
+    Box<dyn Future<Output = Vec<(DeviceDescriptor, Box<dyn RevaultHWI + Send>)>> + Send>;
 
 /// Context is an object passing general information
 /// and service clients through the application components.
 pub struct Context {
     pub config: ConfigContext,
+    /// Path of the GUI configuration file, the GUI settings are only
+    /// kept in memory if None.
+    pub gui_config_path: Option<PathBuf>,
     pub blockheight: i32,
     pub revaultd: Arc<dyn Daemon + Sync + Send>,
     pub converter: Converter,
     pub menu: Menu,
     pub role: Role,
     pub managers_threshold: usize,
-    pub hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
+    pub hardware_wallets: Box<dyn Fn() -> Pin<HardwareWallets> + Send + Sync>,
+    pub notifications: NotificationCenter,
+    pub notification_sink: Box<dyn NotificationSink>,
+    pub labels: LabelStore,
 }
 
 impl Context {
@@ -41,17 +58,21 @@ impl Context {
         converter: Converter,
         role: Role,
         menu: Menu,
-        hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
+        hardware_wallets: Box<dyn Fn() -> Pin<HardwareWallets> + Send + Sync>,
     ) -> Self {
         Self {
             config,
+            gui_config_path: None,
             blockheight: 0,
             revaultd,
             converter,
             role,
             menu,
             managers_threshold: 0,
-            hardware_wallet,
+            hardware_wallets,
+            notifications: NotificationCenter::default(),
+            notification_sink: Box::new(DesktopNotifier),
+            labels: LabelStore::default(),
         }
     }
 
@@ -64,6 +85,15 @@ impl Context {
         self.config.daemon.bitcoind_config.network
     }
 
+    /// Address of the Emergency Deep Vault, only known by the stakeholders.
+    pub fn emergency_address(&self) -> Option<bitcoin::Address> {
+        self.config
+            .daemon
+            .stakeholder_config
+            .as_ref()
+            .map(|cfg| cfg.emergency_address.address().clone())
+    }
+
     pub fn stakeholders_xpubs(&self) -> Vec<DescriptorPublicKey> {
         self.config.daemon.scripts_config.deposit_descriptor.xpubs()
     }
@@ -85,27 +115,46 @@ impl Context {
             .collect()
     }
 
-    pub fn user_signed(&self, psbt: &Psbt) -> bool {
-        let man_fp = &self
-            .config
-            .daemon
-            .manager_config
-            .as_ref()
-            .map(|key| key.xpub.fingerprint());
-        let stk_fp = &self
-            .config
+    /// Returns the relative timelock of the unvault output in blocks.
+    pub fn unvault_csv(&self) -> u32 {
+        self.config
             .daemon
-            .stakeholder_config
-            .as_ref()
-            .map(|key| key.xpub.fingerprint());
+            .scripts_config
+            .unvault_descriptor
+            .csv_value()
+    }
+
+    /// Returns the number of confirmations of a transaction mined at the given height.
+    pub fn confirmations(&self, tx_blockheight: Option<u32>) -> u32 {
+        chain::confirmations(tx_blockheight, self.blockheight)
+    }
+
+    /// Returns the number of blocks left before a vault unvaulted at the given
+    /// height can be spent, None if the unvault transaction is not confirmed.
+    pub fn blocks_before_timelock(&self, unvault_blockheight: Option<u32>) -> Option<u32> {
+        chain::blocks_before_timelock(unvault_blockheight, self.unvault_csv(), self.blockheight)
+    }
+
+    /// Returns the fingerprints of the user manager and stakeholder xpubs.
+    pub fn user_fingerprints(&self) -> Vec<Fingerprint> {
+        let mut fingerprints = Vec::new();
+        if let Some(key) = &self.config.daemon.manager_config {
+            fingerprints.push(key.xpub.fingerprint());
+        }
+        if let Some(key) = &self.config.daemon.stakeholder_config {
+            fingerprints.push(key.xpub.fingerprint());
+        }
+        fingerprints
+    }
+
+    pub fn user_signed(&self, psbt: &Psbt) -> bool {
+        let fingerprints = self.user_fingerprints();
         if let Some(input) = psbt.inputs.first() {
             input.partial_sigs.keys().any(|key| {
                 input
                     .bip32_derivation
                     .get(key)
-                    .map(|(fingerprint, _)| {
-                        Some(*fingerprint) == *man_fp || Some(*fingerprint) == *stk_fp
-                    })
+                    .map(|(fingerprint, _)| fingerprints.contains(fingerprint))
                     .unwrap_or(false)
             })
         } else {
@@ -113,6 +162,14 @@ impl Context {
         }
     }
 
+    /// Decodes the psbt with the descriptors of the configuration.
+    pub fn inspect(&self, psbt: &Psbt) -> PsbtInspection {
+        PsbtInspection::new(
+            psbt,
+            &TransactionRebuilder::from_config(&self.config.daemon),
+        )
+    }
+
     pub fn load_daemon_config(&mut self, cfg: DaemonConfig) -> Result<(), Error> {
         loop {
             if let Some(daemon) = Arc::get_mut(&mut self.revaultd) {
@@ -140,6 +197,39 @@ impl Context {
     }
 }
 
+impl Context {
+    /// Adds the notifications of the events to the notification center
+    /// and raises them on the desktop if enabled.
+    pub fn notify(&mut self, events: &[WatcherEvent]) {
+        let config = self.config.gui.notifications();
+        for notification in notifications(events, &config.kinds, now()) {
+            if config.desktop {
+                self.notification_sink.notify(&notification);
+            }
+            self.notifications.push(notification);
+        }
+        if self.menu == Menu::Notifications {
+            self.notifications.mark_all_read();
+        }
+    }
+
+    pub fn load_gui_config(&mut self, cfg: config::Config) -> Result<(), Error> {
+        if let Some(path) = &self.gui_config_path {
+            cfg.to_file(path)
+                .map_err(|e| Error::Config(e.to_string()))?;
+        }
+
+        self.converter.unit.denomination = cfg.unit.unwrap_or_default();
+        if cfg.price_source != self.config.gui.price_source {
+            self.converter.price = None;
+            let source = cfg.price_source.as_ref().map(|s| s.source());
+            self.converter.set_price_source(source);
+        }
+        self.config.gui = cfg;
+        Ok(())
+    }
+}
+
 pub struct ConfigContext {
     pub daemon: DaemonConfig,
     pub gui: config::Config,
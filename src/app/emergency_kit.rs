@@ -0,0 +1,392 @@
+//! The emergency kit gathers the fully signed emergency and unvault-emergency
+//! transactions of the vaults, so that the stakeholders can broadcast them with
+//! any bitcoin node if the daemon machine is lost.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use bitcoin::{
+    consensus::encode, secp256k1::Secp256k1, Address, Network, OutPoint, Transaction, Txid,
+};
+use serde::{Deserialize, Serialize};
+
+use revaultd::revault_tx::transactions::RevaultTransaction;
+
+use crate::daemon::model::{outpoint, Vault, VaultPresignedTransactions, VaultStatus};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Vaults whose funds can still be sent to the Emergency Deep Vault with
+/// their presigned transactions.
+pub const KIT_VAULT_STATUSES: [VaultStatus; 5] = [
+    VaultStatus::Secured,
+    VaultStatus::Activating,
+    VaultStatus::Active,
+    VaultStatus::Unvaulting,
+    VaultStatus::Unvaulted,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KitTransactionKind {
+    /// Spends the deposit output of the vault.
+    Emergency,
+    /// Spends the unvault output of the vault.
+    UnvaultEmergency,
+}
+
+impl std::fmt::Display for KitTransactionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Emergency => write!(f, "emergency"),
+            Self::UnvaultEmergency => write!(f, "unvault_emergency"),
+        }
+    }
+}
+
+/// KitTransaction is an entry of the manifest, the raw transaction is
+/// written in hex to the file of the entry.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KitTransaction {
+    pub kind: KitTransactionKind,
+    pub vault: OutPoint,
+    /// Amount of the vault in satoshis.
+    pub amount: u64,
+    pub emergency_address: String,
+    pub txid: Txid,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KitManifest {
+    /// Timestamp of the export.
+    pub created_at: u64,
+    pub network: Network,
+    pub transactions: Vec<KitTransaction>,
+}
+
+impl KitManifest {
+    pub fn vaults(&self) -> HashSet<OutPoint> {
+        self.transactions.iter().map(|tx| tx.vault).collect()
+    }
+}
+
+/// Collects the final emergency and unvault-emergency transactions of the vaults.
+pub fn kit_transactions(
+    network: Network,
+    emergency_address: &Address,
+    vaults: &[(Vault, VaultPresignedTransactions)],
+) -> Result<Vec<(KitTransaction, Transaction)>, String> {
+    let mut txs = Vec::new();
+    for (vault, presigned) in vaults {
+        let emergency = presigned
+            .emergency
+            .as_ref()
+            .ok_or_else(|| format!("Vault {} has no emergency transaction", outpoint(vault)))?;
+        txs.push(kit_transaction(
+            network,
+            emergency_address,
+            vault,
+            KitTransactionKind::Emergency,
+            final_transaction(emergency)?,
+        )?);
+
+        let unvault_emergency = presigned.unvault_emergency.as_ref().ok_or_else(|| {
+            format!(
+                "Vault {} has no unvault emergency transaction",
+                outpoint(vault)
+            )
+        })?;
+        txs.push(kit_transaction(
+            network,
+            emergency_address,
+            vault,
+            KitTransactionKind::UnvaultEmergency,
+            final_transaction(unvault_emergency)?,
+        )?);
+    }
+    Ok(txs)
+}
+
+/// Kind of the transaction sending the funds of the vault to the Emergency
+/// Deep Vault: the unvault-emergency transaction once the unvault output
+/// exists, None if the funds cannot be sent anymore.
+pub fn emergency_kind(status: &VaultStatus) -> Option<KitTransactionKind> {
+    match status {
+        VaultStatus::Unvaulting | VaultStatus::Unvaulted => {
+            Some(KitTransactionKind::UnvaultEmergency)
+        }
+        status if KIT_VAULT_STATUSES.contains(status) => Some(KitTransactionKind::Emergency),
+        _ => None,
+    }
+}
+
+/// Returns the final transaction sending the funds of the vault to the
+/// Emergency Deep Vault, according to its status.
+pub fn vault_emergency_transaction(
+    network: Network,
+    emergency_address: &Address,
+    vault: &Vault,
+    presigned: &VaultPresignedTransactions,
+) -> Result<(KitTransaction, Transaction), String> {
+    let kind = emergency_kind(&vault.status).ok_or_else(|| {
+        format!(
+            "Vault {} is {}, its funds cannot be sent to the Emergency Deep Vault",
+            outpoint(vault),
+            vault.status
+        )
+    })?;
+    let tx = match kind {
+        KitTransactionKind::Emergency => presigned
+            .emergency
+            .as_ref()
+            .ok_or_else(|| format!("Vault {} has no emergency transaction", outpoint(vault)))
+            .and_then(final_transaction)?,
+        KitTransactionKind::UnvaultEmergency => presigned
+            .unvault_emergency
+            .as_ref()
+            .ok_or_else(|| {
+                format!(
+                    "Vault {} has no unvault emergency transaction",
+                    outpoint(vault)
+                )
+            })
+            .and_then(final_transaction)?,
+    };
+    kit_transaction(network, emergency_address, vault, kind, tx)
+}
+
+/// The revocation transactions have a single output, paying to the Emergency
+/// Deep Vault: a transaction paying elsewhere was tampered with and is refused.
+fn kit_transaction(
+    network: Network,
+    emergency_address: &Address,
+    vault: &Vault,
+    kind: KitTransactionKind,
+    tx: Transaction,
+) -> Result<(KitTransaction, Transaction), String> {
+    let txid = tx.txid();
+    let output = match tx.output.as_slice() {
+        [output] => output,
+        _ => {
+            return Err(format!(
+                "Transaction {} does not have a single output",
+                txid
+            ))
+        }
+    };
+    if output.script_pubkey != emergency_address.script_pubkey() {
+        return Err(format!(
+            "Transaction {} of vault {} pays to {} instead of the emergency address {}",
+            txid,
+            outpoint(vault),
+            Address::from_script(&output.script_pubkey, network)
+                .map(|address| address.to_string())
+                .unwrap_or_else(|| output.script_pubkey.to_string()),
+            emergency_address
+        ));
+    }
+    let emergency_address = emergency_address.to_string();
+    Ok((
+        KitTransaction {
+            kind,
+            vault: outpoint(vault),
+            amount: vault.amount.as_sat(),
+            emergency_address,
+            txid,
+            file: format!("{}_{}.hex", kind, txid),
+        },
+        tx,
+    ))
+}
+
+/// The daemon keeps the revocation transactions once all the stakeholders
+/// signed them, they are finalized here if they are not already.
+fn final_transaction<T: RevaultTransaction + Clone>(tx: &T) -> Result<Transaction, String> {
+    let mut tx = tx.clone();
+    if !tx.is_finalized() {
+        tx.finalize(&Secp256k1::verification_only())
+            .map_err(|e| format!("Transaction is not fully signed: {}", e))?;
+    }
+    Ok(tx.into_psbt().extract_tx())
+}
+
+/// Writes the raw transactions and the manifest to the directory.
+pub fn write_kit(
+    dir: &Path,
+    network: Network,
+    created_at: u64,
+    txs: Vec<(KitTransaction, Transaction)>,
+) -> Result<KitManifest, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.to_string_lossy(), e))?;
+
+    let mut transactions = Vec::new();
+    for (entry, tx) in txs {
+        let path = dir.join(&entry.file);
+        std::fs::write(&path, encode::serialize_hex(&tx).as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.to_string_lossy(), e))?;
+        transactions.push(entry);
+    }
+
+    let manifest = KitManifest {
+        created_at,
+        network,
+        transactions,
+    };
+    let content = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    let path = dir.join(MANIFEST_FILE_NAME);
+    std::fs::write(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.to_string_lossy(), e))?;
+    Ok(manifest)
+}
+
+pub fn read_manifest(dir: &Path) -> Option<KitManifest> {
+    let content = std::fs::read(dir.join(MANIFEST_FILE_NAME)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KitStatus {
+    /// Every vault is in the kit.
+    UpToDate,
+    /// No kit was exported or it cannot be read anymore.
+    Missing,
+    /// Number of vaults secured since the last export.
+    Outdated(usize),
+}
+
+impl KitStatus {
+    /// Compares the vaults covered by the kit exported to the directory
+    /// with the vaults the emergency can still be triggered for.
+    pub fn check(dir: Option<&Path>, vaults: &[Vault]) -> Self {
+        let vaults: Vec<OutPoint> = vaults
+            .iter()
+            .filter(|vault| KIT_VAULT_STATUSES.contains(&vault.status))
+            .map(outpoint)
+            .collect();
+        if vaults.is_empty() {
+            return Self::UpToDate;
+        }
+
+        match dir.and_then(read_manifest) {
+            None => Self::Missing,
+            Some(manifest) => {
+                let covered = manifest.vaults();
+                match vaults.iter().filter(|v| !covered.contains(v)).count() {
+                    0 => Self::UpToDate,
+                    missing => Self::Outdated(missing),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{util::bip32::ChildNumber, Amount, TxIn, TxOut};
+    use std::str::FromStr;
+
+    const ADDRESS: &str = "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej";
+    const OTHER_ADDRESS: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+    fn vault(vout: u32, status: VaultStatus) -> Vault {
+        Vault {
+            address: Address::from_str(ADDRESS).unwrap(),
+            amount: Amount::from_sat(10_000),
+            derivation_index: ChildNumber::from_normal_idx(0).unwrap(),
+            status,
+            txid: Txid::from_str(
+                "a9735f42110ce529386f612194a1e137a2a2679ac0e789ad7f470cd70c3c2c24",
+            )
+            .unwrap(),
+            vout,
+            blockheight: Some(1),
+            delegated_at: None,
+            secured_at: Some(1),
+            funded_at: Some(1),
+            moved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_emergency_kind() {
+        assert_eq!(
+            emergency_kind(&VaultStatus::Active),
+            Some(KitTransactionKind::Emergency)
+        );
+        assert_eq!(
+            emergency_kind(&VaultStatus::Secured),
+            Some(KitTransactionKind::Emergency)
+        );
+        assert_eq!(
+            emergency_kind(&VaultStatus::Unvaulted),
+            Some(KitTransactionKind::UnvaultEmergency)
+        );
+        assert_eq!(emergency_kind(&VaultStatus::Funded), None);
+        assert_eq!(emergency_kind(&VaultStatus::Spent), None);
+    }
+
+    #[test]
+    fn test_emergency_kit() {
+        let dir =
+            std::env::temp_dir().join(format!("revault_gui_emergency_kit_{}", std::process::id()));
+        let vaults = vec![
+            vault(0, VaultStatus::Active),
+            vault(1, VaultStatus::Funded),
+            vault(2, VaultStatus::Secured),
+        ];
+        assert_eq!(KitStatus::check(None, &vaults), KitStatus::Missing);
+        assert_eq!(KitStatus::check(None, &vaults[1..2]), KitStatus::UpToDate);
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: 9_000,
+                script_pubkey: Address::from_str(ADDRESS).unwrap().script_pubkey(),
+            }],
+        };
+        let emergency_address = Address::from_str(ADDRESS).unwrap();
+        let entry = kit_transaction(
+            Network::Bitcoin,
+            &emergency_address,
+            &vaults[0],
+            KitTransactionKind::Emergency,
+            tx.clone(),
+        )
+        .unwrap();
+        assert_eq!(entry.0.emergency_address, ADDRESS);
+        assert_eq!(entry.0.file, format!("emergency_{}.hex", tx.txid()));
+
+        // A transaction paying to another address is refused.
+        let mut tampered = tx.clone();
+        tampered.output[0].script_pubkey =
+            Address::from_str(OTHER_ADDRESS).unwrap().script_pubkey();
+        assert!(kit_transaction(
+            Network::Bitcoin,
+            &emergency_address,
+            &vaults[0],
+            KitTransactionKind::Emergency,
+            tampered,
+        )
+        .is_err());
+
+        let manifest = write_kit(&dir, Network::Bitcoin, 1, vec![entry]).unwrap();
+        assert_eq!(read_manifest(&dir), Some(manifest));
+        assert_eq!(
+            std::fs::read_to_string(dir.join(format!("emergency_{}.hex", tx.txid()))).unwrap(),
+            encode::serialize_hex(&tx)
+        );
+        assert_eq!(
+            KitStatus::check(Some(dir.as_path()), &vaults),
+            KitStatus::Outdated(1)
+        );
+        assert_eq!(
+            KitStatus::check(Some(dir.as_path()), &vaults[..2]),
+            KitStatus::UpToDate
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::daemon::RevaultDError;
+use crate::{app::label::LabelError, daemon::RevaultDError};
+use bitcoin::OutPoint;
 use revaultd::config::ConfigError;
 use std::convert::From;
 use std::io::ErrorKind;
@@ -9,6 +10,10 @@ pub enum Error {
     // TODO: add Clone to ConfigError
     Config(String),
     Daemon(RevaultDError),
+    Label(LabelError),
+    /// The transactions of the vault given by the daemon differ from the
+    /// ones rebuilt from the descriptors.
+    TransactionMismatch(OutPoint, Vec<String>),
     Unexpected(String),
 }
 
@@ -37,6 +42,13 @@ impl std::fmt::Display for Error {
                     write!(f, "[{:?}] {}", code, e)
                 }
             },
+            Self::Label(e) => write!(f, "{}", e),
+            Self::TransactionMismatch(outpoint, differences) => write!(
+                f,
+                "Transactions of vault {} do not match the descriptors:\n{}",
+                outpoint,
+                differences.join("\n")
+            ),
             Self::Unexpected(e) => write!(f, "Unexpected error: {}", e),
         }
     }
@@ -54,6 +66,12 @@ impl From<RevaultDError> for Error {
     }
 }
 
+impl From<LabelError> for Error {
+    fn from(error: LabelError) -> Self {
+        Error::Label(error)
+    }
+}
+
 impl From<revault_hwi::HWIError> for Error {
     fn from(error: revault_hwi::HWIError) -> Self {
         Error::Hardware(error)
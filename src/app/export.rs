@@ -0,0 +1,199 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::daemon::model::HistoryEvent;
+
+/// Number of events requested to the daemon for each page of the export.
+pub const EXPORT_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// HistoryRecord is a line of the history export.
+/// Amounts are in satoshis and the date is the UTC block time.
+#[derive(Debug, Serialize)]
+pub struct HistoryRecord {
+    pub date: String,
+    pub kind: String,
+    pub amount: Option<u64>,
+    pub fee: Option<u64>,
+    pub txid: String,
+    pub vaults: Vec<String>,
+    pub blockheight: u32,
+}
+
+impl From<&HistoryEvent> for HistoryRecord {
+    fn from(event: &HistoryEvent) -> Self {
+        Self {
+            date: NaiveDateTime::from_timestamp(event.date.into(), 0)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            kind: event.kind.to_string(),
+            amount: event.amount,
+            fee: event.miner_fee,
+            txid: event.txid.to_string(),
+            vaults: event.vaults.iter().map(|v| v.to_string()).collect(),
+            blockheight: event.blockheight,
+        }
+    }
+}
+
+const CSV_HEADERS: [&str; 7] = [
+    "date",
+    "kind",
+    "amount",
+    "fee",
+    "txid",
+    "vaults",
+    "blockheight",
+];
+
+/// Quotes the field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn history_to_csv(events: &[HistoryEvent]) -> String {
+    let mut csv = CSV_HEADERS.join(",");
+    csv.push('\n');
+    for record in events.iter().map(HistoryRecord::from) {
+        let fields = [
+            record.date,
+            record.kind,
+            record.amount.map(|a| a.to_string()).unwrap_or_default(),
+            record.fee.map(|a| a.to_string()).unwrap_or_default(),
+            record.txid,
+            record.vaults.join(" "),
+            record.blockheight.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn history_to_json(events: &[HistoryEvent]) -> Result<String, serde_json::Error> {
+    let records: Vec<HistoryRecord> = events.iter().map(HistoryRecord::from).collect();
+    serde_json::to_string_pretty(&records)
+}
+
+/// Writes the events to the file in the given format.
+pub fn write_history(
+    path: &Path,
+    format: ExportFormat,
+    events: &[HistoryEvent],
+) -> Result<(), String> {
+    let content = match format {
+        ExportFormat::Csv => history_to_csv(events),
+        ExportFormat::Json => history_to_json(events).map_err(|e| e.to_string())?,
+    };
+    std::fs::write(path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.to_string_lossy(), e))
+}
+
+/// Appends to the list the events of the page that are not already in it.
+/// The gethistory cursor is inclusive, so consecutive pages share the events
+/// of the block at the cursor. Events of different kinds may share the same
+/// transaction and vaults, they are distinct events.
+/// Returns the number of events added.
+pub fn merge_history_page(events: &mut Vec<HistoryEvent>, page: Vec<HistoryEvent>) -> usize {
+    let mut added = 0;
+    for evt in page {
+        if !events.iter().any(|e| {
+            e.kind == evt.kind
+                && e.blockheight == evt.blockheight
+                && e.txid == evt.txid
+                && e.vaults == evt.vaults
+        }) {
+            events.push(evt);
+            added += 1;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::model::HistoryEventKind;
+    use bitcoin::{OutPoint, Txid};
+    use std::str::FromStr;
+
+    fn event(date: u32, txid: &str, vout: u32) -> HistoryEvent {
+        let txid = Txid::from_str(txid).unwrap();
+        HistoryEvent {
+            kind: HistoryEventKind::Deposit,
+            date,
+            blockheight: 10,
+            amount: Some(100_000),
+            cpfp_amount: None,
+            miner_fee: None,
+            txid,
+            vaults: vec![OutPoint::new(txid, vout)],
+        }
+    }
+
+    const TXID: &str = "a9735f42110ce529386f612194a1e137a2a2679ac0e789ad7f470cd70c3c2c24";
+
+    #[test]
+    fn test_history_to_csv() {
+        let mut evt = event(0, TXID, 0);
+        evt.vaults.push(OutPoint::new(evt.txid, 1));
+        assert_eq!(
+            history_to_csv(&[evt]),
+            format!(
+                "date,kind,amount,fee,txid,vaults,blockheight\n\
+                 1970-01-01 00:00:00,{},100000,,{},{}:0 {}:1,10\n",
+                HistoryEventKind::Deposit,
+                TXID,
+                TXID,
+                TXID
+            )
+        );
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_merge_history_page() {
+        let mut events = vec![event(3, TXID, 0), event(2, TXID, 1)];
+        let page = vec![event(2, TXID, 1), event(2, TXID, 2), event(1, TXID, 3)];
+        assert_eq!(merge_history_page(&mut events, page), 2);
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[3].date, 1);
+
+        let mut cancel = event(1, TXID, 3);
+        cancel.kind = HistoryEventKind::Cancel;
+        assert_eq!(merge_history_page(&mut events, vec![cancel]), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::daemon::bitcoind::BitcoinD;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeratePreset {
+    Economy,
+    Normal,
+    Priority,
+}
+
+impl FeeratePreset {
+    pub const ALL: [FeeratePreset; 3] = [
+        FeeratePreset::Economy,
+        FeeratePreset::Normal,
+        FeeratePreset::Priority,
+    ];
+
+    /// Number of blocks the transaction is expected to be confirmed within.
+    pub fn conf_target(&self) -> u16 {
+        match self {
+            Self::Economy => 144,
+            Self::Normal => 6,
+            Self::Priority => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for FeeratePreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Economy => write!(f, "Economy"),
+            Self::Normal => write!(f, "Normal"),
+            Self::Priority => write!(f, "Priority"),
+        }
+    }
+}
+
+/// Feerates in sats/vbyte estimated by bitcoind for each preset.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeEstimates {
+    pub economy: Option<u64>,
+    pub normal: Option<u64>,
+    pub priority: Option<u64>,
+}
+
+impl FeeEstimates {
+    /// Estimates the feerate of each preset, a failed estimation is logged
+    /// and left empty so that the other presets can still be used.
+    pub fn fetch(bitcoind: &BitcoinD) -> Self {
+        let estimate = |preset: FeeratePreset| {
+            bitcoind
+                .estimate_smart_fee(preset.conf_target())
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to estimate the {} feerate: {}", preset, e);
+                    None
+                })
+        };
+        Self {
+            economy: estimate(FeeratePreset::Economy),
+            normal: estimate(FeeratePreset::Normal),
+            priority: estimate(FeeratePreset::Priority),
+        }
+    }
+
+    pub fn get(&self, preset: FeeratePreset) -> Option<u64> {
+        match preset {
+            FeeratePreset::Economy => self.economy,
+            FeeratePreset::Normal => self.normal,
+            FeeratePreset::Priority => self.priority,
+        }
+    }
+
+    /// Returns a warning if the feerate is far below the economy estimate
+    /// or far above the priority one.
+    pub fn sanity_warning(&self, feerate: u64) -> Option<String> {
+        if let Some(economy) = self.economy.or(self.normal) {
+            if feerate * 2 < economy {
+                return Some(format!(
+                    "{} sats/vbyte is far below the economy estimate of {} sats/vbyte, the transaction may never confirm",
+                    feerate, economy
+                ));
+            }
+        }
+        if let Some(priority) = self.priority.or(self.normal) {
+            if feerate > priority * 2 {
+                return Some(format!(
+                    "{} sats/vbyte is far above the priority estimate of {} sats/vbyte, the transaction will overpay",
+                    feerate, priority
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Estimated virtual size of a spend transaction.
+/// The inputs are unvault outputs spent by the managers and the cosigning
+/// servers, the outputs are assumed to be P2WSH.
+/// `unvault_keys` is the number of keys in the unvault script and
+/// `signatures` the number of signatures required by the managers path.
+pub fn spend_tx_vsize(
+    inputs: usize,
+    outputs: usize,
+    unvault_keys: usize,
+    signatures: usize,
+) -> u64 {
+    // version, locktime and inputs and outputs counts.
+    let base = 4 + 4 + 1 + 1;
+    // outpoint, empty script_sig and sequence.
+    let input = 36 + 1 + 4;
+    // value and P2WSH script_pubkey.
+    let output = 8 + 1 + 34;
+    // Each key is pushed with its own check opcode, plus the timelock and
+    // threshold opcodes.
+    let witness_script = 35 * unvault_keys + 16;
+    // items count, the empty item for the CHECKMULTISIG bug, signatures and
+    // witness script.
+    let witness = 1 + 1 + 73 * signatures + 3 + witness_script;
+
+    let weight = (base + input * inputs + output * outputs) * 4 + 2 + witness * inputs;
+    weight.div_ceil(4) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanity_warning() {
+        let estimates = FeeEstimates {
+            economy: Some(4),
+            normal: Some(10),
+            priority: Some(20),
+        };
+        assert!(estimates.sanity_warning(10).is_none());
+        assert!(estimates.sanity_warning(40).is_none());
+        assert!(estimates.sanity_warning(41).is_some());
+        assert!(estimates.sanity_warning(1).is_some());
+        assert!(FeeEstimates::default().sanity_warning(1000).is_none());
+    }
+
+    #[test]
+    fn test_spend_tx_vsize() {
+        let one_input = spend_tx_vsize(1, 2, 7, 3);
+        let two_inputs = spend_tx_vsize(2, 2, 7, 3);
+        assert!(two_inputs > one_input);
+        assert_eq!(spend_tx_vsize(1, 3, 7, 3) - one_input, 43);
+    }
+}
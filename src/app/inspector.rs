@@ -0,0 +1,249 @@
+//! Decodes a psbt into what its signature commits to: the coins spent, the
+//! destination of each output and the fee, with the outputs of the wallet
+//! recognized from the descriptors of the configuration.
+
+use bitcoin::{
+    consensus::encode::VarInt,
+    util::{
+        bip32::{ChildNumber, Fingerprint},
+        psbt::{Input, PartiallySignedTransaction as Psbt},
+    },
+    Address, Amount, OutPoint, Script, Txid,
+};
+
+use crate::app::verify::TransactionRebuilder;
+
+/// Size of a DER signature with its sighash type byte, at most.
+const MAX_SIGNATURE_SIZE: usize = 73;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    Deposit,
+    Unvault,
+    Cpfp,
+    Emergency,
+}
+
+impl std::fmt::Display for ScriptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "Vault deposit"),
+            Self::Unvault => write!(f, "Unvault"),
+            Self::Cpfp => write!(f, "CPFP"),
+            Self::Emergency => write!(f, "Emergency Deep Vault"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedInput {
+    pub previous_output: OutPoint,
+    /// None if the psbt does not give the previous output.
+    pub amount: Option<Amount>,
+    pub sequence: u32,
+    /// None if the spent output is not one of the wallet.
+    pub kind: Option<ScriptKind>,
+    /// Fingerprints of the keys with a partial signature.
+    pub signed_by: Vec<Fingerprint>,
+    pub finalized: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedOutput {
+    pub address: String,
+    pub amount: Amount,
+    /// None for an external recipient.
+    pub kind: Option<ScriptKind>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsbtInspection {
+    pub txid: Txid,
+    pub version: i32,
+    pub locktime: u32,
+    pub inputs: Vec<InspectedInput>,
+    pub outputs: Vec<InspectedOutput>,
+    /// None if an input does not give its previous output.
+    pub fee: Option<Amount>,
+    /// Weight of the transaction once every key of the inputs signed,
+    /// the real weight if the inputs are finalized.
+    pub max_weight: u64,
+}
+
+impl PsbtInspection {
+    pub fn new(psbt: &Psbt, rebuilder: &TransactionRebuilder) -> Self {
+        let tx = &psbt.global.unsigned_tx;
+        // The scripts of the wallet are derived at the indexes of the keys
+        // known by the psbt.
+        let mut indexes: Vec<ChildNumber> = psbt
+            .inputs
+            .iter()
+            .flat_map(|input| input.bip32_derivation.values())
+            .chain(
+                psbt.outputs
+                    .iter()
+                    .flat_map(|output| output.bip32_derivation.values()),
+            )
+            .filter_map(|(_, path)| path.as_ref().last().copied())
+            .collect();
+        indexes.sort();
+        indexes.dedup();
+        let scripts = rebuilder.wallet_scripts(&indexes);
+        let kind = |script: &Script| {
+            scripts
+                .iter()
+                .find(|(s, _)| s == script)
+                .map(|(_, kind)| *kind)
+        };
+
+        let inputs: Vec<InspectedInput> = tx
+            .input
+            .iter()
+            .zip(psbt.inputs.iter())
+            .map(|(txin, input)| {
+                let mut signed_by = Vec::new();
+                for key in input.partial_sigs.keys() {
+                    if let Some((fingerprint, _)) = input.bip32_derivation.get(key) {
+                        if !signed_by.contains(fingerprint) {
+                            signed_by.push(*fingerprint);
+                        }
+                    }
+                }
+                InspectedInput {
+                    previous_output: txin.previous_output,
+                    amount: input
+                        .witness_utxo
+                        .as_ref()
+                        .map(|utxo| Amount::from_sat(utxo.value)),
+                    sequence: txin.sequence,
+                    kind: input
+                        .witness_utxo
+                        .as_ref()
+                        .and_then(|utxo| kind(&utxo.script_pubkey)),
+                    signed_by,
+                    finalized: input.final_script_witness.is_some(),
+                }
+            })
+            .collect();
+
+        let outputs: Vec<InspectedOutput> = tx
+            .output
+            .iter()
+            .map(|output| InspectedOutput {
+                address: Address::from_script(&output.script_pubkey, rebuilder.network())
+                    .map(|address| address.to_string())
+                    .unwrap_or_else(|| output.script_pubkey.to_string()),
+                amount: Amount::from_sat(output.value),
+                kind: kind(&output.script_pubkey),
+            })
+            .collect();
+
+        let fee = inputs
+            .iter()
+            .map(|input| input.amount.map(|amount| amount.as_sat()))
+            .sum::<Option<u64>>()
+            .and_then(|total| total.checked_sub(tx.output.iter().map(|o| o.value).sum()))
+            .map(Amount::from_sat);
+
+        let witness_weight: usize = psbt.inputs.iter().map(max_witness_size).sum();
+        let max_weight = tx.get_weight()
+            + if witness_weight > 0 {
+                // Segwit marker and flag.
+                2 + witness_weight
+            } else {
+                0
+            };
+
+        Self {
+            txid: tx.txid(),
+            version: tx.version,
+            locktime: tx.lock_time,
+            inputs,
+            outputs,
+            fee,
+            max_weight: max_weight as u64,
+        }
+    }
+
+    /// Lowest feerate of the transaction in sats/vbyte.
+    pub fn feerate(&self) -> Option<u64> {
+        let vbytes = self.max_weight.div_ceil(4);
+        self.fee
+            .filter(|_| vbytes > 0)
+            .map(|fee| fee.as_sat() / vbytes)
+    }
+
+    /// Returns the fingerprints of the keys which signed at least one input.
+    pub fn signers(&self) -> Vec<Fingerprint> {
+        let mut signers = Vec::new();
+        for fingerprint in self.inputs.iter().flat_map(|input| &input.signed_by) {
+            if !signers.contains(fingerprint) {
+                signers.push(*fingerprint);
+            }
+        }
+        signers
+    }
+}
+
+/// Size of the witness of the input, with a signature for every key of the
+/// script if the input is not finalized yet.
+fn max_witness_size(input: &Input) -> usize {
+    if let Some(witness) = &input.final_script_witness {
+        return VarInt(witness.len() as u64).len()
+            + witness
+                .iter()
+                .map(|item| VarInt(item.len() as u64).len() + item.len())
+                .sum::<usize>();
+    }
+
+    match &input.witness_script {
+        Some(script) => {
+            let signatures = input.bip32_derivation.len();
+            // The items of the witness: the signatures, the keys of pkh
+            // fragments, the dummy and the script.
+            VarInt(signatures as u64 * 2 + 2).len()
+                + signatures * (1 + MAX_SIGNATURE_SIZE + 1 + 33)
+                + 1
+                + VarInt(script.len() as u64).len()
+                + script.len()
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{Transaction, TxIn, TxOut};
+
+    #[test]
+    fn test_max_witness_size() {
+        let mut input = Input::default();
+        assert_eq!(max_witness_size(&input), 0);
+
+        input.final_script_witness = Some(vec![vec![1; 72], vec![2; 33]]);
+        assert_eq!(max_witness_size(&input), 1 + 73 + 34);
+    }
+
+    #[test]
+    fn test_feerate() {
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn::default()],
+            output: vec![TxOut::default()],
+        };
+        let mut inspection = PsbtInspection {
+            txid: tx.txid(),
+            version: tx.version,
+            locktime: tx.lock_time,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            fee: None,
+            max_weight: 401,
+        };
+        assert_eq!(inspection.feerate(), None);
+        inspection.fee = Some(Amount::from_sat(1_010));
+        assert_eq!(inspection.feerate(), Some(10));
+    }
+}
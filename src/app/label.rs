@@ -0,0 +1,293 @@
+//! Labels given by the user to the addresses, transactions and coins of the
+//! wallet. They are kept in the GUI datadir in the BIP-329 format: one JSON
+//! object per line, with the `type`, `ref` and `label` fields, so that they
+//! can be exchanged with other wallets.
+
+use std::path::{Path, PathBuf};
+
+use bitcoin::{Address, Network, OutPoint, Txid};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_FILE_NAME: &str = "labels.jsonl";
+
+/// Number of the saved addresses suggested for an address being typed.
+pub const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelKind {
+    Tx,
+    Addr,
+    Pubkey,
+    Input,
+    Output,
+    Xpub,
+}
+
+/// Label is a line of a BIP-329 file.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Label {
+    #[serde(rename = "type")]
+    pub kind: LabelKind,
+    #[serde(rename = "ref")]
+    pub reference: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spendable: Option<bool>,
+}
+
+impl Label {
+    pub fn new(kind: LabelKind, reference: String, label: String) -> Self {
+        Self {
+            kind,
+            reference,
+            label,
+            origin: None,
+            spendable: None,
+        }
+    }
+}
+
+/// LabelStore keeps the labels in memory and writes them to its file
+/// after each change, the labels are only kept in memory if it has no file.
+#[derive(Debug, Clone, Default)]
+pub struct LabelStore {
+    path: Option<PathBuf>,
+    labels: Vec<Label>,
+}
+
+impl LabelStore {
+    /// Reads the labels of the file, the store is empty if the file
+    /// does not exist yet.
+    pub fn load(path: PathBuf) -> Result<Self, LabelError> {
+        let labels = match std::fs::read_to_string(&path) {
+            Ok(content) => parse(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(LabelError::Io(format!("{}: {}", path.display(), e))),
+        };
+        Ok(Self {
+            path: Some(path),
+            labels,
+        })
+    }
+
+    pub fn file_name(network: &Network) -> String {
+        if *network == Network::Bitcoin {
+            DEFAULT_FILE_NAME.to_string()
+        } else {
+            format!("labels_{}.jsonl", network)
+        }
+    }
+
+    pub fn get(&self, kind: LabelKind, reference: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|l| l.kind == kind && l.reference == reference)
+            .map(|l| l.label.as_str())
+    }
+
+    pub fn address(&self, address: &Address) -> Option<&str> {
+        self.get(LabelKind::Addr, &address.to_string())
+    }
+
+    pub fn outpoint(&self, outpoint: &OutPoint) -> Option<&str> {
+        self.get(LabelKind::Output, &outpoint.to_string())
+    }
+
+    pub fn txid(&self, txid: &Txid) -> Option<&str> {
+        self.get(LabelKind::Tx, &txid.to_string())
+    }
+
+    /// Returns the label of the reference or the reference itself.
+    pub fn label_or(&self, kind: LabelKind, reference: String) -> String {
+        match self.get(kind, &reference) {
+            Some(label) => label.to_string(),
+            None => reference,
+        }
+    }
+
+    /// Sets the label of the reference, an empty label removes it.
+    pub fn set(
+        &mut self,
+        kind: LabelKind,
+        reference: String,
+        label: String,
+    ) -> Result<(), LabelError> {
+        self.insert(Label::new(kind, reference, label.trim().to_string()));
+        self.save()
+    }
+
+    fn insert(&mut self, label: Label) {
+        let position = self
+            .labels
+            .iter()
+            .position(|l| l.kind == label.kind && l.reference == label.reference);
+        match (position, label.label.is_empty()) {
+            (Some(i), true) => {
+                self.labels.remove(i);
+            }
+            (Some(i), false) => self.labels[i] = label,
+            (None, true) => {}
+            (None, false) => self.labels.push(label),
+        }
+    }
+
+    /// Saved addresses whose label or address contains the search,
+    /// the search must not be empty.
+    pub fn suggest_addresses(&self, search: &str) -> Vec<(&str, &str)> {
+        let search = search.trim().to_lowercase();
+        if search.is_empty() {
+            return Vec::new();
+        }
+        self.labels
+            .iter()
+            .filter(|l| l.kind == LabelKind::Addr && l.reference.to_lowercase() != search)
+            .filter(|l| {
+                l.label.to_lowercase().contains(&search)
+                    || l.reference.to_lowercase().starts_with(&search)
+            })
+            .map(|l| (l.reference.as_str(), l.label.as_str()))
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Merges the labels of the file with the stored ones, the labels of
+    /// the file take precedence. Returns the number of imported labels.
+    pub fn import(&mut self, path: &Path) -> Result<usize, LabelError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| LabelError::Io(format!("{}: {}", path.display(), e)))?;
+        let labels = parse(&content)?;
+        let count = labels.len();
+        for label in labels {
+            self.insert(label);
+        }
+        self.save()?;
+        Ok(count)
+    }
+
+    /// Writes the labels to the file, returns the number of exported labels.
+    pub fn export(&self, path: &Path) -> Result<usize, LabelError> {
+        std::fs::write(path, to_jsonl(&self.labels)?.as_bytes())
+            .map_err(|e| LabelError::Io(format!("{}: {}", path.display(), e)))?;
+        Ok(self.labels.len())
+    }
+
+    fn save(&self) -> Result<(), LabelError> {
+        if let Some(path) = &self.path {
+            self.export(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the lines of a BIP-329 file, the empty lines are skipped.
+pub fn parse(content: &str) -> Result<Vec<Label>, LabelError> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| LabelError::Format(i + 1, e.to_string()))
+        })
+        .collect()
+}
+
+pub fn to_jsonl(labels: &[Label]) -> Result<String, LabelError> {
+    let mut content = String::new();
+    for label in labels {
+        let line =
+            serde_json::to_string(label).map_err(|e| LabelError::Format(0, e.to_string()))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelError {
+    /// Line of the file and parsing error.
+    Format(usize, String),
+    Io(String),
+}
+
+impl std::fmt::Display for LabelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Format(line, e) => write!(f, "Invalid label at line {}: {}", line, e),
+            Self::Io(e) => write!(f, "Failed to access the labels file: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const ADDRESS: &str = "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej";
+
+    #[test]
+    fn test_parse_labels() {
+        let content = r#"{"type":"tx","ref":"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd","label":"Transaction","origin":"wpkh([d34db33f/84'/0'/0'])"}
+
+{"type":"output","ref":"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:0","label":"Vault of the treasury","spendable":false}"#;
+        let labels = parse(content).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].kind, LabelKind::Tx);
+        assert_eq!(
+            labels[0].origin.as_deref(),
+            Some("wpkh([d34db33f/84'/0'/0'])")
+        );
+        assert_eq!(labels[1].kind, LabelKind::Output);
+        assert_eq!(labels[1].spendable, Some(false));
+        assert_eq!(parse(&to_jsonl(&labels).unwrap()).unwrap(), labels);
+
+        assert!(matches!(
+            parse("\n{\"type\":\"unknown\",\"ref\":\"\",\"label\":\"\"}"),
+            Err(LabelError::Format(2, _))
+        ));
+    }
+
+    #[test]
+    fn test_label_store() {
+        let dir = std::env::temp_dir().join(format!("revault_gui_labels_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LabelStore::file_name(&Network::Bitcoin));
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = LabelStore::load(path.clone()).unwrap();
+        let address = Address::from_str(ADDRESS).unwrap();
+        store
+            .set(LabelKind::Addr, ADDRESS.to_string(), " Alice ".to_string())
+            .unwrap();
+        assert_eq!(store.address(&address), Some("Alice"));
+        assert_eq!(store.suggest_addresses("ali"), vec![(ADDRESS, "Alice")]);
+        assert_eq!(store.suggest_addresses("bc1qwq"), vec![(ADDRESS, "Alice")]);
+        assert!(store.suggest_addresses(ADDRESS).is_empty());
+        assert!(store.suggest_addresses("").is_empty());
+
+        let store = LabelStore::load(path.clone()).unwrap();
+        assert_eq!(store.address(&address), Some("Alice"));
+
+        let import = dir.join("import.jsonl");
+        std::fs::write(
+            &import,
+            format!(
+                "{{\"type\":\"addr\",\"ref\":\"{}\",\"label\":\"Bob\"}}\n",
+                ADDRESS
+            ),
+        )
+        .unwrap();
+        let mut store = LabelStore::load(path.clone()).unwrap();
+        assert_eq!(store.import(&import).unwrap(), 1);
+        assert_eq!(store.address(&address), Some("Bob"));
+
+        store
+            .set(LabelKind::Addr, ADDRESS.to_string(), "".to_string())
+            .unwrap();
+        assert_eq!(store.address(&address), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    }
+}
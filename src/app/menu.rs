@@ -9,8 +9,10 @@ pub enum Menu {
     ImportSpend,
     CreateVaults,
     RevaultVaults,
+    UnvaultAlerts,
     DelegateFunds,
     Settings,
+    Notifications,
     Vaults(VaultsMenu),
 }
 
@@ -1,14 +1,30 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint};
+use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Transaction, Txid};
 use tokio::sync::Mutex;
 
-use revault_hwi::{app::revault::RevaultHWI, HWIError};
+use revault_hwi::{app::revault::RevaultHWI, DeviceDescriptor, HWIError};
 use revaultd::config::Config as DaemonConfig;
 
 use crate::{
-    app::{error::Error, menu::Menu},
+    app::{
+        coin_selection::SelectionStrategy,
+        config::Config as GUIConfig,
+        emergency_kit::KitTransaction,
+        error::Error,
+        export::ExportFormat,
+        fee::{FeeEstimates, FeeratePreset},
+        label::LabelKind,
+        menu::Menu,
+        notification::NotificationKind,
+        state::history::HistoryFilter,
+        trend::Period,
+        watcher::WatcherEvent,
+    },
+    conversion::{Denomination, Price, PriceError},
     daemon::{
+        bitcoind::MempoolEntry,
         model::{
             HistoryEvent, HistoryEventKind, ServersStatuses, SpendTx, SpendTxStatus, Vault,
             VaultPresignedTransactions, VaultStatus, VaultTransactions,
@@ -27,7 +43,6 @@ impl Into<Message> for Menu {
 #[derive(Debug, Clone)]
 pub enum Message {
     Reload,
-    Tick,
     Event(iced_native::Event),
     Clipboard(String),
     ChangeRole(Role),
@@ -43,11 +58,18 @@ pub enum Message {
     Vault(VaultMessage),
     FilterVaults(VaultFilterMessage),
     FilterTxs(&'static [SpendTxStatus]),
-    BlockHeight(Result<i32, RevaultDError>),
+    Watcher(Result<Vec<WatcherEvent>, RevaultDError>),
+    Notification(NotificationMessage),
     ServerStatus(Result<ServersStatuses, RevaultDError>),
     HistoryEvents(Result<Vec<HistoryEvent>, RevaultDError>),
+    /// Events of the history screen and whether the history has more events.
+    HistoryPage(Result<(Vec<HistoryEvent>, bool), RevaultDError>),
     HistoryEvent(HistoryEventMessage),
-    FilterHistoryEvents(Option<HistoryEventKind>),
+    FilterHistoryEvents(HistoryFilter),
+    HistoryFilter(HistoryFilterMessage),
+    ExportHistory,
+    HistoryExport(HistoryExportMessage),
+    Trend(TrendMessage),
     Menu(Menu),
     Next,
     Previous,
@@ -60,13 +82,24 @@ pub enum Message {
     SpendTx(SpendTxMessage),
     Emergency,
     EmergencyBroadcasted(Result<(), RevaultDError>),
+    EmergencyKit(EmergencyKitMessage),
+    SelectedEmergency(SelectedEmergencyMessage),
     Close,
     Revault,
     Revaulted(Result<(), RevaultDError>),
+    UnvaultAlerts(Result<Vec<(Vault, VaultTransactions, Option<SpendTx>)>, RevaultDError>),
+    CancelUnvault(OutPoint),
+    UnvaultCanceled(OutPoint, Result<(), RevaultDError>),
     Settings(usize, SettingsMessage),
     AddWatchtower,
     LoadDaemonConfig(DaemonConfig),
     DaemonConfigLoaded(Result<(), Error>),
+    DisplayUnit(Denomination),
+    LoadPrice,
+    Price(Result<Price, PriceError>),
+    LoadGuiConfig(GUIConfig),
+    GuiConfigLoaded(Result<(), Error>),
+    Label(LabelMessage),
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +122,7 @@ pub enum SpendTxMessage {
     Sign(SignMessage),
     Signed(Result<(), RevaultDError>),
     Inputs(Result<Vec<Vault>, RevaultDError>),
+    OnChainTransactions(Result<VaultTransactions, RevaultDError>),
     SpendTransactions(Result<Vec<SpendTx>, RevaultDError>),
     SelectDelete,
     UnselectDelete,
@@ -99,6 +133,18 @@ pub enum SpendTxMessage {
     Update,
     Updated(Result<(), RevaultDError>),
     WithPriority(bool),
+    SelectBumpFee,
+    UnselectBumpFee,
+    MempoolEntry(Result<MempoolEntry, RevaultDError>),
+    CpfpFeerateEdited(String),
+    CreateCpfp,
+    CpfpSign(SignMessage),
+    CpfpBroadcasted(Result<Txid, RevaultDError>),
+    FeeEstimates(Result<FeeEstimates, RevaultDError>),
+    FeeratePresetSelected(FeeratePreset),
+    SelectionStrategySelected(SelectionStrategy),
+    RecipientsPathEdited(String),
+    ImportRecipients,
 }
 
 #[derive(Debug, Clone)]
@@ -106,10 +152,78 @@ pub enum HistoryEventMessage {
     OnChainTransactions(Result<Vec<VaultTransactions>, RevaultDError>),
 }
 
+#[derive(Debug, Clone)]
+pub enum HistoryFilterMessage {
+    KindToggled(HistoryEventKind, bool),
+    FromEdited(String),
+    ToEdited(String),
+    MinAmountEdited(String),
+    MaxAmountEdited(String),
+    SearchEdited(String),
+    Apply,
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub enum TrendMessage {
+    PeriodSelected(Period),
+    /// Page of the history events, with the generation of its paging.
+    HistoryEvents(u64, Result<Vec<HistoryEvent>, RevaultDError>),
+}
+
+#[derive(Debug, Clone)]
+pub enum HistoryExportMessage {
+    FromEdited(String),
+    ToEdited(String),
+    KindToggled(HistoryEventKind, bool),
+    FormatSelected(ExportFormat),
+    PathEdited(String),
+    Start,
+    HistoryEvents(Result<Vec<HistoryEvent>, RevaultDError>),
+    Written(Result<(), Error>),
+}
+
+#[derive(Debug, Clone)]
+pub enum EmergencyKitMessage {
+    PathEdited(String),
+    Export,
+    /// Number of vaults in the kit and directory it was written to.
+    Exported(Result<(usize, PathBuf), Error>),
+}
+
+#[derive(Debug, Clone)]
+pub enum SelectedEmergencyMessage {
+    VaultToggled(OutPoint, bool),
+    /// Retrieves the emergency transactions of the selected vaults
+    /// for the confirmation.
+    Review,
+    Transactions(Result<Vec<(KitTransaction, Transaction)>, Error>),
+    Back,
+    Broadcast,
+    /// Result of each broadcast transaction.
+    Broadcasted(Result<Vec<Result<Txid, RevaultDError>>, RevaultDError>),
+}
+
 #[derive(Debug, Clone)]
 pub enum VaultMessage {
     ListOnchainTransaction,
     OnChainTransactions(Result<VaultTransactions, RevaultDError>),
+    PresignedTransactions(Result<VaultPresignedTransactions, RevaultDError>),
+    LabelEdited(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum LabelMessage {
+    /// Sets the label of the reference in the label store, an empty
+    /// label removes it.
+    Save(LabelKind, String, String),
+    PathEdited(String),
+    Import,
+    Export,
+    ImportFrom(PathBuf),
+    ExportTo(PathBuf),
+    /// Number of labels written to the store or to the export file.
+    Written(Result<usize, Error>),
 }
 
 #[derive(Debug, Clone)]
@@ -122,7 +236,8 @@ pub enum SignMessage {
     CheckConnection,
     Ping(Result<(), HWIError>),
     SelectSign,
-    Connected(Result<Arc<Mutex<Box<dyn RevaultHWI + Send>>>, HWIError>),
+    Devices(Vec<(DeviceDescriptor, Arc<Mutex<Box<dyn RevaultHWI + Send>>>)>),
+    SelectDevice(DeviceDescriptor),
     PsbtSigned(Result<Box<Psbt>, HWIError>),
 }
 
@@ -135,5 +250,17 @@ pub enum InputMessage {
 pub enum RecipientMessage {
     Delete,
     AddressEdited(String),
+    /// Saved address picked among the suggestions.
+    AddressSelected(String),
+    LabelEdited(String),
+    SaveLabel,
     AmountEdited(String),
 }
+
+#[derive(Debug, Clone)]
+pub enum NotificationMessage {
+    Dismiss(usize),
+    ClearAll,
+    DesktopToggled(bool),
+    KindToggled(NotificationKind, bool),
+}
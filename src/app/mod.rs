@@ -1,30 +1,47 @@
+pub mod chain;
+pub mod coin_selection;
 pub mod config;
 pub mod context;
+pub mod emergency_kit;
+pub mod export;
+pub mod fee;
+pub mod inspector;
+pub mod label;
 pub mod menu;
 pub mod message;
+pub mod notification;
+pub mod payouts;
 pub mod state;
+pub mod timeline;
+pub mod trend;
+pub mod verify;
+pub mod watcher;
 
 mod error;
 mod view;
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use iced::{clipboard, time, Command, Element, Subscription};
 use iced_native::{window, Event};
 
 pub use config::Config;
-pub use message::{Message, SettingsMessage};
+pub use message::{LabelMessage, Message, NotificationMessage, SettingsMessage};
 
 use menu::Menu;
 use state::{
     DepositState, EmergencyState, HistoryState, ManagerCreateSendTransactionState,
-    ManagerHomeState, ManagerImportSendTransactionState, ManagerSendState, RevaultVaultsState,
-    SettingsState, StakeholderCreateVaultsState, StakeholderDelegateVaultsState,
-    StakeholderHomeState, State, VaultsState,
+    ManagerHomeState, ManagerImportSendTransactionState, ManagerSendState, NotificationsState,
+    RevaultVaultsState, SettingsState, StakeholderCreateVaultsState,
+    StakeholderDelegateVaultsState, StakeholderHomeState, State, UnvaultAlertsState, VaultsState,
 };
+use watcher::WatcherEvent;
 
-use crate::{app::context::Context, revault::Role};
+use crate::{
+    app::{context::Context, error::Error},
+    conversion::fetch_price,
+    revault::Role,
+};
 
 pub struct App {
     should_exit: bool,
@@ -38,7 +55,9 @@ pub fn new_state(context: &Context) -> Box<dyn State> {
         (_, Menu::History) => HistoryState::new().into(),
         (_, Menu::Vaults(menu)) => VaultsState::new(menu).into(),
         (_, Menu::RevaultVaults) => RevaultVaultsState::default().into(),
+        (_, Menu::UnvaultAlerts) => UnvaultAlertsState::new().into(),
         (_, Menu::Settings) => SettingsState::new(context).into(),
+        (_, Menu::Notifications) => NotificationsState::new().into(),
         (Role::Stakeholder, Menu::Home) => StakeholderHomeState::new().into(),
         (Role::Stakeholder, Menu::CreateVaults) => StakeholderCreateVaultsState::new().into(),
         (Role::Stakeholder, Menu::DelegateFunds) => StakeholderDelegateVaultsState::new().into(),
@@ -58,22 +77,38 @@ impl App {
     pub fn new(context: Context) -> (App, Command<Message>) {
         let state = new_state(&context);
         let cmd = state.load(&context);
-        (
-            Self {
-                should_exit: false,
-                state,
-                context,
-            },
-            cmd,
-        )
+        let app = Self {
+            should_exit: false,
+            state,
+            context,
+        };
+        let price = app.load_price();
+        (app, Command::batch(vec![cmd, price]))
+    }
+
+    /// Fetches the price of the price source, if one is configured.
+    fn load_price(&self) -> Command<Message> {
+        match self.context.converter.price_source() {
+            Some(source) => Command::perform(fetch_price(source), Message::Price),
+            None => Command::none(),
+        }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             iced_native::subscription::events().map(Message::Event),
-            time::every(Duration::from_secs(30)).map(|_| Message::Tick),
+            watcher::watch(
+                self.context.revaultd.clone(),
+                self.context.config.gui.watcher_interval(),
+                self.context.config.daemon.manager_config.is_some(),
+            )
+            .map(Message::Watcher),
             self.state.subscription(),
-        ])
+        ];
+        if let Some(interval) = self.context.config.gui.price_interval() {
+            subscriptions.push(time::every(interval).map(|_| Message::LoadPrice));
+        }
+        Subscription::batch(subscriptions)
     }
 
     pub fn should_exit(&self) -> bool {
@@ -96,34 +131,80 @@ impl App {
 
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::Tick => {
-                let revaultd = self.context.revaultd.clone();
-                Command::perform(
-                    async move { revaultd.get_info().map(|res| res.blockheight) },
-                    Message::BlockHeight,
-                )
-            }
-            Message::BlockHeight(res) => {
-                if let Ok(blockheight) = res {
-                    self.context.blockheight = blockheight;
+            Message::Watcher(Ok(events)) => {
+                self.context.notify(&events);
+                for event in &events {
+                    if let WatcherEvent::NewBlocks { blockheight } = event {
+                        self.context.blockheight = *blockheight;
+                    }
+                }
+                if events.is_empty() {
+                    Command::none()
+                } else {
+                    self.state
+                        .update(&self.context, Message::Watcher(Ok(events)))
                 }
+            }
+            Message::LoadPrice => self.load_price(),
+            Message::Price(res) => {
+                self.context.converter.set_price(res);
+                Command::none()
+            }
+            Message::Watcher(Err(e)) => {
+                log::warn!("Failed to poll the daemon: {}", e);
                 Command::none()
             }
             Message::LoadDaemonConfig(cfg) => {
                 let res = self.context.load_daemon_config(cfg);
                 self.update(Message::DaemonConfigLoaded(res))
             }
+            Message::LoadGuiConfig(cfg) => {
+                let res = self.context.load_gui_config(cfg);
+                // The price source may have changed.
+                Command::batch(vec![
+                    self.load_price(),
+                    self.update(Message::GuiConfigLoaded(res)),
+                ])
+            }
+            Message::Label(LabelMessage::Save(kind, reference, label)) => {
+                let res = self
+                    .context
+                    .labels
+                    .set(kind, reference, label)
+                    .map(|_| 1)
+                    .map_err(Error::from);
+                self.update(Message::Label(LabelMessage::Written(res)))
+            }
+            Message::Label(LabelMessage::ImportFrom(path)) => {
+                let res = self.context.labels.import(&path).map_err(Error::from);
+                self.update(Message::Label(LabelMessage::Written(res)))
+            }
+            Message::Label(LabelMessage::ExportTo(path)) => {
+                let res = self.context.labels.export(&path).map_err(Error::from);
+                self.update(Message::Label(LabelMessage::Written(res)))
+            }
             Message::ChangeRole(role) => {
                 self.context.role = role;
                 self.state = new_state(&self.context);
                 self.state.load(&self.context)
             }
             Message::Menu(menu) => {
+                if menu == Menu::Notifications {
+                    self.context.notifications.mark_all_read();
+                }
                 self.context.menu = menu;
                 self.state = new_state(&self.context);
                 self.state.load(&self.context)
             }
             Message::Clipboard(text) => clipboard::write(text),
+            Message::Notification(NotificationMessage::Dismiss(i)) => {
+                self.context.notifications.dismiss(i);
+                Command::none()
+            }
+            Message::Notification(NotificationMessage::ClearAll) => {
+                self.context.notifications.clear();
+                Command::none()
+            }
             Message::Event(Event::Window(window::Event::CloseRequested)) => {
                 self.stop();
                 Command::none()
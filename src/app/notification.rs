@@ -0,0 +1,273 @@
+//! Notifications of the vault changes the user must react to. They are kept
+//! in the notification center of the application and raised on the desktop.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bitcoin::{Amount, OutPoint};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::watcher::WatcherEvent,
+    daemon::model::{HistoryEventKind, VaultStatus},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Deposit,
+    Unvaulting,
+    Unvaulted,
+    Cancel,
+    Emergency,
+    Spend,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 6] = [
+        NotificationKind::Deposit,
+        NotificationKind::Unvaulting,
+        NotificationKind::Unvaulted,
+        NotificationKind::Cancel,
+        NotificationKind::Emergency,
+        NotificationKind::Spend,
+    ];
+
+    /// Kinds notified by default: the vault movements a stakeholder
+    /// may have to revault or emergency.
+    pub const CRITICAL: [NotificationKind; 4] = [
+        NotificationKind::Unvaulting,
+        NotificationKind::Unvaulted,
+        NotificationKind::Cancel,
+        NotificationKind::Emergency,
+    ];
+
+    /// Returns the kind of the notification of a vault reaching the status.
+    /// Cancel and emergency are notified once their transaction is confirmed.
+    pub fn from_vault_status(status: &VaultStatus) -> Option<Self> {
+        match status {
+            VaultStatus::Unvaulting => Some(Self::Unvaulting),
+            VaultStatus::Unvaulted => Some(Self::Unvaulted),
+            VaultStatus::Canceled => Some(Self::Cancel),
+            VaultStatus::EmergencyVaulted | VaultStatus::UnvaultEmergencyVaulted => {
+                Some(Self::Emergency)
+            }
+            VaultStatus::Spent => Some(Self::Spend),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the history event recording the same transaction.
+    pub fn history_event_kind(&self) -> Option<HistoryEventKind> {
+        match self {
+            Self::Deposit => Some(HistoryEventKind::Deposit),
+            Self::Cancel => Some(HistoryEventKind::Cancel),
+            Self::Spend => Some(HistoryEventKind::Spend),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "Deposit"),
+            Self::Unvaulting => write!(f, "Unvaulting"),
+            Self::Unvaulted => write!(f, "Unvaulted"),
+            Self::Cancel => write!(f, "Cancel"),
+            Self::Emergency => write!(f, "Emergency"),
+            Self::Spend => write!(f, "Spend"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub outpoint: OutPoint,
+    /// Only known for the deposits.
+    pub amount: Option<Amount>,
+    /// Timestamp of the reception of the event by the GUI.
+    pub date: u64,
+    pub read: bool,
+}
+
+impl Notification {
+    pub fn title(&self) -> String {
+        match self.kind {
+            NotificationKind::Deposit => "New deposit".to_string(),
+            NotificationKind::Unvaulting => "A vault is unvaulting".to_string(),
+            NotificationKind::Unvaulted => "A vault is unvaulted".to_string(),
+            NotificationKind::Cancel => "Cancel transaction confirmed".to_string(),
+            NotificationKind::Emergency => "Emergency transaction confirmed".to_string(),
+            NotificationKind::Spend => "Spend transaction confirmed".to_string(),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        if let Some(amount) = self.amount {
+            format!("{} received on {}", amount, self.outpoint)
+        } else {
+            format!("Vault {}", self.outpoint)
+        }
+    }
+}
+
+/// Returns the notifications of the events of the given kinds.
+pub fn notifications(
+    events: &[WatcherEvent],
+    kinds: &[NotificationKind],
+    date: u64,
+) -> Vec<Notification> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            WatcherEvent::NewDeposit { outpoint, amount } => Some(Notification {
+                kind: NotificationKind::Deposit,
+                outpoint: *outpoint,
+                amount: Some(*amount),
+                date,
+                read: false,
+            }),
+            WatcherEvent::VaultStatusChanged {
+                outpoint, status, ..
+            } => NotificationKind::from_vault_status(status).map(|kind| Notification {
+                kind,
+                outpoint: *outpoint,
+                amount: None,
+                date,
+                read: false,
+            }),
+            _ => None,
+        })
+        .filter(|notification| kinds.contains(&notification.kind))
+        .collect()
+}
+
+/// NotificationSink raises the notifications outside of the application window.
+pub trait NotificationSink: Send + Sync {
+    fn notify(&self, notification: &Notification);
+}
+
+/// DesktopNotifier sends the notifications to the freedesktop notification
+/// server through D-Bus.
+#[derive(Debug, Default)]
+pub struct DesktopNotifier;
+
+impl NotificationSink for DesktopNotifier {
+    #[cfg(target_os = "linux")]
+    fn notify(&self, notification: &Notification) {
+        let summary = notification.title();
+        let body = notification.body();
+        // The D-Bus call is blocking.
+        std::thread::spawn(move || {
+            if let Err(e) = notify_rust::Notification::new()
+                .appname("Revault GUI")
+                .summary(&summary)
+                .body(&body)
+                .show()
+            {
+                log::warn!("Failed to send desktop notification: {}", e);
+            }
+        });
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn notify(&self, notification: &Notification) {
+        log::info!("{}: {}", notification.title(), notification.body());
+    }
+}
+
+/// NotificationCenter keeps the notifications received since the start
+/// of the application, the most recent first.
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    notifications: Vec<Notification>,
+}
+
+impl NotificationCenter {
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+
+    pub fn push(&mut self, notification: Notification) {
+        self.notifications.insert(0, notification);
+    }
+
+    pub fn unread(&self) -> usize {
+        self.notifications.iter().filter(|n| !n.read).count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for notification in &mut self.notifications {
+            notification.read = true;
+        }
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        if index < self.notifications.len() {
+            self.notifications.remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.notifications.clear();
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::Txid;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_notifications() {
+        let txid =
+            Txid::from_str("a9735f42110ce529386f612194a1e137a2a2679ac0e789ad7f470cd70c3c2c24")
+                .unwrap();
+        let events = vec![
+            WatcherEvent::NewBlocks { blockheight: 2 },
+            WatcherEvent::NewDeposit {
+                outpoint: OutPoint::new(txid, 0),
+                amount: Amount::from_sat(1_000),
+            },
+            WatcherEvent::VaultStatusChanged {
+                outpoint: OutPoint::new(txid, 1),
+                previous: VaultStatus::Active,
+                status: VaultStatus::Unvaulting,
+            },
+            WatcherEvent::VaultStatusChanged {
+                outpoint: OutPoint::new(txid, 2),
+                previous: VaultStatus::Secured,
+                status: VaultStatus::Active,
+            },
+        ];
+
+        let critical = notifications(&events, &NotificationKind::CRITICAL, 0);
+        assert_eq!(critical.len(), 1);
+        assert_eq!(critical[0].kind, NotificationKind::Unvaulting);
+        assert_eq!(critical[0].outpoint, OutPoint::new(txid, 1));
+
+        let all = notifications(&events, &NotificationKind::ALL, 0);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].amount, Some(Amount::from_sat(1_000)));
+
+        let mut center = NotificationCenter::default();
+        for notification in all {
+            center.push(notification);
+        }
+        assert_eq!(center.unread(), 2);
+        assert_eq!(center.notifications()[0].kind, NotificationKind::Unvaulting);
+        center.mark_all_read();
+        assert_eq!(center.unread(), 0);
+        center.dismiss(0);
+        assert_eq!(center.notifications()[0].kind, NotificationKind::Deposit);
+    }
+}
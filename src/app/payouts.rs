@@ -0,0 +1,257 @@
+//! Recipients of a spend transaction imported from a file, so that the
+//! managers paying many recipients do not have to type each of them.
+//!
+//! A CSV file has one `address,amount[,label]` line per recipient, with an
+//! optional header line. A JSON file is an array of objects with the
+//! `address`, `amount` and optional `label` fields. Amounts are in BTC.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use bitcoin::{util::address::Payload, Address, Amount, Denomination, Network};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payout {
+    /// Line, or position in the JSON array, starting at 1.
+    pub line: usize,
+    pub address: Address,
+    pub amount: Amount,
+    pub label: Option<String>,
+}
+
+/// PayoutsImport is the result of the import of a recipients file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PayoutsImport {
+    pub payouts: Vec<Payout>,
+    /// Line and error of each rejected recipient.
+    pub errors: Vec<(usize, String)>,
+}
+
+impl PayoutsImport {
+    pub fn total(&self) -> Amount {
+        self.payouts
+            .iter()
+            .fold(Amount::from_sat(0), |total, payout| total + payout.amount)
+    }
+
+    /// Keeps the payout unless its address was already imported.
+    fn push(&mut self, line: usize, payout: Result<Payout, String>) {
+        match payout {
+            Ok(mut payout) => {
+                payout.line = line;
+                if self.payouts.iter().any(|p| p.address == payout.address) {
+                    self.errors
+                        .push((line, format!("{} is a duplicate recipient", payout.address)));
+                } else {
+                    self.payouts.push(payout);
+                }
+            }
+            Err(e) => self.errors.push((line, e)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPayout {
+    address: String,
+    amount: serde_json::Value,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// Reads the recipients of the file, the file is parsed as JSON if it has
+/// the json extension or starts with an array.
+pub fn read(path: &Path, network: Network) -> Result<PayoutsImport, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if path.extension().map(|ext| ext == "json").unwrap_or(false)
+        || content.trim_start().starts_with('[')
+    {
+        parse_json(&content, network)
+    } else {
+        Ok(parse_csv(&content, network))
+    }
+}
+
+pub fn parse_csv(content: &str, network: Network) -> PayoutsImport {
+    let mut rows = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = csv_fields(line);
+        if rows.is_empty()
+            && fields
+                .first()
+                .map(|f| f.eq_ignore_ascii_case("address"))
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        rows.push((i + 1, fields));
+    }
+
+    let mut import = PayoutsImport::default();
+    for (line, fields) in rows {
+        let row = match fields.as_slice() {
+            [address, amount] => payout(address, amount, None, network),
+            [address, amount, label] => payout(address, amount, Some(label.as_str()), network),
+            _ => Err("expected the address, the amount and an optional label".to_string()),
+        };
+        import.push(line, row);
+    }
+    import
+}
+
+pub fn parse_json(content: &str, network: Network) -> Result<PayoutsImport, String> {
+    let rows: Vec<RawPayout> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid recipients file: {}", e))?;
+    let mut import = PayoutsImport::default();
+    for (i, row) in rows.into_iter().enumerate() {
+        let amount = match row.amount {
+            serde_json::Value::String(s) => s,
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => {
+                import.push(i + 1, Err("the amount must be a number".to_string()));
+                continue;
+            }
+        };
+        let payout = payout(&row.address, &amount, row.label.as_deref(), network);
+        import.push(i + 1, payout);
+    }
+    Ok(import)
+}
+
+fn payout(
+    address: &str,
+    amount: &str,
+    label: Option<&str>,
+    network: Network,
+) -> Result<Payout, String> {
+    let address = Address::from_str(address.trim())
+        .map_err(|e| format!("invalid address {}: {}", address, e))?;
+    if !valid_for_network(&address, network) {
+        return Err(format!("{} is not a {} address", address, network));
+    }
+    if address.address_type().is_none() {
+        return Err(format!("{} has an unsupported address type", address));
+    }
+
+    let amount = Amount::from_str_in(amount.trim(), Denomination::Bitcoin)
+        .map_err(|e| format!("invalid amount {}: {}", amount, e))?;
+    if amount <= address.script_pubkey().dust_value() {
+        return Err(format!("{} is below the dust value", amount));
+    }
+
+    Ok(Payout {
+        line: 0,
+        address,
+        amount,
+        label: label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty()),
+    })
+}
+
+/// The test networks share the base58 prefixes, and signet shares the
+/// bech32 prefix of testnet.
+fn valid_for_network(address: &Address, network: Network) -> bool {
+    if address.network == network {
+        return true;
+    }
+    match address.payload {
+        Payload::PubkeyHash(_) | Payload::ScriptHash(_) => {
+            address.network != Network::Bitcoin && network != Network::Bitcoin
+        }
+        _ => matches!(
+            (address.network, network),
+            (Network::Testnet, Network::Signet) | (Network::Signet, Network::Testnet)
+        ),
+    }
+}
+
+/// Splits the line on the commas outside of quotes.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej";
+    const OTHER_ADDRESS: &str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+    const TESTNET_ADDRESS: &str = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+
+    #[test]
+    fn test_parse_csv() {
+        let content = format!(
+            "address,amount,label\n\
+            {},0.5,\"Alice, the treasurer\"\n\
+            \n\
+            {},0.1\n\
+            {},0.2\n\
+            {},0.3\n\
+            {},0.00000001\n\
+            notanaddress,1",
+            ADDRESS, OTHER_ADDRESS, ADDRESS, TESTNET_ADDRESS, OTHER_ADDRESS
+        );
+        let import = parse_csv(&content, Network::Bitcoin);
+        assert_eq!(import.payouts.len(), 2);
+        assert_eq!(
+            import.payouts[0].label.as_deref(),
+            Some("Alice, the treasurer")
+        );
+        assert_eq!(import.payouts[1].label, None);
+        assert_eq!(import.total(), Amount::from_sat(60_000_000));
+        assert_eq!(
+            import.errors.iter().map(|(l, _)| *l).collect::<Vec<_>>(),
+            vec![5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let content = format!(
+            "[{{\"address\":\"{}\",\"amount\":0.5,\"label\":\"Alice\"}},\
+            {{\"address\":\"{}\",\"amount\":\"0.1\"}},\
+            {{\"address\":\"{}\",\"amount\":true}}]",
+            ADDRESS, OTHER_ADDRESS, OTHER_ADDRESS
+        );
+        let import = parse_json(&content, Network::Bitcoin).unwrap();
+        assert_eq!(import.payouts.len(), 2);
+        assert_eq!(import.payouts[0].amount, Amount::from_sat(50_000_000));
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].0, 3);
+
+        assert!(parse_json("{}", Network::Bitcoin).is_err());
+    }
+
+    #[test]
+    fn test_valid_for_network() {
+        let testnet = Address::from_str(TESTNET_ADDRESS).unwrap();
+        assert!(valid_for_network(&testnet, Network::Testnet));
+        assert!(valid_for_network(&testnet, Network::Signet));
+        assert!(!valid_for_network(&testnet, Network::Regtest));
+        assert!(!valid_for_network(&testnet, Network::Bitcoin));
+        let mainnet = Address::from_str(OTHER_ADDRESS).unwrap();
+        assert!(!valid_for_network(&mainnet, Network::Regtest));
+    }
+}
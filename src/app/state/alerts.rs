@@ -0,0 +1,207 @@
+use iced::{Command, Element};
+
+use super::{cmd::list_moving_vaults, State};
+
+use crate::{
+    app::{
+        chain,
+        context::Context,
+        error::Error,
+        message::Message,
+        view::{LoadingDashboard, UnvaultAlertView, UnvaultAlertsView},
+        watcher::WatcherEvent,
+    },
+    daemon::model::{outpoint, SpendTx, Vault, VaultStatus, VaultTransactions},
+};
+
+/// UnvaultAlertsState lists the moving vaults with the spend transaction
+/// they are unvaulted for, and cancels the unvaults one by one.
+#[derive(Debug)]
+pub enum UnvaultAlertsState {
+    Loading {
+        fail: Option<Error>,
+        view: LoadingDashboard,
+    },
+    Loaded {
+        alerts: Vec<UnvaultAlert>,
+        warning: Option<Error>,
+        view: UnvaultAlertsView,
+    },
+}
+
+impl UnvaultAlertsState {
+    pub fn new() -> Self {
+        Self::Loading {
+            fail: None,
+            view: LoadingDashboard::default(),
+        }
+    }
+}
+
+impl State for UnvaultAlertsState {
+    fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
+        match message {
+            Message::Reload => return self.load(ctx),
+            Message::Watcher(Ok(events)) => {
+                if events.iter().any(WatcherEvent::is_vault_change) {
+                    return self.load(ctx);
+                }
+            }
+            Message::UnvaultAlerts(res) => match self {
+                Self::Loading { fail, .. } => match res {
+                    Ok(vaults) => {
+                        *self = Self::Loaded {
+                            alerts: vaults
+                                .into_iter()
+                                .map(|(vault, txs, spend_tx)| {
+                                    UnvaultAlert::new(vault, txs, spend_tx)
+                                })
+                                .collect(),
+                            warning: None,
+                            view: UnvaultAlertsView::default(),
+                        }
+                    }
+                    Err(e) => *fail = Some(e.into()),
+                },
+                Self::Loaded {
+                    alerts, warning, ..
+                } => match res {
+                    Ok(vaults) => {
+                        *warning = None;
+                        *alerts = vaults
+                            .into_iter()
+                            .map(|(vault, txs, spend_tx)| {
+                                let mut alert = UnvaultAlert::new(vault, txs, spend_tx);
+                                // Keeps the errors of the previous cancel attempts.
+                                if let Some(previous) = alerts
+                                    .iter_mut()
+                                    .find(|a| outpoint(&a.vault) == outpoint(&alert.vault))
+                                {
+                                    alert.warning = previous.warning.take();
+                                }
+                                alert
+                            })
+                            .collect();
+                    }
+                    Err(e) => *warning = Some(e.into()),
+                },
+            },
+            Message::CancelUnvault(selected) => {
+                if let Self::Loaded { alerts, .. } = self {
+                    if let Some(alert) = alerts
+                        .iter_mut()
+                        .find(|a| outpoint(&a.vault) == selected && a.can_cancel())
+                    {
+                        alert.processing = true;
+                        alert.warning = None;
+                        let revaultd = ctx.revaultd.clone();
+                        return Command::perform(
+                            async move { (selected, revaultd.revault(&selected).await) },
+                            |(outpoint, res)| Message::UnvaultCanceled(outpoint, res),
+                        );
+                    }
+                }
+            }
+            Message::UnvaultCanceled(selected, res) => {
+                if let Self::Loaded { alerts, .. } = self {
+                    if let Some(alert) = alerts.iter_mut().find(|a| outpoint(&a.vault) == selected)
+                    {
+                        alert.processing = false;
+                        match res {
+                            Ok(()) => alert.vault.status = VaultStatus::Canceling,
+                            Err(e) => alert.warning = Some(e.into()),
+                        }
+                    }
+                }
+            }
+            _ => {}
+        };
+        Command::none()
+    }
+
+    fn view(&mut self, ctx: &Context) -> Element<Message> {
+        match self {
+            Self::Loading { fail, view } => view.view(ctx, fail.as_ref()),
+            Self::Loaded {
+                alerts,
+                warning,
+                view,
+            } => view.view(
+                ctx,
+                warning.as_ref(),
+                alerts.iter_mut().map(|alert| alert.view(ctx)).collect(),
+            ),
+        }
+    }
+
+    fn load(&self, ctx: &Context) -> Command<Message> {
+        Command::perform(
+            list_moving_vaults(
+                ctx.revaultd.clone(),
+                ctx.config.daemon.manager_config.is_some(),
+            ),
+            Message::UnvaultAlerts,
+        )
+    }
+}
+
+impl From<UnvaultAlertsState> for Box<dyn State> {
+    fn from(s: UnvaultAlertsState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+#[derive(Debug)]
+pub struct UnvaultAlert {
+    pub vault: Vault,
+    pub txs: VaultTransactions,
+    pub spend_tx: Option<SpendTx>,
+    pub processing: bool,
+    pub warning: Option<Error>,
+    view: UnvaultAlertView,
+}
+
+impl UnvaultAlert {
+    pub fn new(vault: Vault, txs: VaultTransactions, spend_tx: Option<SpendTx>) -> Self {
+        Self {
+            vault,
+            txs,
+            spend_tx,
+            processing: false,
+            warning: None,
+            view: UnvaultAlertView::default(),
+        }
+    }
+
+    /// Only the unvaults not spent yet can be canceled.
+    pub fn can_cancel(&self) -> bool {
+        !self.processing
+            && (self.vault.status == VaultStatus::Unvaulting
+                || self.vault.status == VaultStatus::Unvaulted)
+    }
+
+    /// Returns the number of blocks left before the spend transaction can be
+    /// mined, None if the unvault transaction is not confirmed.
+    pub fn blocks_before_timelock(&self, csv: u32, blockheight: i32) -> Option<u32> {
+        chain::blocks_before_timelock(
+            self.txs.unvault.as_ref().and_then(|tx| tx.blockheight),
+            csv,
+            blockheight,
+        )
+    }
+
+    pub fn view(&mut self, ctx: &Context) -> Element<Message> {
+        let blocks_before_timelock =
+            self.blocks_before_timelock(ctx.unvault_csv(), ctx.blockheight);
+        let can_cancel = self.can_cancel();
+        self.view.view(
+            ctx,
+            &self.vault,
+            self.spend_tx.as_ref(),
+            blocks_before_timelock,
+            can_cancel,
+            self.processing,
+            self.warning.as_ref(),
+        )
+    }
+}
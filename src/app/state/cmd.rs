@@ -1,19 +1,32 @@
-use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
+use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Transaction, Txid};
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::daemon::{
-    model::{
-        RevocationTransactions, ServersStatuses, SpendTx, SpendTxStatus, Vault, VaultStatus,
-        VaultTransactions,
+use crate::{
+    app::{
+        emergency_kit::{
+            kit_transactions, vault_emergency_transaction, write_kit, KitTransaction,
+            KIT_VAULT_STATUSES,
+        },
+        error::Error,
+        fee::FeeEstimates,
+        notification::now,
+    },
+    daemon::{
+        bitcoind::{BitcoinD, MempoolEntry},
+        model::{
+            outpoint, RevocationTransactions, ServersStatuses, SpendTx, SpendTxStatus, Vault,
+            VaultPresignedTransactions, VaultStatus, VaultTransactions, MOVING_VAULT_STATUSES,
+        },
+        Daemon, RevaultDError,
     },
-    Daemon, RevaultDError,
 };
 
 /// retrieves a bitcoin address for deposit.
 pub async fn get_deposit_address(
     revaultd: Arc<dyn Daemon + Send + Sync>,
 ) -> Result<bitcoin::Address, RevaultDError> {
-    revaultd.get_deposit_address()
+    revaultd.get_deposit_address().await
 }
 
 pub async fn list_vaults(
@@ -21,14 +34,14 @@ pub async fn list_vaults(
     statuses: Option<&[VaultStatus]>,
     outpoints: Option<Vec<OutPoint>>,
 ) -> Result<Vec<Vault>, RevaultDError> {
-    revaultd.list_vaults(statuses, outpoints.as_deref())
+    revaultd.list_vaults(statuses, outpoints.as_deref()).await
 }
 
 pub async fn get_onchain_txs(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     outpoint: OutPoint,
 ) -> Result<VaultTransactions, RevaultDError> {
-    let list = revaultd.list_onchain_transactions(&[outpoint])?;
+    let list = revaultd.list_onchain_transactions(&[outpoint]).await?;
     if list.is_empty() {
         return Err(RevaultDError::Unexpected(
             "vault has no onchain_transactions".to_string(),
@@ -38,18 +51,67 @@ pub async fn get_onchain_txs(
     Ok(list[0].to_owned())
 }
 
+pub async fn get_presigned_txs(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    outpoint: OutPoint,
+) -> Result<VaultPresignedTransactions, RevaultDError> {
+    let list = revaultd.list_presigned_transactions(&[outpoint]).await?;
+    list.into_iter()
+        .next()
+        .ok_or_else(|| RevaultDError::Unexpected("vault has no presigned transactions".to_string()))
+}
+
+/// Returns the moving vaults with their onchain transactions and the known
+/// spend transaction of each one. The spend transactions are only known
+/// by the managers daemon.
+pub async fn list_moving_vaults(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    with_spend_txs: bool,
+) -> Result<Vec<(Vault, VaultTransactions, Option<SpendTx>)>, RevaultDError> {
+    let vaults = revaultd
+        .list_vaults(Some(&MOVING_VAULT_STATUSES), None)
+        .await?;
+    if vaults.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outpoints: Vec<OutPoint> = vaults.iter().map(outpoint).collect();
+    let txs = revaultd.list_onchain_transactions(&outpoints).await?;
+    let spend_txs = if with_spend_txs {
+        revaultd.list_spend_txs(None).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(vaults
+        .into_iter()
+        .filter_map(|vault| {
+            let outpoint = outpoint(&vault);
+            let txs = txs
+                .iter()
+                .find(|txs| txs.vault_outpoint == outpoint)?
+                .clone();
+            let spend_tx = spend_txs
+                .iter()
+                .find(|tx| tx.deposit_outpoints.contains(&outpoint))
+                .cloned();
+            Some((vault, txs, spend_tx))
+        })
+        .collect())
+}
+
 pub async fn get_revocation_txs(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     outpoint: OutPoint,
 ) -> Result<RevocationTransactions, RevaultDError> {
-    revaultd.get_revocation_txs(&outpoint)
+    revaultd.get_revocation_txs(&outpoint).await
 }
 
 pub async fn get_unvault_tx(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     outpoint: OutPoint,
 ) -> Result<Psbt, RevaultDError> {
-    revaultd.get_unvault_tx(&outpoint)
+    revaultd.get_unvault_tx(&outpoint).await
 }
 
 pub async fn set_unvault_tx(
@@ -57,28 +119,28 @@ pub async fn set_unvault_tx(
     outpoint: OutPoint,
     unvault_tx: Psbt,
 ) -> Result<(), RevaultDError> {
-    revaultd.set_unvault_tx(&outpoint, &unvault_tx)
+    revaultd.set_unvault_tx(&outpoint, &unvault_tx).await
 }
 
 pub async fn update_spend_tx(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     psbt: Psbt,
 ) -> Result<(), RevaultDError> {
-    revaultd.update_spend_tx(&psbt)
+    revaultd.update_spend_tx(&psbt).await
 }
 
 pub async fn list_spend_txs(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     statuses: Option<&[SpendTxStatus]>,
 ) -> Result<Vec<SpendTx>, RevaultDError> {
-    revaultd.list_spend_txs(statuses)
+    revaultd.list_spend_txs(statuses).await
 }
 
 pub async fn delete_spend_tx(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     txid: Txid,
 ) -> Result<(), RevaultDError> {
-    revaultd.delete_spend_tx(&txid)
+    revaultd.delete_spend_tx(&txid).await
 }
 
 pub async fn broadcast_spend_tx(
@@ -86,15 +148,127 @@ pub async fn broadcast_spend_tx(
     txid: Txid,
     with_priority: bool,
 ) -> Result<(), RevaultDError> {
-    revaultd.broadcast_spend_tx(&txid, with_priority)
+    revaultd.broadcast_spend_tx(&txid, with_priority).await
 }
 
 pub async fn emergency(revaultd: Arc<dyn Daemon + Send + Sync>) -> Result<(), RevaultDError> {
-    revaultd.emergency()
+    revaultd.emergency().await
+}
+
+/// Writes the emergency kit of the vaults to the directory,
+/// returns the number of vaults in the kit.
+/// The kit is not written if a vault has no presigned transactions.
+pub async fn export_emergency_kit(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    network: bitcoin::Network,
+    emergency_address: bitcoin::Address,
+    path: PathBuf,
+) -> Result<(usize, PathBuf), Error> {
+    let vaults = revaultd
+        .list_vaults(Some(&KIT_VAULT_STATUSES), None)
+        .await?;
+    let outpoints: Vec<OutPoint> = vaults.iter().map(outpoint).collect();
+    let presigned_txs = revaultd.list_presigned_transactions(&outpoints).await?;
+    let mut missing = Vec::new();
+    let mut vaults_with_txs = Vec::new();
+    for vault in vaults {
+        match presigned_txs
+            .iter()
+            .find(|txs| txs.vault_outpoint == outpoint(&vault))
+        {
+            Some(txs) => vaults_with_txs.push((vault, txs.clone())),
+            None => missing.push(outpoint(&vault).to_string()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(Error::Unexpected(format!(
+            "Vaults {} have no presigned transactions",
+            missing.join(", ")
+        )));
+    }
+    let txs = kit_transactions(network, &emergency_address, &vaults_with_txs)
+        .map_err(Error::Unexpected)?;
+    write_kit(&path, network, now(), txs).map_err(Error::Unexpected)?;
+    Ok((vaults_with_txs.len(), path))
+}
+
+/// Returns the transactions sending the funds of the vaults to the
+/// Emergency Deep Vault, one per vault.
+pub async fn list_emergency_transactions(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    network: bitcoin::Network,
+    emergency_address: bitcoin::Address,
+    outpoints: Vec<OutPoint>,
+) -> Result<Vec<(KitTransaction, Transaction)>, Error> {
+    let vaults = revaultd
+        .list_vaults(Some(&KIT_VAULT_STATUSES), Some(outpoints.as_slice()))
+        .await?;
+    if vaults.len() != outpoints.len() {
+        return Err(Error::Unexpected(
+            "Some of the vaults cannot be sent to the Emergency Deep Vault anymore".to_string(),
+        ));
+    }
+    let presigned_txs = revaultd.list_presigned_transactions(&outpoints).await?;
+    vaults
+        .iter()
+        .map(|vault| {
+            let presigned = presigned_txs
+                .iter()
+                .find(|txs| txs.vault_outpoint == outpoint(vault))
+                .ok_or_else(|| {
+                    format!("Vault {} has no presigned transactions", outpoint(vault))
+                })?;
+            vault_emergency_transaction(network, &emergency_address, vault, presigned)
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(Error::Unexpected)
+}
+
+/// Broadcasts each transaction on its own, a rejected transaction does not
+/// prevent the following ones from being broadcast.
+/// Returns the result of each transaction, in order.
+pub async fn broadcast_transactions(
+    bitcoind: BitcoinD,
+    txs: Vec<Transaction>,
+) -> Result<Vec<Result<Txid, RevaultDError>>, RevaultDError> {
+    bitcoind_call(bitcoind, move |bitcoind| {
+        Ok(txs.iter().map(|tx| bitcoind.broadcast(tx)).collect())
+    })
+    .await
 }
 
 pub async fn get_server_status(
     revaultd: Arc<dyn Daemon + Send + Sync>,
 ) -> Result<ServersStatuses, RevaultDError> {
-    revaultd.get_server_status()
+    revaultd.get_server_status().await
+}
+
+/// Runs the bitcoind call on the blocking thread pool, the client waits
+/// for the response on its socket.
+async fn bitcoind_call<T, F>(bitcoind: BitcoinD, f: F) -> Result<T, RevaultDError>
+where
+    T: Send + 'static,
+    F: FnOnce(&BitcoinD) -> Result<T, RevaultDError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&bitcoind))
+        .await
+        .map_err(|e| RevaultDError::Unexpected(e.to_string()))?
+}
+
+pub async fn get_mempool_entry(
+    bitcoind: BitcoinD,
+    txid: Txid,
+) -> Result<MempoolEntry, RevaultDError> {
+    bitcoind_call(bitcoind, move |bitcoind| bitcoind.get_mempool_entry(&txid)).await
+}
+
+pub async fn estimate_fees(bitcoind: BitcoinD) -> Result<FeeEstimates, RevaultDError> {
+    bitcoind_call(bitcoind, |bitcoind| Ok(FeeEstimates::fetch(bitcoind))).await
+}
+
+pub async fn send_raw_transaction(
+    bitcoind: BitcoinD,
+    tx: Transaction,
+) -> Result<Txid, RevaultDError> {
+    bitcoind_call(bitcoind, move |bitcoind| bitcoind.send_raw_transaction(&tx)).await
 }
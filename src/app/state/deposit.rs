@@ -6,6 +6,7 @@ use super::{cmd::get_deposit_address, State};
 
 use crate::app::{
     context::Context, error::Error, message::Message, view::DepositView, view::LoadingDashboard,
+    watcher::WatcherEvent,
 };
 
 /// DepositState handles the deposit process.
@@ -62,6 +63,15 @@ impl State for DepositState {
             } => {
                 match message {
                     Message::Reload => return self.load(ctx),
+                    // The daemon gives a new address once the current one received a deposit.
+                    Message::Watcher(Ok(events)) => {
+                        if events
+                            .iter()
+                            .any(|event| matches!(event, WatcherEvent::NewDeposit { .. }))
+                        {
+                            return self.load(ctx);
+                        }
+                    }
                     Message::DepositAddress(res) => {
                         match res {
                             Ok(addr) => {
@@ -1,16 +1,24 @@
 use std::convert::From;
+use std::path::PathBuf;
 
+use bitcoin::{OutPoint, Transaction, Txid};
 use iced::{Command, Element};
+use revault_ui::component::form;
 
 use super::{cmd::list_vaults, State};
 
-use crate::daemon::model::VaultStatus;
+use crate::daemon::{
+    bitcoind::BitcoinD,
+    model::{outpoint, Vault, VaultStatus},
+    RevaultDError,
+};
 
 use crate::app::{
     context::Context,
+    emergency_kit::{KitStatus, KitTransaction},
     error::Error,
     menu::Menu,
-    message::Message,
+    message::{EmergencyKitMessage, Message, SelectedEmergencyMessage},
     state::cmd,
     view::{EmergencyTriggeredView, EmergencyView, LoadingModal},
 };
@@ -30,6 +38,9 @@ pub enum EmergencyState {
         warning: Option<Error>,
 
         processing: bool,
+
+        kit: EmergencyKit,
+        selection: SelectedEmergency,
     },
     Triggered {
         vaults_number: usize,
@@ -47,22 +58,232 @@ impl EmergencyState {
     }
 }
 
+/// EmergencyKit exports the presigned revocation transactions, they can be
+/// broadcast without the daemon.
+#[derive(Debug)]
+pub struct EmergencyKit {
+    status: KitStatus,
+    path: form::Value<String>,
+    processing: bool,
+    /// Number of vaults and directory of the last export.
+    exported: Option<(usize, PathBuf)>,
+}
+
+impl EmergencyKit {
+    pub fn new(ctx: &Context, status: KitStatus) -> Self {
+        let path = ctx
+            .config
+            .gui
+            .emergency_kit_path
+            .clone()
+            .unwrap_or_else(|| {
+                let mut path = dirs::home_dir().unwrap_or_default();
+                path.push("revault_emergency_kit");
+                path
+            });
+        Self {
+            status,
+            path: form::Value {
+                value: path.to_string_lossy().to_string(),
+                valid: true,
+            },
+            processing: false,
+            exported: None,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        message: EmergencyKitMessage,
+    ) -> Result<Command<Message>, Error> {
+        match message {
+            EmergencyKitMessage::PathEdited(path) => {
+                self.path.value = path;
+                self.path.valid = true;
+            }
+            EmergencyKitMessage::Export => {
+                self.path.valid = !self.path.value.is_empty();
+                if !self.path.valid || self.processing {
+                    return Ok(Command::none());
+                }
+                let emergency_address = ctx.emergency_address().ok_or_else(no_emergency_address)?;
+                self.processing = true;
+                self.exported = None;
+                return Ok(Command::perform(
+                    cmd::export_emergency_kit(
+                        ctx.revaultd.clone(),
+                        ctx.network(),
+                        emergency_address,
+                        PathBuf::from(&self.path.value),
+                    ),
+                    |res| Message::EmergencyKit(EmergencyKitMessage::Exported(res)),
+                ));
+            }
+            EmergencyKitMessage::Exported(res) => {
+                self.processing = false;
+                let (count, path) = res?;
+                self.status = KitStatus::UpToDate;
+                self.exported = Some((count, path.clone()));
+                // The kit directory is kept to warn the user once new vaults
+                // are secured.
+                let mut gui_config = ctx.config.gui.clone();
+                gui_config.emergency_kit_path = Some(path);
+                return Ok(Command::perform(
+                    async move { gui_config },
+                    Message::LoadGuiConfig,
+                ));
+            }
+        }
+        Ok(Command::none())
+    }
+}
+
+/// SelectedEmergency sends the funds of the selected vaults only to the
+/// Emergency Deep Vault, their presigned transactions are broadcast with bitcoind.
+#[derive(Debug)]
+pub struct SelectedEmergency {
+    /// Vaults whose funds can still be sent to the Emergency Deep Vault.
+    vaults: Vec<Vault>,
+    selected: Vec<OutPoint>,
+    /// Transactions to broadcast, set once the selection is reviewed.
+    transactions: Option<Vec<(KitTransaction, Transaction)>>,
+    /// Result of the last broadcast of each transaction.
+    broadcasted: Option<Vec<Result<Txid, RevaultDError>>>,
+    processing: bool,
+}
+
+impl SelectedEmergency {
+    pub fn new(vaults: Vec<Vault>) -> Self {
+        Self {
+            vaults,
+            selected: Vec::new(),
+            transactions: None,
+            broadcasted: None,
+            processing: false,
+        }
+    }
+
+    /// The vaults that are not listed anymore are removed from the selection.
+    fn set_vaults(&mut self, vaults: Vec<Vault>) {
+        self.selected
+            .retain(|selected| vaults.iter().any(|vault| outpoint(vault) == *selected));
+        self.vaults = vaults;
+    }
+
+    /// Every reviewed transaction was broadcast.
+    fn all_broadcasted(&self) -> bool {
+        self.broadcasted
+            .as_ref()
+            .map(|results| results.iter().all(|res| res.is_ok()))
+            .unwrap_or(false)
+    }
+
+    /// Number of vaults and funds sent by the reviewed transactions.
+    fn sent(&self) -> (usize, u64) {
+        self.transactions
+            .as_ref()
+            .map(|txs| (txs.len(), txs.iter().map(|(entry, _)| entry.amount).sum()))
+            .unwrap_or((0, 0))
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        message: SelectedEmergencyMessage,
+    ) -> Result<Command<Message>, Error> {
+        match message {
+            SelectedEmergencyMessage::VaultToggled(vault, checked) => {
+                if self.transactions.is_none() {
+                    self.selected.retain(|selected| *selected != vault);
+                    if checked {
+                        self.selected.push(vault);
+                    }
+                }
+            }
+            SelectedEmergencyMessage::Review => {
+                if self.selected.is_empty() || self.processing {
+                    return Ok(Command::none());
+                }
+                let emergency_address = ctx.emergency_address().ok_or_else(no_emergency_address)?;
+                self.processing = true;
+                return Ok(Command::perform(
+                    cmd::list_emergency_transactions(
+                        ctx.revaultd.clone(),
+                        ctx.network(),
+                        emergency_address,
+                        self.selected.clone(),
+                    ),
+                    |res| Message::SelectedEmergency(SelectedEmergencyMessage::Transactions(res)),
+                ));
+            }
+            SelectedEmergencyMessage::Transactions(res) => {
+                self.processing = false;
+                self.broadcasted = None;
+                self.transactions = Some(res?);
+            }
+            SelectedEmergencyMessage::Back => {
+                if !self.processing {
+                    self.transactions = None;
+                    self.broadcasted = None;
+                }
+            }
+            SelectedEmergencyMessage::Broadcast => {
+                if let Some(txs) = &self.transactions {
+                    if self.processing {
+                        return Ok(Command::none());
+                    }
+                    self.processing = true;
+                    return Ok(Command::perform(
+                        cmd::broadcast_transactions(
+                            BitcoinD::new(&ctx.config.daemon.bitcoind_config),
+                            txs.iter().map(|(_, tx)| tx.clone()).collect(),
+                        ),
+                        |res| {
+                            Message::SelectedEmergency(SelectedEmergencyMessage::Broadcasted(res))
+                        },
+                    ));
+                }
+            }
+            SelectedEmergencyMessage::Broadcasted(res) => {
+                self.processing = false;
+                self.broadcasted = Some(res?);
+            }
+        }
+        Ok(Command::none())
+    }
+}
+
+/// The emergency transactions are checked against the configured address.
+fn no_emergency_address() -> Error {
+    Error::Unexpected("The configuration has no emergency address".to_string())
+}
+
+/// Vaults already sent to the Emergency Deep Vault.
+fn is_emergency(vault: &Vault) -> bool {
+    vault.status == VaultStatus::EmergencyVaulting
+        || vault.status == VaultStatus::EmergencyVaulted
+        || vault.status == VaultStatus::UnvaultEmergencyVaulting
+        || vault.status == VaultStatus::UnvaultEmergencyVaulted
+}
+
 impl State for EmergencyState {
     fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
         match message {
             Message::Vaults(res) => match self {
                 Self::Loading { fail, .. } => match res {
                     Ok(vaults) => {
-                        if vaults.iter().any(|vault| {
-                            vault.status == VaultStatus::EmergencyVaulting
-                                || vault.status == VaultStatus::EmergencyVaulted
-                                || vault.status == VaultStatus::UnvaultEmergencyVaulting
-                                || vault.status == VaultStatus::UnvaultEmergencyVaulted
-                        }) {
+                        let kit_status =
+                            KitStatus::check(ctx.config.gui.emergency_kit_path.as_deref(), &vaults);
+                        // Some vaults may have been sent to the Emergency Deep Vault
+                        // alone, the emergency can still be triggered for the others.
+                        let (emergency, vaults): (Vec<Vault>, Vec<Vault>) =
+                            vaults.into_iter().partition(is_emergency);
+                        if !emergency.is_empty() && vaults.is_empty() {
                             *self = Self::Triggered {
                                 view: EmergencyTriggeredView::new(),
-                                vaults_number: vaults.len(),
-                                funds_amount: vaults
+                                vaults_number: emergency.len(),
+                                funds_amount: emergency
                                     .into_iter()
                                     .fold(0, |acc, vault| acc + vault.amount.as_sat()),
                             };
@@ -71,10 +292,12 @@ impl State for EmergencyState {
                                 view: EmergencyView::new(),
                                 vaults_number: vaults.len(),
                                 funds_amount: vaults
-                                    .into_iter()
+                                    .iter()
                                     .fold(0, |acc, vault| acc + vault.amount.as_sat()),
                                 warning: None,
                                 processing: false,
+                                kit: EmergencyKit::new(ctx, kit_status),
+                                selection: SelectedEmergency::new(vaults),
                             };
                         }
                     }
@@ -84,13 +307,22 @@ impl State for EmergencyState {
                     vaults_number,
                     funds_amount,
                     warning,
+                    kit,
+                    selection,
                     ..
                 } => match res {
                     Ok(vaults) => {
+                        kit.status =
+                            KitStatus::check(ctx.config.gui.emergency_kit_path.as_deref(), &vaults);
+                        let vaults: Vec<Vault> = vaults
+                            .into_iter()
+                            .filter(|vault| !is_emergency(vault))
+                            .collect();
                         *vaults_number = vaults.len();
                         *funds_amount = vaults
-                            .into_iter()
+                            .iter()
                             .fold(0, |acc, vault| acc + vault.amount.as_sat());
+                        selection.set_vaults(vaults);
                         *warning = None;
                     }
                     Err(e) => *warning = Error::from(e).into(),
@@ -112,6 +344,42 @@ impl State for EmergencyState {
                     );
                 }
             }
+            Message::EmergencyKit(msg) => {
+                if let Self::Loaded { kit, warning, .. } = self {
+                    *warning = None;
+                    match kit.update(ctx, msg) {
+                        Ok(cmd) => return cmd,
+                        Err(e) => *warning = Some(e),
+                    }
+                }
+            }
+            Message::SelectedEmergency(msg) => {
+                if let Self::Loaded {
+                    selection, warning, ..
+                } = self
+                {
+                    *warning = None;
+                    match selection.update(ctx, msg) {
+                        Ok(cmd) => {
+                            if selection.all_broadcasted() {
+                                let (vaults_number, funds_amount) = selection.sent();
+                                *self = Self::Triggered {
+                                    view: EmergencyTriggeredView::new(),
+                                    vaults_number,
+                                    funds_amount,
+                                };
+                            }
+                            return cmd;
+                        }
+                        Err(e) => *warning = Some(e),
+                    }
+                }
+            }
+            Message::GuiConfigLoaded(Err(e)) => {
+                if let Self::Loaded { warning, .. } = self {
+                    *warning = Some(e);
+                }
+            }
             Message::EmergencyBroadcasted(res) => {
                 if let Self::Loaded {
                     processing,
@@ -147,12 +415,23 @@ impl State for EmergencyState {
                 warning,
                 processing,
                 vaults_number,
+                kit,
+                selection,
             } => view.view(
                 ctx,
                 *vaults_number,
                 *funds_amount,
                 warning.as_ref(),
                 *processing,
+                kit.status,
+                &kit.path,
+                kit.processing,
+                kit.exported.as_ref(),
+                &selection.vaults,
+                &selection.selected,
+                selection.transactions.as_deref(),
+                selection.broadcasted.as_deref(),
+                selection.processing,
             ),
             Self::Triggered {
                 view,
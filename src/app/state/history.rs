@@ -1,7 +1,11 @@
 use std::convert::TryInto;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use chrono::NaiveDate;
 use iced::{Command, Element};
+use revault_ui::component::form;
 
 use super::State;
 
@@ -9,15 +13,235 @@ use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{HistoryEventMessage, Message},
+        export::{merge_history_page, write_history, ExportFormat, EXPORT_PAGE_SIZE},
+        message::{HistoryEventMessage, HistoryExportMessage, HistoryFilterMessage, Message},
         view::LoadingDashboard,
-        view::{HistoryEventListItemView, HistoryEventView, HistoryView},
+        view::{HistoryEventListItemView, HistoryEventView, HistoryExportView, HistoryView},
+        watcher::WatcherEvent,
+    },
+    daemon::{
+        model::{HistoryEvent, HistoryEventKind, VaultTransactions, ALL_HISTORY_EVENTS},
+        Daemon, RevaultDError,
     },
-    daemon::model::{HistoryEvent, HistoryEventKind, VaultTransactions, ALL_HISTORY_EVENTS},
 };
 
 pub const HISTORY_EVENT_PAGE_SIZE: u64 = 20;
 
+/// HistoryFilter selects the displayed history events.
+/// The kinds and the date range are given to the daemon, the amounts
+/// and the search are applied to the fetched events, the pages are fetched
+/// until enough of them match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryFilter {
+    pub kinds: Vec<HistoryEventKind>,
+    pub start: u32,
+    /// End of the date range, now if None.
+    pub end: Option<u32>,
+    pub min_amount: Option<u64>,
+    pub max_amount: Option<u64>,
+    /// Part of the txid or of a vault outpoint of the event.
+    pub search: String,
+}
+
+impl Default for HistoryFilter {
+    fn default() -> Self {
+        Self {
+            kinds: ALL_HISTORY_EVENTS.to_vec(),
+            start: 0,
+            end: None,
+            min_amount: None,
+            max_amount: None,
+            search: String::new(),
+        }
+    }
+}
+
+impl HistoryFilter {
+    pub fn end(&self) -> u32 {
+        self.end.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                .try_into()
+                .unwrap()
+        })
+    }
+
+    pub fn matches(&self, event: &HistoryEvent) -> bool {
+        let amount = event.amount.unwrap_or(0);
+        if self.min_amount.map(|min| amount < min).unwrap_or(false)
+            || self.max_amount.map(|max| amount > max).unwrap_or(false)
+        {
+            return false;
+        }
+
+        let search = self.search.trim().to_lowercase();
+        search.is_empty()
+            || event.txid.to_string().contains(&search)
+            || event
+                .vaults
+                .iter()
+                .any(|outpoint| outpoint.to_string().contains(&search))
+    }
+
+    fn fetch(&self, ctx: &Context) -> Command<Message> {
+        Command::perform(
+            fetch_page(ctx.revaultd.clone(), self.clone(), self.end(), false),
+            Message::HistoryPage,
+        )
+    }
+}
+
+/// Fetches the events of the filter before the cursor, until a page of
+/// events matching the amounts and the search is fetched or the history
+/// is exhausted. The cursor is the date of the last displayed event if
+/// after_event is true.
+/// Returns the fetched events, matching or not, and whether the history
+/// has more events.
+async fn fetch_page(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    filter: HistoryFilter,
+    mut cursor: u32,
+    mut after_event: bool,
+) -> Result<(Vec<HistoryEvent>, bool), RevaultDError> {
+    let mut fetched = Vec::new();
+    loop {
+        let mut limit = HISTORY_EVENT_PAGE_SIZE;
+        let mut page = revaultd
+            .get_history(&filter.kinds, filter.start, cursor, limit)
+            .await?;
+
+        // because gethistory cursor is inclusive and use blocktime
+        // multiple events can occur in the same block.
+        // If there is more event in the same block that the
+        // HISTORY_EVENT_PAGE_SIZE they can not be retrieved by changing
+        // the cursor value (blocktime) but by increasing the limit.
+        if after_event {
+            // 1. Check if the events retrieved have all the same blocktime
+            if let Some(blocktime) = page.first().map(|evt| evt.date) {
+                // 2. Retrieve a larger batch of event with the same cursor but
+                //    a larger limit.
+                while !page.iter().any(|evt| evt.date != blocktime) && page.len() as u64 == limit {
+                    // increments of the equivalent of one page more.
+                    limit += HISTORY_EVENT_PAGE_SIZE;
+                    page = revaultd
+                        .get_history(&filter.kinds, filter.start, cursor, limit)
+                        .await?;
+                }
+            }
+        }
+
+        let has_next = !page.is_empty() && page.len() as u64 % HISTORY_EVENT_PAGE_SIZE == 0;
+        let oldest = page.last().map(|evt| evt.date);
+        merge_history_page(&mut fetched, page);
+        let matching = fetched.iter().filter(|evt| filter.matches(evt)).count() as u64;
+        match oldest {
+            Some(oldest) if has_next && matching < HISTORY_EVENT_PAGE_SIZE => {
+                cursor = oldest;
+                after_event = true;
+            }
+            _ => return Ok((fetched, has_next)),
+        }
+    }
+}
+
+/// HistoryFilterForm holds the values of the filter bar until they are applied.
+#[derive(Debug)]
+pub struct HistoryFilterForm {
+    pub kinds: Vec<HistoryEventKind>,
+    pub from: form::Value<String>,
+    pub to: form::Value<String>,
+    pub min_amount: form::Value<String>,
+    pub max_amount: form::Value<String>,
+    pub search: form::Value<String>,
+}
+
+impl HistoryFilterForm {
+    pub fn new() -> Self {
+        Self {
+            kinds: ALL_HISTORY_EVENTS.to_vec(),
+            from: form::Value::default(),
+            to: form::Value::default(),
+            min_amount: form::Value::default(),
+            max_amount: form::Value::default(),
+            search: form::Value::default(),
+        }
+    }
+
+    pub fn update(&mut self, message: HistoryFilterMessage) {
+        match message {
+            HistoryFilterMessage::KindToggled(kind, checked) => {
+                self.kinds.retain(|k| *k != kind);
+                if checked {
+                    self.kinds.push(kind);
+                }
+            }
+            HistoryFilterMessage::FromEdited(value) => self.from = valid(value),
+            HistoryFilterMessage::ToEdited(value) => self.to = valid(value),
+            HistoryFilterMessage::MinAmountEdited(value) => self.min_amount = valid(value),
+            HistoryFilterMessage::MaxAmountEdited(value) => self.max_amount = valid(value),
+            HistoryFilterMessage::SearchEdited(value) => self.search = valid(value),
+            HistoryFilterMessage::Apply | HistoryFilterMessage::Clear => {}
+        }
+    }
+
+    /// Returns the filter if all the values are valid.
+    pub fn filter(&mut self, ctx: &Context) -> Option<HistoryFilter> {
+        let start = optional(&self.from.value, |v| parse_date(v, false));
+        let end = optional(&self.to.value, |v| parse_date(v, true));
+        let min_amount = optional(&self.min_amount.value, |v| {
+            ctx.converter.parse(v).map(|a| a.as_sat())
+        });
+        let max_amount = optional(&self.max_amount.value, |v| {
+            ctx.converter.parse(v).map(|a| a.as_sat())
+        });
+
+        self.from.valid = start.is_some();
+        self.to.valid = match (start, end) {
+            (Some(Some(start)), Some(Some(end))) => start <= end,
+            (_, end) => end.is_some(),
+        };
+        self.min_amount.valid = min_amount.is_some();
+        self.max_amount.valid = match (min_amount, max_amount) {
+            (Some(Some(min)), Some(Some(max))) => min <= max,
+            (_, max) => max.is_some(),
+        };
+
+        if !self.from.valid
+            || !self.to.valid
+            || !self.min_amount.valid
+            || !self.max_amount.valid
+            || self.kinds.is_empty()
+        {
+            return None;
+        }
+
+        Some(HistoryFilter {
+            kinds: self.kinds.clone(),
+            start: start.flatten().unwrap_or(0),
+            end: end.flatten(),
+            min_amount: min_amount.flatten(),
+            max_amount: max_amount.flatten(),
+            search: self.search.value.clone(),
+        })
+    }
+}
+
+fn valid(value: String) -> form::Value<String> {
+    form::Value { value, valid: true }
+}
+
+/// Parses the value if it is not empty, returns None if the parsing failed
+/// and Some(None) if the value is empty.
+fn optional<T, F: Fn(&str) -> Option<T>>(value: &str, parse: F) -> Option<Option<T>> {
+    if value.trim().is_empty() {
+        Some(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
 /// HistoryState displays history events.
 #[derive(Debug)]
 pub enum HistoryState {
@@ -26,9 +250,11 @@ pub enum HistoryState {
         view: LoadingDashboard,
     },
     Loaded {
-        event_kind_filter: Option<HistoryEventKind>,
+        filter: HistoryFilter,
+        filter_form: HistoryFilterForm,
         events: Vec<HistoryEventListItemState>,
         selected_event: Option<HistoryEventState>,
+        export: Option<HistoryExportState>,
         has_next: bool,
         // Error in case of reload failure.
         warning: Option<Error>,
@@ -50,19 +276,20 @@ impl State for HistoryState {
     fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
         match self {
             Self::Loading { fail, .. } => {
-                if let Message::HistoryEvents(res) = message {
+                if let Message::HistoryPage(res) = message {
                     match res {
-                        Ok(events) => {
-                            let has_next = events.len() as u64 >= HISTORY_EVENT_PAGE_SIZE;
+                        Ok((events, has_next)) => {
                             *self = Self::Loaded {
                                 has_next,
-                                event_kind_filter: None,
+                                filter: HistoryFilter::default(),
+                                filter_form: HistoryFilterForm::new(),
                                 events: events
                                     .into_iter()
                                     .map(|evt| HistoryEventListItemState::new(evt))
                                     .collect(),
                                 warning: None,
                                 selected_event: None,
+                                export: None,
                                 view: HistoryView::new(),
                             };
                         }
@@ -73,15 +300,23 @@ impl State for HistoryState {
             Self::Loaded {
                 events,
                 warning,
-                event_kind_filter,
+                filter,
+                filter_form,
                 has_next,
                 selected_event,
+                export,
                 ..
             } => match message {
                 Message::Reload => {
                     *events = Vec::new();
-                    *event_kind_filter = None;
-                    return self.load(ctx);
+                    return filter.fetch(ctx);
+                }
+                Message::Watcher(Ok(changes)) => {
+                    if selected_event.is_none() && changes.iter().any(WatcherEvent::is_vault_change)
+                    {
+                        *events = Vec::new();
+                        return filter.fetch(ctx);
+                    }
                 }
                 Message::SelectHistoryEvent(i) => {
                     if let Some(item) = events.get(i) {
@@ -100,89 +335,53 @@ impl State for HistoryState {
                     if selected_event.is_some() {
                         *selected_event = None;
                     }
+                    if export.is_some() {
+                        *export = None;
+                    }
+                }
+                Message::ExportHistory => {
+                    *export = Some(HistoryExportState::new());
                 }
-                Message::FilterHistoryEvents(filter) => {
+                Message::HistoryExport(msg) => {
+                    if let Some(export) = export {
+                        return export.update(ctx, msg);
+                    }
+                }
+                Message::HistoryFilter(HistoryFilterMessage::Apply) => {
+                    if let Some(new_filter) = filter_form.filter(ctx) {
+                        *events = Vec::new();
+                        *filter = new_filter;
+                        return filter.fetch(ctx);
+                    }
+                }
+                Message::HistoryFilter(HistoryFilterMessage::Clear) => {
+                    *filter_form = HistoryFilterForm::new();
                     *events = Vec::new();
-                    *event_kind_filter = filter;
-                    let t1: u32 = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs()
-                        .try_into()
-                        .unwrap();
-                    let kind = event_kind_filter
-                        .as_ref()
-                        .map(|filter| vec![filter.clone()])
-                        .unwrap_or(vec![
-                            HistoryEventKind::Cancel,
-                            HistoryEventKind::Deposit,
-                            HistoryEventKind::Spend,
-                        ]);
-                    let revaultd = ctx.revaultd.clone();
-                    return Command::perform(
-                        async move { revaultd.get_history(kind.as_slice(), 0, t1, u32::MAX.into()) },
-                        Message::HistoryEvents,
-                    );
+                    *filter = HistoryFilter::default();
+                    return filter.fetch(ctx);
+                }
+                Message::HistoryFilter(msg) => filter_form.update(msg),
+                Message::FilterHistoryEvents(new_filter) => {
+                    *events = Vec::new();
+                    *filter = new_filter;
+                    return filter.fetch(ctx);
                 }
                 Message::Next => {
                     if let Some(last) = events.last() {
-                        let revaultd = ctx.revaultd.clone();
-                        let last_event_date = last.event.date as u32;
-                        let kind = event_kind_filter
-                            .as_ref()
-                            .map(|filter| vec![filter.clone()])
-                            .unwrap_or(vec![
-                                HistoryEventKind::Cancel,
-                                HistoryEventKind::Deposit,
-                                HistoryEventKind::Spend,
-                            ]);
                         return Command::perform(
-                            async move {
-                                let mut limit = HISTORY_EVENT_PAGE_SIZE;
-                                let mut events = revaultd.get_history(
-                                    kind.as_slice(),
-                                    0 as u32,
-                                    last_event_date,
-                                    limit,
-                                )?;
-
-                                // because gethistory cursor is inclusive and use blocktime
-                                // multiple events can occur in the same block.
-                                // If there is more event in the same block that the
-                                // HISTORY_EVENT_PAGE_SIZE they can not be retrieved by changing
-                                // the cursor value (blocktime) but by increasing the limit.
-                                //
-                                // 1. Check if the events retrieved have all the same blocktime
-                                let blocktime = if let Some(event) = events.first() {
-                                    event.date
-                                } else {
-                                    return Ok(events);
-                                };
-
-                                // 2. Retrieve a larger batch of event with the same cursor but
-                                //    a larger limit.
-                                while !events.iter().any(|evt| evt.date != blocktime)
-                                    && events.len() as u64 == limit
-                                {
-                                    // increments of the equivalent of one page more.
-                                    limit += HISTORY_EVENT_PAGE_SIZE;
-                                    events = revaultd.get_history(
-                                        kind.as_slice(),
-                                        0,
-                                        last_event_date,
-                                        limit,
-                                    )?;
-                                }
-                                Ok(events)
-                            },
-                            Message::HistoryEvents,
+                            fetch_page(
+                                ctx.revaultd.clone(),
+                                filter.clone(),
+                                last.event.date as u32,
+                                true,
+                            ),
+                            Message::HistoryPage,
                         );
                     }
                 }
-                Message::HistoryEvents(res) => match res {
-                    Ok(evts) => {
-                        *has_next =
-                            !evts.is_empty() && evts.len() as u64 % HISTORY_EVENT_PAGE_SIZE == 0;
+                Message::HistoryPage(res) => match res {
+                    Ok((evts, next)) => {
+                        *has_next = next;
                         // gethistory cursor is inclusive and use blocktime.
                         // multiple events can occur in the same block and
                         // if they are included or not in the batch of events
@@ -216,22 +415,32 @@ impl State for HistoryState {
                 events,
                 warning,
                 view,
-                event_kind_filter,
+                filter,
+                filter_form,
                 has_next,
                 selected_event,
+                export,
             } => {
                 if let Some(event) = selected_event {
                     event.view(ctx)
+                } else if let Some(export) = export {
+                    export.view(ctx)
                 } else {
                     view.view(
                         ctx,
                         warning.as_ref(),
+                        &filter_form.kinds,
+                        &filter_form.from,
+                        &filter_form.to,
+                        &filter_form.min_amount,
+                        &filter_form.max_amount,
+                        &filter_form.search,
                         events
                             .iter_mut()
                             .enumerate()
+                            .filter(|(_, evt)| filter.matches(&evt.event))
                             .map(|(i, evt)| evt.view(ctx, i))
                             .collect(),
-                        &event_kind_filter,
                         *has_next,
                     )
                 }
@@ -241,17 +450,7 @@ impl State for HistoryState {
 
     // We retrieve the full history
     fn load(&self, ctx: &Context) -> Command<Message> {
-        let t1: u32 = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            .try_into()
-            .unwrap();
-        let revaultd = ctx.revaultd.clone();
-        Command::perform(
-            async move { revaultd.get_history(&ALL_HISTORY_EVENTS, 0, t1, HISTORY_EVENT_PAGE_SIZE) },
-            Message::HistoryEvents,
-        )
+        HistoryFilter::default().fetch(ctx)
     }
 }
 
@@ -315,8 +514,248 @@ impl HistoryEventState {
         let revaultd = ctx.revaultd.clone();
         let vaults = self.event.vaults.clone();
         Command::perform(
-            async move { revaultd.list_onchain_transactions(vaults.as_ref()) },
+            async move { revaultd.list_onchain_transactions(vaults.as_ref()).await },
             |msg| Message::HistoryEvent(HistoryEventMessage::OnChainTransactions(msg)),
         )
     }
 }
+
+/// Export in progress, the history is fetched page by page from the end
+/// of the date range to its start.
+#[derive(Debug)]
+struct HistoryExport {
+    kinds: Vec<HistoryEventKind>,
+    start: u32,
+    end: u32,
+    cursor: u32,
+    limit: u64,
+    events: Vec<HistoryEvent>,
+    format: ExportFormat,
+    path: PathBuf,
+    writing: bool,
+}
+
+impl HistoryExport {
+    fn progress(&self) -> f32 {
+        if self.writing || self.end <= self.start {
+            return 1.0;
+        }
+        (self.end - self.cursor) as f32 / (self.end - self.start) as f32
+    }
+
+    fn fetch(&self, ctx: &Context) -> Command<Message> {
+        let revaultd = ctx.revaultd.clone();
+        let kinds = self.kinds.clone();
+        let (start, cursor, limit) = (self.start, self.cursor, self.limit);
+        Command::perform(
+            async move { revaultd.get_history(&kinds, start, cursor, limit).await },
+            |res| Message::HistoryExport(HistoryExportMessage::HistoryEvents(res)),
+        )
+    }
+}
+
+/// HistoryExportState writes the history events of a date range
+/// to a CSV or JSON file.
+#[derive(Debug)]
+pub struct HistoryExportState {
+    from: form::Value<String>,
+    to: form::Value<String>,
+    kinds: Vec<HistoryEventKind>,
+    format: ExportFormat,
+    path: form::Value<String>,
+    export: Option<HistoryExport>,
+    /// Number of events written and file of the last export.
+    pub exported: Option<(usize, PathBuf)>,
+    warning: Option<Error>,
+    view: HistoryExportView,
+}
+
+impl HistoryExportState {
+    pub fn new() -> Self {
+        let mut path = dirs::home_dir().unwrap_or_default();
+        path.push("revault_history");
+        path.set_extension(ExportFormat::Csv.extension());
+        Self {
+            from: form::Value::default(),
+            to: form::Value::default(),
+            kinds: ALL_HISTORY_EVENTS.to_vec(),
+            format: ExportFormat::Csv,
+            path: form::Value {
+                value: path.to_string_lossy().to_string(),
+                valid: true,
+            },
+            export: None,
+            exported: None,
+            warning: None,
+            view: HistoryExportView::new(),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context, message: HistoryExportMessage) -> Command<Message> {
+        match message {
+            HistoryExportMessage::FromEdited(from) => {
+                self.from.value = from;
+                self.from.valid = true;
+            }
+            HistoryExportMessage::ToEdited(to) => {
+                self.to.value = to;
+                self.to.valid = true;
+            }
+            HistoryExportMessage::KindToggled(kind, checked) => {
+                self.kinds.retain(|k| *k != kind);
+                if checked {
+                    self.kinds.push(kind);
+                }
+            }
+            HistoryExportMessage::FormatSelected(format) => {
+                let mut path = PathBuf::from(&self.path.value);
+                if path.extension().and_then(|ext| ext.to_str()) == Some(self.format.extension()) {
+                    path.set_extension(format.extension());
+                    self.path.value = path.to_string_lossy().to_string();
+                }
+                self.format = format;
+            }
+            HistoryExportMessage::PathEdited(path) => {
+                self.path.value = path;
+                self.path.valid = true;
+            }
+            HistoryExportMessage::Start => {
+                if self.export.is_some() {
+                    return Command::none();
+                }
+                return self.start(ctx);
+            }
+            HistoryExportMessage::HistoryEvents(res) => {
+                if let Some(export) = &mut self.export {
+                    match res {
+                        Ok(events) => {
+                            let full = events.len() as u64 == export.limit;
+                            let oldest = events.last().map(|evt| evt.date);
+                            merge_history_page(&mut export.events, events);
+                            match oldest {
+                                Some(oldest) if full => {
+                                    // gethistory cursor is inclusive and uses the blocktime,
+                                    // if all the events of the page are in the same block,
+                                    // the next ones can only be retrieved with a larger limit.
+                                    if oldest < export.cursor {
+                                        export.cursor = oldest;
+                                        export.limit = EXPORT_PAGE_SIZE;
+                                    } else {
+                                        export.limit += EXPORT_PAGE_SIZE;
+                                    }
+                                    return export.fetch(ctx);
+                                }
+                                _ => {
+                                    export.writing = true;
+                                    let path = export.path.clone();
+                                    let format = export.format;
+                                    let events = export.events.clone();
+                                    return Command::perform(
+                                        async move {
+                                            write_history(&path, format, &events)
+                                                .map_err(Error::Unexpected)
+                                        },
+                                        |res| {
+                                            Message::HistoryExport(HistoryExportMessage::Written(
+                                                res,
+                                            ))
+                                        },
+                                    );
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            self.export = None;
+                            self.warning = Some(e.into());
+                        }
+                    }
+                }
+            }
+            HistoryExportMessage::Written(res) => {
+                if let Some(export) = self.export.take() {
+                    match res {
+                        Ok(()) => self.exported = Some((export.events.len(), export.path)),
+                        Err(e) => self.warning = Some(e),
+                    }
+                }
+            }
+        };
+        Command::none()
+    }
+
+    fn start(&mut self, ctx: &Context) -> Command<Message> {
+        self.warning = None;
+        self.exported = None;
+
+        let start = if self.from.value.is_empty() {
+            Some(0)
+        } else {
+            parse_date(&self.from.value, false)
+        };
+        let end = if self.to.value.is_empty() {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .and_then(|d| d.as_secs().try_into().ok())
+        } else {
+            parse_date(&self.to.value, true)
+        };
+        self.from.valid = start.is_some();
+        self.to.valid = match (start, end) {
+            (Some(start), Some(end)) => start <= end,
+            (_, end) => end.is_some(),
+        };
+        self.path.valid = !self.path.value.is_empty();
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) if self.to.valid && self.path.valid => (start, end),
+            _ => return Command::none(),
+        };
+
+        if self.kinds.is_empty() {
+            return Command::none();
+        }
+
+        let export = HistoryExport {
+            kinds: self.kinds.clone(),
+            start,
+            end,
+            cursor: end,
+            limit: EXPORT_PAGE_SIZE,
+            events: Vec::new(),
+            format: self.format,
+            path: PathBuf::from(&self.path.value),
+            writing: false,
+        };
+        let cmd = export.fetch(ctx);
+        self.export = Some(export);
+        cmd
+    }
+
+    pub fn view(&mut self, ctx: &Context) -> Element<Message> {
+        self.view.view(
+            ctx,
+            self.warning.as_ref(),
+            &self.from,
+            &self.to,
+            &self.kinds,
+            self.format,
+            &self.path,
+            self.export
+                .as_ref()
+                .map(|export| (export.progress(), export.events.len())),
+            self.exported.as_ref(),
+        )
+    }
+}
+
+/// Parses a YYYY-MM-DD date to the timestamp of the start of the day,
+/// or of its end if end_of_day is true.
+fn parse_date(date: &str, end_of_day: bool) -> Option<u32> {
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+    let datetime = if end_of_day {
+        date.and_hms(23, 59, 59)
+    } else {
+        date.and_hms(0, 0, 0)
+    };
+    datetime.timestamp().try_into().ok()
+}
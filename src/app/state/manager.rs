@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, HashMap};
 use std::convert::From;
 use std::convert::TryInto;
+use std::path::Path;
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -8,26 +9,40 @@ use bitcoin::{consensus::encode, util::psbt::PartiallySignedTransaction as Psbt,
 use iced::{Command, Element, Subscription};
 
 use super::{
-    cmd::{list_spend_txs, list_vaults, update_spend_tx},
+    cmd::{estimate_fees, list_spend_txs, list_vaults, update_spend_tx},
     vault::{Vault, VaultListItem},
     State,
 };
 
-use crate::daemon::model::{
-    self, outpoint, SpendTx, SpendTxStatus, VaultStatus, ALL_HISTORY_EVENTS, ALL_SPEND_TX_STATUSES,
-    CURRENT_VAULT_STATUSES,
+use crate::daemon::{
+    bitcoind::BitcoinD,
+    model::{
+        self, outpoint, SpendTx, SpendTxStatus, VaultStatus, ALL_HISTORY_EVENTS,
+        ALL_SPEND_TX_STATUSES, CURRENT_VAULT_STATUSES,
+    },
 };
 
+use crate::conversion::Converter;
+
 use revault_ui::component::form;
-use revaultd::revault_tx::transactions::{RevaultTransaction, SpendTransaction};
+use revaultd::revault_tx::{
+    miniscript::DescriptorPublicKey,
+    transactions::{RevaultTransaction, SpendTransaction},
+};
 
 use crate::app::{
+    coin_selection::{self, Candidate, Selection, SelectionStrategy},
     context::Context,
     error::Error,
-    message::{InputMessage, Message, RecipientMessage, SpendTxMessage},
+    fee::{spend_tx_vsize, FeeEstimates, FeeratePreset},
+    inspector::PsbtInspection,
+    label::LabelKind,
+    message::{InputMessage, LabelMessage, Message, RecipientMessage, SpendTxMessage},
+    payouts::{self, Payout, PayoutsImport},
     state::{
         history::{HistoryEventListItemState, HistoryEventState},
         sign::{Signer, SpendTransactionTarget},
+        trend::TrendState,
         SpendTransactionListItem, SpendTransactionState,
     },
     view::{
@@ -39,6 +54,7 @@ use crate::app::{
         vault::VaultListItemView,
         LoadingDashboard, ManagerHomeView,
     },
+    watcher::WatcherEvent,
 };
 
 #[derive(Debug)]
@@ -61,6 +77,7 @@ pub enum ManagerHomeState {
 
         latest_events: Vec<HistoryEventListItemState>,
         selected_event: Option<HistoryEventState>,
+        trend: TrendState,
     },
 }
 
@@ -118,6 +135,7 @@ impl ManagerHomeState {
                     latest_events: Vec::new(),
                     selected_vault: None,
                     selected_event: None,
+                    trend: TrendState::new(),
                     view: ManagerHomeView::default(),
                 }
             }
@@ -143,11 +161,19 @@ impl ManagerHomeState {
             .unwrap();
         let revaultd = ctx.revaultd.clone();
         Command::perform(
-            async move { revaultd.get_history(&ALL_HISTORY_EVENTS, 0, now, 5) },
+            async move { revaultd.get_history(&ALL_HISTORY_EVENTS, 0, now, 5).await },
             Message::HistoryEvents,
         )
     }
 
+    fn load_trend(&mut self, ctx: &Context) -> Command<Message> {
+        if let Self::Loaded { trend, .. } = self {
+            trend.refresh(ctx)
+        } else {
+            Command::none()
+        }
+    }
+
     fn load_vaults(ctx: &Context) -> Command<Message> {
         Command::perform(
             list_vaults(ctx.revaultd.clone(), Some(&CURRENT_VAULT_STATUSES), None),
@@ -164,7 +190,10 @@ impl State for ManagerHomeState {
                     match res {
                         Ok(vaults) => {
                             self.update_vaults(vaults);
-                            return Command::batch(vec![Self::load_history(ctx)]);
+                            return Command::batch(vec![
+                                Self::load_history(ctx),
+                                self.load_trend(ctx),
+                            ]);
                         }
                         Err(e) => *fail = Some(e.into()),
                     }
@@ -176,15 +205,22 @@ impl State for ManagerHomeState {
                 selected_event,
                 latest_events,
                 spending_vaults,
+                trend,
                 ..
             } => match message {
                 Message::Reload => {
                     return self.load(ctx);
                 }
+                Message::Trend(msg) => return trend.update(ctx, msg),
+                Message::Watcher(Ok(events)) => {
+                    if events.iter().any(WatcherEvent::is_vault_change) {
+                        return Self::load_vaults(ctx);
+                    }
+                }
                 Message::Vaults(res) => match res {
                     Ok(vaults) => {
                         self.update_vaults(vaults);
-                        return Command::batch(vec![Self::load_history(ctx)]);
+                        return Command::batch(vec![Self::load_history(ctx), self.load_trend(ctx)]);
                     }
                     Err(e) => *warning = Error::from(e).into(),
                 },
@@ -204,6 +240,7 @@ impl State for ManagerHomeState {
                         return selected.update(ctx, msg).map(Message::Vault);
                     }
                 }
+                Message::Label(LabelMessage::Written(Err(e))) => *warning = Some(e),
                 Message::HistoryEvents(res) => match res {
                     Ok(events) => {
                         *latest_events = events
@@ -253,6 +290,7 @@ impl State for ManagerHomeState {
                 spending_vaults,
                 latest_events,
                 balance,
+                trend,
                 view,
                 ..
             } => {
@@ -264,9 +302,11 @@ impl State for ManagerHomeState {
                     return v.view(ctx);
                 }
 
+                let total = balance.values().map(|(_, amount)| amount).sum();
                 view.view(
                     ctx,
                     warning.as_ref(),
+                    trend.view(ctx, total),
                     spending_vaults.iter_mut().map(|v| v.view(ctx)).collect(),
                     latest_events
                         .iter_mut()
@@ -349,6 +389,17 @@ impl State for ManagerSendState {
                         Message::SpendTransactions,
                     );
                 }
+                Message::Watcher(Ok(events)) => {
+                    if let Some(tx) = selected_tx {
+                        return tx.update(ctx, Message::Watcher(Ok(events)));
+                    }
+                    if events.iter().any(WatcherEvent::is_spend_tx_change) {
+                        return Command::perform(
+                            list_spend_txs(ctx.revaultd.clone(), Some(txs_status_filter)),
+                            Message::SpendTransactions,
+                        );
+                    }
+                }
                 Message::SpendTransactions(res) => match res {
                     Ok(transactions) => {
                         *txs = transactions
@@ -424,7 +475,7 @@ impl State for ManagerSendState {
     fn load(&self, ctx: &Context) -> Command<Message> {
         let revaultd = ctx.revaultd.clone();
         Command::perform(
-            async move { revaultd.list_spend_txs(Some(&ALL_SPEND_TX_STATUSES)) },
+            async move { revaultd.list_spend_txs(Some(&ALL_SPEND_TX_STATUSES)).await },
             Message::SpendTransactions,
         )
     }
@@ -521,6 +572,9 @@ enum ManagerSendStep {
     SelectInputs(ManagerSelectInputsView),
     Sign {
         signer: Signer<SpendTransactionTarget>,
+        /// Inspection of the generated psbt, which does not change until
+        /// it is signed.
+        inspection: PsbtInspection,
         view: ManagerStepSignView,
     },
     Success(ManagerSpendTransactionCreatedView),
@@ -533,8 +587,13 @@ pub struct ManagerCreateSendTransactionState {
     inputs: Vec<ManagerSendInput>,
     outputs: Vec<ManagerSendOutput>,
     feerate: Option<u64>,
+    feerate_preset: Option<FeeratePreset>,
+    fee_estimates: Option<FeeEstimates>,
     processing: bool,
     valid_feerate: bool,
+    auto_selection: Option<AutoSelection>,
+    recipients_path: form::Value<String>,
+    recipients_import: Option<Result<PayoutsImport, String>>,
 
     tx: Option<(SpendTx, u64)>,
 
@@ -549,9 +608,39 @@ impl ManagerCreateSendTransactionState {
             inputs: Vec::new(),
             outputs: vec![ManagerSendOutput::new()],
             feerate: None,
+            feerate_preset: None,
+            fee_estimates: None,
             tx: None,
             processing: false,
             valid_feerate: false,
+            auto_selection: None,
+            recipients_path: form::Value::default(),
+            recipients_import: None,
+        }
+    }
+
+    /// Adds the imported payouts to the recipients, in place of the empty
+    /// ones. The payouts to an address which is already a recipient are
+    /// moved to the errors of the import.
+    pub fn import_recipients(&mut self, converter: &Converter, import: &mut PayoutsImport) {
+        self.outputs
+            .retain(|output| !output.address.value.is_empty() || !output.amount.value.is_empty());
+        let payouts = std::mem::take(&mut import.payouts);
+        for payout in payouts {
+            let address = payout.address.to_string();
+            if self.outputs.iter().any(|o| o.address.value == address) {
+                import
+                    .errors
+                    .push((payout.line, format!("{} is already a recipient", address)));
+            } else {
+                self.outputs
+                    .push(ManagerSendOutput::from_payout(converter, &payout));
+                import.payouts.push(payout);
+            }
+        }
+        import.errors.sort_by_key(|(line, _)| *line);
+        if self.outputs.is_empty() {
+            self.outputs.push(ManagerSendOutput::new());
         }
     }
 
@@ -574,10 +663,10 @@ impl ManagerCreateSendTransactionState {
         input_amount
     }
 
-    pub fn output_amount(&self) -> u64 {
+    pub fn output_amount(&self, converter: &Converter) -> u64 {
         let mut output_amount = 0;
         for output in &self.outputs {
-            if let Ok(amount) = output.amount() {
+            if let Ok(amount) = output.amount(converter) {
                 output_amount += amount;
             }
         }
@@ -598,6 +687,58 @@ impl ManagerCreateSendTransactionState {
             .collect()
     }
 
+    /// Estimated virtual size of the spend transaction with the selected inputs,
+    /// or a single one if none is selected yet.
+    pub fn estimated_vsize(&self, ctx: &Context) -> u64 {
+        let inputs = self.inputs.iter().filter(|input| input.selected).count();
+        self.vsize_with_inputs(ctx, std::cmp::max(inputs, 1))
+    }
+
+    fn vsize_with_inputs(&self, ctx: &Context, inputs: usize) -> u64 {
+        let unvault_keys = ctx.config.daemon.scripts_config.unvault_descriptor.xpubs();
+        let cosigners = unvault_keys
+            .iter()
+            .filter(|key| matches!(key, DescriptorPublicKey::SinglePub(_)))
+            .count();
+        // The recipients, the CPFP output and the change output.
+        let outputs = self.outputs.len() + 2;
+        spend_tx_vsize(
+            inputs,
+            outputs,
+            unvault_keys.len(),
+            ctx.managers_threshold + cosigners,
+        )
+    }
+
+    /// Selects the inputs covering the recipients and the fee with the
+    /// strategy, the selection is left untouched if the vaults do not cover it.
+    pub fn select_inputs(&mut self, ctx: &Context, strategy: SelectionStrategy) {
+        let candidates: Vec<Candidate> = self
+            .inputs
+            .iter()
+            .map(|input| Candidate {
+                amount: input.unvault_output_amount.as_sat(),
+                blockheight: input.vault.blockheight,
+            })
+            .collect();
+        let feerate = self.feerate.unwrap_or(0);
+        let output_amount = self.output_amount(&ctx.converter);
+        let selection = coin_selection::select(strategy, &candidates, output_amount, |n| {
+            feerate * self.vsize_with_inputs(ctx, n)
+        });
+        if let Some(selection) = &selection {
+            self.tx = None;
+            for (i, input) in self.inputs.iter_mut().enumerate() {
+                input.selected = selection.indexes.contains(&i);
+            }
+        }
+        self.auto_selection = Some(AutoSelection {
+            strategy,
+            selection,
+            adjusted: false,
+        });
+    }
+
     // TODO: remove it for subscription
     // It was introduced because of difficulties with the trait type inference.
     pub fn sub(&self) -> Subscription<Message> {
@@ -634,7 +775,7 @@ impl State for ManagerCreateSendTransactionState {
                     .map(|output| {
                         (
                             bitcoin::Address::from_str(&output.address.value).unwrap(),
-                            output.amount().unwrap(),
+                            output.amount(&ctx.converter).unwrap(),
                         )
                     })
                     .collect();
@@ -643,13 +784,60 @@ impl State for ManagerCreateSendTransactionState {
                 let feerate = self.feerate.unwrap_or(0);
                 return Command::perform(
                     async move {
-                        let resp = revaultd.get_spend_tx(inputs.as_slice(), &outputs, feerate)?;
+                        let resp = revaultd
+                            .get_spend_tx(inputs.as_slice(), &outputs, feerate)
+                            .await?;
                         Ok((resp, feerate))
                     },
                     Message::SpendTransaction,
                 );
             }
+            Message::SpendTx(SpendTxMessage::FeeEstimates(res)) => match res {
+                Ok(estimates) => {
+                    if self.feerate.is_none() {
+                        if let Some(normal) = estimates.normal {
+                            self.feerate = Some(normal);
+                            self.feerate_preset = Some(FeeratePreset::Normal);
+                            self.valid_feerate = true;
+                        }
+                    }
+                    self.fee_estimates = Some(estimates);
+                }
+                // The feerate can still be set manually.
+                Err(e) => log::warn!("Failed to estimate fees: {}", e),
+            },
+            Message::SpendTx(SpendTxMessage::FeeratePresetSelected(preset)) => {
+                if let Some(f) = self
+                    .fee_estimates
+                    .as_ref()
+                    .and_then(|estimates| estimates.get(preset))
+                {
+                    self.feerate = Some(f);
+                    self.feerate_preset = Some(preset);
+                    self.valid_feerate = true;
+                }
+            }
+            Message::SpendTx(SpendTxMessage::SelectionStrategySelected(strategy)) => {
+                self.select_inputs(ctx, strategy);
+            }
+            Message::SpendTx(SpendTxMessage::RecipientsPathEdited(path)) => {
+                self.recipients_path.value = path;
+                self.recipients_path.valid = true;
+            }
+            Message::SpendTx(SpendTxMessage::ImportRecipients) => {
+                self.recipients_path.valid = !self.recipients_path.value.is_empty();
+                if self.recipients_path.valid {
+                    self.tx = None;
+                    let mut res =
+                        payouts::read(Path::new(&self.recipients_path.value), ctx.network());
+                    if let Ok(import) = &mut res {
+                        self.import_recipients(&ctx.converter, import);
+                    }
+                    self.recipients_import = Some(res);
+                }
+            }
             Message::SpendTx(SpendTxMessage::FeerateEdited(feerate)) => {
+                self.feerate_preset = None;
                 if let Ok(f) = feerate.parse::<u64>() {
                     self.feerate = Some(f);
                     self.valid_feerate = true;
@@ -708,11 +896,25 @@ impl State for ManagerCreateSendTransactionState {
                                     .collect(),
                                 tx.psbt.clone().into_psbt(),
                             )),
+                            inspection: ctx.inspect(tx.psbt.psbt()),
                             view: ManagerStepSignView::new(),
                         };
                     }
                 }
                 ManagerSendStep::SelectFee(_) => {
+                    // The recipients or the feerate may have changed since the
+                    // last automatic selection, it is done again unless the
+                    // manager adjusted it.
+                    match &self.auto_selection {
+                        Some(auto) if !auto.adjusted => {
+                            let strategy = auto.strategy;
+                            self.select_inputs(ctx, strategy);
+                        }
+                        None if !self.inputs.iter().any(|input| input.selected) => {
+                            self.select_inputs(ctx, SelectionStrategy::FewestInputs);
+                        }
+                        _ => {}
+                    }
                     self.step = ManagerSendStep::SelectInputs(ManagerSelectInputsView::new());
                 }
                 _ => (),
@@ -744,11 +946,25 @@ impl State for ManagerCreateSendTransactionState {
                 if let Some(input) = self.inputs.get_mut(i) {
                     input.update(msg);
                 }
+                if let Some(auto) = &mut self.auto_selection {
+                    auto.adjusted = true;
+                }
+            }
+            Message::Recipient(i, RecipientMessage::SaveLabel) => {
+                if let Some(output) = self.outputs.get_mut(i) {
+                    if let Some(label) = output.label.take() {
+                        let address = output.address.value.clone();
+                        return Command::perform(
+                            async move { LabelMessage::Save(LabelKind::Addr, address, label) },
+                            Message::Label,
+                        );
+                    }
+                }
             }
             Message::Recipient(i, msg) => {
                 self.tx = None;
                 if let Some(output) = self.outputs.get_mut(i) {
-                    output.update(msg);
+                    output.update(&ctx.converter, msg);
                 }
             }
             _ => {}
@@ -763,7 +979,8 @@ impl State for ManagerCreateSendTransactionState {
     fn view(&mut self, ctx: &Context) -> Element<Message> {
         let selected_inputs = self.selected_inputs();
         let input_amount = self.input_amount();
-        let output_amount = self.output_amount();
+        let output_amount = self.output_amount(&ctx.converter);
+        let estimated_vsize = self.estimated_vsize(ctx);
         match &mut self.step {
             ManagerSendStep::SelectOutputs(v) => {
                 let mut valid =
@@ -778,11 +995,14 @@ impl State for ManagerCreateSendTransactionState {
                     }
                 }
                 v.view(
+                    ctx,
                     self.outputs
                         .iter_mut()
                         .enumerate()
-                        .map(|(i, v)| v.view().map(move |msg| Message::Recipient(i, msg)))
+                        .map(|(i, v)| v.view(ctx).map(move |msg| Message::Recipient(i, msg)))
                         .collect(),
+                    &self.recipients_path,
+                    self.recipients_import.as_ref(),
                     valid,
                     no_duplicate,
                 )
@@ -796,17 +1016,37 @@ impl State for ManagerCreateSendTransactionState {
                     .collect(),
                 input_amount,
                 output_amount,
+                self.feerate.map(|f| f * estimated_vsize),
+                estimated_vsize,
+                self.auto_selection
+                    .as_ref()
+                    .map(|auto| (auto.strategy, auto.selection.as_ref(), auto.adjusted)),
                 self.warning.as_ref(),
             ),
-            ManagerSendStep::SelectFee(v) => {
-                v.view(self.feerate, self.valid_feerate, self.warning.as_ref())
-            }
-            ManagerSendStep::Sign { signer, view } => {
+            ManagerSendStep::SelectFee(v) => v.view(
+                ctx,
+                self.feerate,
+                self.valid_feerate,
+                self.feerate_preset,
+                self.fee_estimates.as_ref(),
+                estimated_vsize,
+                self.fee_estimates
+                    .as_ref()
+                    .zip(self.feerate)
+                    .and_then(|(estimates, f)| estimates.sanity_warning(f)),
+                self.warning.as_ref(),
+            ),
+            ManagerSendStep::Sign {
+                signer,
+                inspection,
+                view,
+            } => {
                 let (tx, feerate) = self.tx.as_ref().unwrap();
                 view.view(
                     ctx,
                     &selected_inputs,
                     &tx,
+                    inspection,
                     &feerate,
                     signer.error.clone().as_ref(),
                     signer
@@ -823,32 +1063,40 @@ impl State for ManagerCreateSendTransactionState {
 
     fn load(&self, ctx: &Context) -> Command<Message> {
         let revaultd = ctx.revaultd.clone();
-        Command::batch(vec![Command::perform(
-            async move {
-                let vaults = revaultd.list_vaults(Some(&[VaultStatus::Active]), None)?;
-                let outpoints: Vec<OutPoint> =
-                    vaults.iter().map(|vault| outpoint(&vault)).collect();
-                let txs = revaultd.list_presigned_transactions(&outpoints)?;
-                let vaults_with_txs = vaults
-                    .into_iter()
-                    .map(|vault| {
-                        let tx = txs
-                            .iter()
-                            .find_map(|txs| {
-                                if txs.vault_outpoint == outpoint(&vault) {
-                                    Some(txs.unvault.clone().into_psbt())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap();
-                        (vault, tx)
-                    })
-                    .collect();
-                Ok(vaults_with_txs)
-            },
-            Message::VaultsWithUnvaultTx,
-        )])
+        Command::batch(vec![
+            Command::perform(
+                async move {
+                    let vaults = revaultd
+                        .list_vaults(Some(&[VaultStatus::Active]), None)
+                        .await?;
+                    let outpoints: Vec<OutPoint> =
+                        vaults.iter().map(|vault| outpoint(&vault)).collect();
+                    let txs = revaultd.list_presigned_transactions(&outpoints).await?;
+                    let vaults_with_txs = vaults
+                        .into_iter()
+                        .map(|vault| {
+                            let tx = txs
+                                .iter()
+                                .find_map(|txs| {
+                                    if txs.vault_outpoint == outpoint(&vault) {
+                                        Some(txs.unvault.clone().into_psbt())
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .unwrap();
+                            (vault, tx)
+                        })
+                        .collect();
+                    Ok(vaults_with_txs)
+                },
+                Message::VaultsWithUnvaultTx,
+            ),
+            Command::perform(
+                estimate_fees(BitcoinD::new(&ctx.config.daemon.bitcoind_config)),
+                |res| Message::SpendTx(SpendTxMessage::FeeEstimates(res)),
+            ),
+        ])
     }
 }
 
@@ -862,6 +1110,8 @@ impl From<ManagerCreateSendTransactionState> for Box<dyn State> {
 struct ManagerSendOutput {
     address: form::Value<String>,
     amount: form::Value<String>,
+    /// Label of the address being edited, the saved one is displayed if None.
+    label: Option<String>,
 
     view: ManagerSendOutputView,
 }
@@ -871,18 +1121,35 @@ impl ManagerSendOutput {
         Self {
             address: form::Value::default(),
             amount: form::Value::default(),
+            label: None,
             view: ManagerSendOutputView::new(),
         }
     }
 
-    fn amount(&self) -> Result<u64, Error> {
+    /// The amount of the payout is written in the selected unit.
+    fn from_payout(converter: &Converter, payout: &Payout) -> Self {
+        let mut output = Self::new();
+        output.update(
+            converter,
+            RecipientMessage::AddressEdited(payout.address.to_string()),
+        );
+        output.update(
+            converter,
+            RecipientMessage::AmountEdited(converter.converts(payout.amount)),
+        );
+        output.label = payout.label.clone();
+        output
+    }
+
+    /// The amount is written in the selected unit.
+    fn amount(&self, converter: &Converter) -> Result<u64, Error> {
         if self.amount.value.is_empty() {
             return Err(Error::Unexpected("Amount should be non-zero".to_string()));
         }
 
-        let amount =
-            bitcoin::Amount::from_str_in(&self.amount.value, bitcoin::Denomination::Bitcoin)
-                .map_err(|_| Error::Unexpected("cannot parse output amount".to_string()))?;
+        let amount = converter
+            .parse(&self.amount.value)
+            .ok_or_else(|| Error::Unexpected("cannot parse output amount".to_string()))?;
 
         if amount.as_sat() == 0 {
             return Err(Error::Unexpected("Amount should be non-zero".to_string()));
@@ -906,9 +1173,11 @@ impl ManagerSendOutput {
             && self.amount.valid
     }
 
-    fn update(&mut self, message: RecipientMessage) {
+    fn update(&mut self, converter: &Converter, message: RecipientMessage) {
         match message {
-            RecipientMessage::AddressEdited(address) => {
+            RecipientMessage::AddressEdited(address)
+            | RecipientMessage::AddressSelected(address) => {
+                self.label = None;
                 self.address.value = address;
                 if self.address.value.is_empty() {
                     // Make the error disappear if we deleted the invalid address
@@ -916,16 +1185,17 @@ impl ManagerSendOutput {
                 } else if bitcoin::Address::from_str(&self.address.value).is_ok() {
                     self.address.valid = true;
                     if !self.amount.value.is_empty() {
-                        self.amount.valid = self.amount().is_ok();
+                        self.amount.valid = self.amount(converter).is_ok();
                     }
                 } else {
                     self.address.valid = false;
                 }
             }
+            RecipientMessage::LabelEdited(label) => self.label = Some(label),
             RecipientMessage::AmountEdited(amount) => {
                 self.amount.value = amount;
                 if !self.amount.value.is_empty() {
-                    self.amount.valid = self.amount().is_ok();
+                    self.amount.valid = self.amount(converter).is_ok();
                 } else {
                     // Make the error disappear if we deleted the invalid amount
                     self.amount.valid = true;
@@ -935,11 +1205,43 @@ impl ManagerSendOutput {
         };
     }
 
-    fn view(&mut self) -> Element<RecipientMessage> {
-        self.view.view(&self.address, &self.amount)
+    fn view(&mut self, ctx: &Context) -> Element<RecipientMessage> {
+        let address = bitcoin::Address::from_str(&self.address.value).ok();
+        // The label can only be given to a valid address, which is then no
+        // longer completed with the saved ones.
+        let (label, suggestions) = match &address {
+            Some(address) => {
+                let saved = ctx.labels.address(address);
+                (
+                    Some((
+                        self.label.as_deref().or(saved).unwrap_or_default(),
+                        self.label.is_some() && self.label.as_deref() != saved,
+                    )),
+                    Vec::new(),
+                )
+            }
+            None => (None, ctx.labels.suggest_addresses(&self.address.value)),
+        };
+        self.view.view(
+            &self.address,
+            &self.amount,
+            ctx.converter.unit,
+            label,
+            &suggestions,
+        )
     }
 }
 
+/// AutoSelection is the last selection of the inputs made with a strategy.
+#[derive(Debug)]
+struct AutoSelection {
+    strategy: SelectionStrategy,
+    /// None if the vaults do not cover the recipients and the fee.
+    selection: Option<Selection>,
+    /// The manager changed the selection by hand.
+    adjusted: bool,
+}
+
 #[derive(Debug, Clone)]
 struct ManagerSendInput {
     vault: model::Vault,
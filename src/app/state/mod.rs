@@ -1,18 +1,22 @@
+mod alerts;
 pub mod cmd;
 mod deposit;
 mod emergency;
 pub mod history;
 pub mod manager;
+mod notifications;
 mod revault;
 mod settings;
 mod sign;
 mod spend_transaction;
 pub mod stakeholder;
+mod trend;
 mod vault;
 mod vaults;
 
 use iced::{Command, Element, Subscription};
 
+pub use alerts::UnvaultAlertsState;
 pub use deposit::DepositState;
 pub use emergency::EmergencyState;
 pub use history::HistoryState;
@@ -20,6 +24,7 @@ pub use manager::{
     ManagerCreateSendTransactionState, ManagerHomeState, ManagerImportSendTransactionState,
     ManagerSendState,
 };
+pub use notifications::NotificationsState;
 pub use revault::RevaultVaultsState;
 pub use settings::SettingsState;
 pub use spend_transaction::{SpendTransactionListItem, SpendTransactionState};
@@ -0,0 +1,34 @@
+use iced::{Command, Element};
+
+use super::State;
+
+use crate::app::{context::Context, message::Message, view::NotificationsView};
+
+/// NotificationsState displays the notification center, the notifications
+/// are kept by the context and dismissed by the application.
+#[derive(Debug, Default)]
+pub struct NotificationsState {
+    view: NotificationsView,
+}
+
+impl NotificationsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl State for NotificationsState {
+    fn update(&mut self, _ctx: &Context, _message: Message) -> Command<Message> {
+        Command::none()
+    }
+
+    fn view(&mut self, ctx: &Context) -> Element<Message> {
+        self.view.view(ctx)
+    }
+}
+
+impl From<NotificationsState> for Box<dyn State> {
+    fn from(s: NotificationsState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
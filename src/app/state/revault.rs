@@ -6,9 +6,11 @@ use crate::{
     app::{
         context::Context,
         error::Error,
+        inspector::PsbtInspection,
         menu::Menu,
         message::Message,
         state::State,
+        verify::TransactionRebuilder,
         view::{
             LoadingModal, RevaultSelectVaultsView, RevaultSuccessView, RevaultVaultListItemView,
         },
@@ -53,7 +55,10 @@ impl State for RevaultVaultsState {
                         Ok(vaults) => {
                             *self = Self::SelectVaults {
                                 total: vaults.iter().map(|v| v.amount.as_sat()).sum::<u64>(),
-                                vaults: vaults.into_iter().map(RevaultVaultListItem::new).collect(),
+                                vaults: vaults
+                                    .into_iter()
+                                    .map(|vault| RevaultVaultListItem::new(ctx, vault))
+                                    .collect(),
                                 view: RevaultSelectVaultsView::default(),
                                 warning: None,
                                 processing: false,
@@ -94,7 +99,7 @@ impl State for RevaultVaultsState {
                     return Command::perform(
                         async move {
                             for outpoint in outpoints {
-                                revaultd.revault(&outpoint)?;
+                                revaultd.revault(&outpoint).await?;
                             }
                             Ok(())
                         },
@@ -156,10 +161,12 @@ impl State for RevaultVaultsState {
         let revaultd = ctx.revaultd.clone();
         Command::perform(
             async move {
-                revaultd.list_vaults(
-                    Some(&[VaultStatus::Unvaulting, VaultStatus::Unvaulted]),
-                    None,
-                )
+                revaultd
+                    .list_vaults(
+                        Some(&[VaultStatus::Unvaulting, VaultStatus::Unvaulted]),
+                        None,
+                    )
+                    .await
             },
             Message::Vaults,
         )
@@ -175,14 +182,25 @@ impl From<RevaultVaultsState> for Box<dyn State> {
 #[derive(Debug)]
 pub struct RevaultVaultListItem {
     vault: Vault,
+    /// Lowest feerate cancel transaction, rebuilt from the descriptors.
+    cancel_tx: Option<PsbtInspection>,
     selected: bool,
     view: RevaultVaultListItemView,
 }
 
 impl RevaultVaultListItem {
-    pub fn new(vault: Vault) -> Self {
+    pub fn new(ctx: &Context, vault: Vault) -> Self {
+        let cancel_tx =
+            match TransactionRebuilder::from_config(&ctx.config.daemon).cancel_txs(&vault) {
+                Ok(cancel_txs) => Some(ctx.inspect(&cancel_txs[0])),
+                Err(e) => {
+                    log::warn!("{}", e);
+                    None
+                }
+            };
         Self {
             vault,
+            cancel_tx,
             selected: false,
             view: RevaultVaultListItemView::default(),
         }
@@ -193,6 +211,7 @@ impl RevaultVaultListItem {
     }
 
     pub fn view(&mut self, ctx: &Context) -> Element<Message> {
-        self.view.view(ctx, &self.vault, self.selected)
+        self.view
+            .view(ctx, &self.vault, self.cancel_tx.as_ref(), self.selected)
     }
 }
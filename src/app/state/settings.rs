@@ -13,7 +13,7 @@ use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{Message, SettingsMessage},
+        message::{LabelMessage, Message, NotificationMessage, SettingsMessage},
         state::cmd::get_server_status,
         state::State,
         view::settings::*,
@@ -42,6 +42,12 @@ pub struct SettingsState {
 
     settings: Vec<Box<dyn Setting>>,
     current: Option<usize>,
+
+    /// File the labels are imported from or exported to.
+    labels_path: form::Value<String>,
+    /// True if the last labels operation is an import.
+    labels_import: bool,
+    labels_written: Option<usize>,
 }
 
 impl SettingsState {
@@ -69,6 +75,9 @@ impl SettingsState {
             config_updated: false,
             settings,
             current: None,
+            labels_path: form::Value::default(),
+            labels_import: false,
+            labels_written: None,
         }
     }
 }
@@ -106,6 +115,59 @@ impl State for SettingsState {
                     }
                 }
             },
+            Message::DisplayUnit(unit) => {
+                let mut gui_config = ctx.config.gui.clone();
+                gui_config.unit = Some(unit);
+                return Command::perform(async move { gui_config }, Message::LoadGuiConfig);
+            }
+            Message::Notification(NotificationMessage::KindToggled(kind, checked)) => {
+                let mut gui_config = ctx.config.gui.clone();
+                let mut notifications = gui_config.notifications();
+                notifications.kinds.retain(|k| *k != kind);
+                if checked {
+                    notifications.kinds.push(kind);
+                }
+                gui_config.notifications = Some(notifications);
+                return Command::perform(async move { gui_config }, Message::LoadGuiConfig);
+            }
+            Message::Notification(NotificationMessage::DesktopToggled(desktop)) => {
+                let mut gui_config = ctx.config.gui.clone();
+                let mut notifications = gui_config.notifications();
+                notifications.desktop = desktop;
+                gui_config.notifications = Some(notifications);
+                return Command::perform(async move { gui_config }, Message::LoadGuiConfig);
+            }
+            Message::GuiConfigLoaded(res) => {
+                if let Err(e) = res {
+                    self.warning = Some(e);
+                }
+            }
+            Message::Label(LabelMessage::PathEdited(path)) => {
+                self.labels_path.value = path;
+                self.labels_path.valid = true;
+            }
+            Message::Label(LabelMessage::Import) | Message::Label(LabelMessage::Export) => {
+                self.labels_path.valid = !self.labels_path.value.is_empty();
+                if !self.labels_path.valid {
+                    return Command::none();
+                }
+                self.labels_import = matches!(message, Message::Label(LabelMessage::Import));
+                self.labels_written = None;
+                let path = PathBuf::from(&self.labels_path.value);
+                return if self.labels_import {
+                    Command::perform(async move { path }, |path| {
+                        Message::Label(LabelMessage::ImportFrom(path))
+                    })
+                } else {
+                    Command::perform(async move { path }, |path| {
+                        Message::Label(LabelMessage::ExportTo(path))
+                    })
+                };
+            }
+            Message::Label(LabelMessage::Written(res)) => match res {
+                Ok(count) => self.labels_written = Some(count),
+                Err(e) => self.warning = Some(e),
+            },
             Message::Settings(i, SettingsMessage::Remove) => {
                 if Some(i) == self.current {
                     self.current = None;
@@ -145,10 +207,14 @@ impl State for SettingsState {
     fn view(&mut self, ctx: &Context) -> Element<Message> {
         let server_statuses = &self.server_statuses;
         let can_edit = self.current.is_none() && !ctx.revaultd.is_external();
+        let labels_import = self.labels_import;
+        let labels_written = self.labels_written.map(|count| (count, labels_import));
         self.view.view(
             ctx,
             self.warning.as_ref(),
             can_edit,
+            &self.labels_path,
+            labels_written,
             self.settings
                 .iter_mut()
                 .enumerate()
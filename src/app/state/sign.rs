@@ -12,13 +12,38 @@ use tokio::sync::Mutex;
 
 use iced::{time, Command, Element, Subscription};
 
-use revault_hwi::{app::revault::RevaultHWI, HWIError};
+use revault_hwi::{app::revault::RevaultHWI, DeviceDescriptor, HWIError};
 
 use crate::{
     app::{context::Context, error::Error, message::SignMessage, view::sign::SignerView},
     daemon::model::{outpoint, Vault},
 };
 
+/// Interval between two pings of the selected device.
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval between two enumerations of the devices while none is selected,
+/// an enumeration spawns hwi and opens the serial ports.
+const ENUMERATE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// TransactionTarget is a psbt the Signer asks the device to sign.
+pub trait TransactionTarget {
+    fn psbt(&self) -> &Psbt;
+
+    /// Returns the fingerprints of the keys expected to sign the psbt.
+    fn fingerprints(&self) -> Vec<Fingerprint> {
+        let mut fingerprints = Vec::new();
+        for input in &self.psbt().inputs {
+            for (fingerprint, _) in input.bip32_derivation.values() {
+                if !fingerprints.contains(fingerprint) {
+                    fingerprints.push(*fingerprint);
+                }
+            }
+        }
+        fingerprints
+    }
+}
+
 #[derive(Debug)]
 pub struct SpendTransactionTarget {
     pub spend_tx: Psbt,
@@ -42,6 +67,12 @@ impl SpendTransactionTarget {
     }
 }
 
+impl TransactionTarget for SpendTransactionTarget {
+    fn psbt(&self) -> &Psbt {
+        &self.spend_tx
+    }
+}
+
 #[derive(Debug)]
 pub struct Signer<T> {
     device: Device,
@@ -77,11 +108,15 @@ impl<T> Signer<T> {
             Subscription::none()
         }
     }
+}
 
+impl<T: TransactionTarget> Signer<T> {
     pub fn view(&mut self, ctx: &Context) -> Element<SignMessage> {
         self.view.view(
             ctx,
-            self.device.is_connected(),
+            self.device.available(),
+            self.device.selected(),
+            &self.target.fingerprints(),
             self.processing,
             self.signed,
         )
@@ -94,9 +129,7 @@ impl Signer<SpendTransactionTarget> {
             SignMessage::SelectSign => {
                 self.processing = true;
                 return Command::perform(
-                    self.device
-                        .clone()
-                        .sign_spend_tx(self.target.spend_tx.clone()),
+                    self.device.clone().sign_tx(self.target.psbt().clone()),
                     |tx| SignMessage::PsbtSigned(tx.map(Box::new)),
                 );
             }
@@ -140,25 +173,119 @@ impl Signer<SpendTransactionTarget> {
     }
 }
 
+/// CpfpTransactionTarget is the transaction bumping the feerate of a spend
+/// transaction, signed with a key of the CPFP descriptor.
+#[derive(Debug)]
+pub struct CpfpTransactionTarget {
+    pub cpfp_tx: Psbt,
+}
+
+impl CpfpTransactionTarget {
+    pub fn new(cpfp_tx: Psbt) -> Self {
+        Self { cpfp_tx }
+    }
+}
+
+impl TransactionTarget for CpfpTransactionTarget {
+    fn psbt(&self) -> &Psbt {
+        &self.cpfp_tx
+    }
+}
+
+impl Signer<CpfpTransactionTarget> {
+    pub fn update(&mut self, ctx: &Context, message: SignMessage) -> Command<SignMessage> {
+        match message {
+            SignMessage::SelectSign => {
+                self.processing = true;
+                return Command::perform(
+                    self.device.clone().sign_tx(self.target.psbt().clone()),
+                    |tx| SignMessage::PsbtSigned(tx.map(Box::new)),
+                );
+            }
+            SignMessage::PsbtSigned(res) => {
+                self.processing = false;
+                match res {
+                    Ok(tx) => {
+                        if tx.global.unsigned_tx.txid()
+                            == self.target.cpfp_tx.global.unsigned_tx.txid()
+                        {
+                            // The CPFP descriptor is a 1-of-N multisig.
+                            if tx.inputs.iter().any(|input| input.partial_sigs.is_empty()) {
+                                log::info!("Hardware wallet did not sign the cpfp tx");
+                                self.error = Some(HWIError::DeviceDidNotSign.into());
+                                return Command::none();
+                            }
+                            self.signed = true;
+                            self.target.cpfp_tx = *tx;
+                        }
+                    }
+                    Err(e) => {
+                        log::info!("{:?}", e);
+                        self.error = Some(e.into());
+                    }
+                }
+            }
+            _ => return self.device.update(&ctx, message),
+        };
+        Command::none()
+    }
+}
+
+/// Device keeps the list of reachable hardware wallets and the one selected
+/// by the user, either explicitly or because it holds one of the user keys.
+/// The first device is selected otherwise, as the flows without a device
+/// picker could not sign at all.
+/// The devices are enumerated again only once the selected one is disconnected.
 #[derive(Debug, Clone)]
 pub struct Device {
     channel: Option<Arc<Mutex<Box<dyn RevaultHWI + Send>>>>,
+    selected: Option<usize>,
+    available: Vec<DeviceDescriptor>,
+    channels: Vec<Arc<Mutex<Box<dyn RevaultHWI + Send>>>>,
+    /// An enumeration is running, the next one waits for its result.
+    enumerating: bool,
 }
 
 impl Device {
     pub fn new() -> Self {
-        Device { channel: None }
+        Device {
+            channel: None,
+            selected: None,
+            available: Vec::new(),
+            channels: Vec::new(),
+            enumerating: false,
+        }
     }
 
     pub fn is_connected(&self) -> bool {
         self.channel.is_some()
     }
 
+    pub fn available(&self) -> &[DeviceDescriptor] {
+        &self.available
+    }
+
+    pub fn selected(&self) -> Option<&DeviceDescriptor> {
+        self.selected.and_then(|i| self.available.get(i))
+    }
+
+    /// The device is looked up by its descriptor, the list may have been
+    /// enumerated again since it was displayed.
+    fn select(&mut self, device: &DeviceDescriptor) {
+        if let Some(i) = self.available.iter().position(|d| d == device) {
+            if let Some(channel) = self.channels.get(i) {
+                self.channel = Some(channel.clone());
+                self.selected = Some(i);
+            }
+        }
+    }
+
     pub fn update(&mut self, ctx: &Context, message: SignMessage) -> Command<SignMessage> {
         match message {
             SignMessage::Ping(res) => {
                 if res.is_err() {
                     self.channel = None;
+                    self.selected = None;
                 }
             }
             SignMessage::CheckConnection => {
@@ -167,21 +294,60 @@ impl Device {
                         async move { channel.lock().await.is_connected().await },
                         SignMessage::Ping,
                     );
-                } else {
-                    let connect = &ctx.hardware_wallet;
-                    return Command::perform(connect(), |res| {
-                        SignMessage::Connected(res.map(|channel| Arc::new(Mutex::new(channel))))
+                } else if !self.enumerating {
+                    // Previous connections are released before enumerating again,
+                    // a serial port cannot be opened twice.
+                    self.enumerating = true;
+                    self.channels.clear();
+                    let enumerate = &ctx.hardware_wallets;
+                    return Command::perform(enumerate(), |devices| {
+                        SignMessage::Devices(
+                            devices
+                                .into_iter()
+                                .map(|(descriptor, channel)| {
+                                    (descriptor, Arc::new(Mutex::new(channel)))
+                                })
+                                .collect(),
+                        )
                     });
                 }
             }
-            SignMessage::Connected(Ok(channel)) => self.channel = Some(channel),
+            SignMessage::Devices(devices) => {
+                self.enumerating = false;
+                if self.channel.is_some() {
+                    return Command::none();
+                }
+                let (available, channels) = devices.into_iter().unzip();
+                self.available = available;
+                self.channels = channels;
+                let fingerprints = ctx.user_fingerprints();
+                let device = self
+                    .available
+                    .iter()
+                    .find(|d| {
+                        d.fingerprint
+                            .map(|fg| fingerprints.contains(&fg))
+                            .unwrap_or(false)
+                    })
+                    .or_else(|| self.available.first())
+                    .cloned();
+                if let Some(device) = device {
+                    self.select(&device);
+                }
+            }
+            SignMessage::SelectDevice(device) => self.select(&device),
             _ => {}
         };
         Command::none()
     }
 
     pub fn subscription(&self) -> Subscription<SignMessage> {
-        time::every(Duration::from_secs(1)).map(|_| SignMessage::CheckConnection)
+        let interval = if self.channel.is_some() {
+            PING_INTERVAL
+        } else {
+            ENUMERATE_INTERVAL
+        };
+        time::every(interval).map(|_| SignMessage::CheckConnection)
     }
 
     pub async fn sign_revocation_txs(
@@ -209,10 +375,10 @@ impl Device {
         }
     }
 
-    pub async fn sign_spend_tx(self, spend_tx: Psbt) -> Result<Psbt, HWIError> {
+    pub async fn sign_tx(self, tx: Psbt) -> Result<Psbt, HWIError> {
         if let Some(channel) = self.channel {
             let mut res = channel.lock().await;
-            return res.sign_tx(&spend_tx).await;
+            return res.sign_tx(&tx).await;
         } else {
             Err(HWIError::DeviceDisconnected)
         }
@@ -9,27 +9,40 @@ use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{Message, SpendTxMessage},
+        inspector::PsbtInspection,
+        message::{Message, SignMessage, SpendTxMessage},
         state::{
-            cmd::{broadcast_spend_tx, delete_spend_tx, list_vaults, update_spend_tx},
-            sign::{Signer, SpendTransactionTarget},
+            cmd::{
+                broadcast_spend_tx, delete_spend_tx, get_mempool_entry, get_onchain_txs,
+                list_vaults, send_raw_transaction, update_spend_tx,
+            },
+            sign::{CpfpTransactionTarget, Signer, SpendTransactionTarget},
             State,
         },
         view::spend_transaction::{
             spend_tx_confirmed, spend_tx_deprecated, spend_tx_processing,
-            SpendTransactionBroadcastView, SpendTransactionDeleteView,
-            SpendTransactionListItemView, SpendTransactionSharePsbtView, SpendTransactionSignView,
-            SpendTransactionView,
+            SpendTransactionBroadcastView, SpendTransactionBroadcastedView,
+            SpendTransactionBumpFeeView, SpendTransactionDeleteView, SpendTransactionListItemView,
+            SpendTransactionSharePsbtView, SpendTransactionSignView, SpendTransactionView,
         },
+        watcher::WatcherEvent,
+    },
+    cpfp::{cpfp_psbt, finalize_cpfp},
+    daemon::{
+        bitcoind::{BitcoinD, MempoolEntry},
+        model::{self, outpoint},
     },
-    daemon::model::{self, outpoint},
 };
 
 #[derive(Debug)]
 pub struct SpendTransactionState {
     tx: model::SpendTx,
     psbt: Psbt,
+    /// Inspection of the psbt, computed again once it is signed or imported.
+    inspection: PsbtInspection,
     deposits: Vec<model::Vault>,
+    /// Height of the block including the spend transaction, if mined.
+    blockheight: Option<u32>,
     warning: Option<Error>,
 
     action: SpendTransactionAction,
@@ -48,21 +61,46 @@ impl SpendTransactionState {
                 &tx.status,
             ),
             psbt: tx.psbt.psbt().clone(),
+            inspection: ctx.inspect(tx.psbt.psbt()),
             tx,
             deposits: Vec::new(),
+            blockheight: None,
             warning: None,
             view: SpendTransactionView::default(),
         }
     }
 
+    /// The spend transaction is recorded in the onchain transactions of
+    /// each vault it spends.
+    fn load_blockheight(&self, ctx: &Context) -> Command<Message> {
+        if !matches!(
+            self.tx.status,
+            model::ListSpendStatus::Broadcasted | model::ListSpendStatus::Confirmed
+        ) {
+            return Command::none();
+        }
+        if let Some(deposit) = self.tx.deposit_outpoints.first() {
+            Command::perform(get_onchain_txs(ctx.revaultd.clone(), *deposit), |res| {
+                Message::SpendTx(SpendTxMessage::OnChainTransactions(res))
+            })
+        } else {
+            Command::none()
+        }
+    }
+
     // TODO: remove it for subscription
     pub fn sub(&self) -> Subscription<Message> {
-        if let SpendTransactionAction::Sign { signer, .. } = &self.action {
-            signer
+        match &self.action {
+            SpendTransactionAction::Sign { signer, .. } => signer
                 .subscription()
-                .map(|msg| Message::SpendTx(SpendTxMessage::Sign(msg)))
-        } else {
-            Subscription::none()
+                .map(|msg| Message::SpendTx(SpendTxMessage::Sign(msg))),
+            SpendTransactionAction::BumpFee {
+                signer: Some(signer),
+                ..
+            } => signer
+                .subscription()
+                .map(|msg| Message::SpendTx(SpendTxMessage::CpfpSign(msg))),
+            _ => Subscription::none(),
         }
     }
 }
@@ -76,14 +114,60 @@ impl State for SpendTransactionState {
                         .into_iter()
                         .filter(|vlt| self.tx.deposit_outpoints.contains(&outpoint(vlt)))
                         .collect();
+                    return self.load_blockheight(ctx);
                 }
                 Err(e) => self.warning = Error::from(e).into(),
             },
+            Message::SpendTx(SpendTxMessage::OnChainTransactions(res)) => match res {
+                Ok(txs) => self.blockheight = txs.spend.and_then(|tx| tx.blockheight),
+                Err(e) => self.warning = Error::from(e).into(),
+            },
+            Message::Watcher(Ok(events)) => {
+                let txid = self.psbt.global.unsigned_tx.txid();
+                for event in &events {
+                    if let WatcherEvent::SpendTxStatusChanged {
+                        txid: changed,
+                        status: model::ListSpendStatus::Confirmed,
+                        ..
+                    } = event
+                    {
+                        if *changed == txid {
+                            self.tx.status = model::ListSpendStatus::Confirmed;
+                            self.action = SpendTransactionAction::Confirmed;
+                        }
+                    }
+                }
+                // The confirmations are counted from the wallet blockheight once
+                // the spend transaction is mined.
+                if self.blockheight.is_none()
+                    && events
+                        .iter()
+                        .any(|event| matches!(event, WatcherEvent::NewBlocks { .. }))
+                {
+                    return self.load_blockheight(ctx);
+                }
+            }
             Message::SpendTx(msg) => {
-                return self
+                let psbt_changed = matches!(
+                    msg,
+                    SpendTxMessage::Sign(SignMessage::PsbtSigned(_))
+                        | SpendTxMessage::Signed(_)
+                        | SpendTxMessage::Updated(_)
+                );
+                let cmd = self
                     .action
-                    .update(ctx, &mut self.psbt, &self.tx.status, msg)
+                    .update(
+                        ctx,
+                        &mut self.psbt,
+                        &self.tx.status,
+                        self.tx.cpfp_index,
+                        msg,
+                    )
                     .map(Message::SpendTx);
+                if psbt_changed {
+                    self.inspection = ctx.inspect(&self.psbt);
+                }
+                return cmd;
             }
             _ => {}
         };
@@ -97,14 +181,18 @@ impl State for SpendTransactionState {
     fn view(&mut self, ctx: &Context) -> Element<Message> {
         let show_delete_button = !matches!(
             self.action,
-            SpendTransactionAction::Delete { .. } | SpendTransactionAction::Processing
+            SpendTransactionAction::Delete { .. }
+                | SpendTransactionAction::Processing
+                | SpendTransactionAction::Broadcasted { .. }
+                | SpendTransactionAction::BumpFee { .. }
         );
         self.view.view(
             ctx,
             &self.tx,
             &self.psbt,
             &self.deposits,
-            self.action.view(ctx, &self.psbt),
+            self.action
+                .view(ctx, &self.psbt, &self.inspection, self.blockheight),
             self.warning.as_ref(),
             show_delete_button,
             ctx.user_signed(&self.psbt),
@@ -128,6 +216,20 @@ pub enum SpendTransactionAction {
     Processing,
     Deprecated,
     Confirmed,
+    Broadcasted {
+        view: SpendTransactionBroadcastedView,
+    },
+    BumpFee {
+        /// Mempool entry of the spend transaction.
+        parent: Option<MempoolEntry>,
+        /// Target feerate of the package in sats/vbyte.
+        feerate: form::Value<String>,
+        signer: Option<Signer<CpfpTransactionTarget>>,
+        processing: bool,
+        success: bool,
+        warning: Option<Error>,
+        view: SpendTransactionBumpFeeView,
+    },
     SharePsbt {
         psbt_input: form::Value<String>,
         processing: bool,
@@ -167,8 +269,11 @@ impl SpendTransactionAction {
         match status {
             model::ListSpendStatus::Deprecated => return Self::Deprecated,
             model::ListSpendStatus::Confirmed => return Self::Confirmed,
-            model::ListSpendStatus::Pending | model::ListSpendStatus::Broadcasted => {
-                return Self::Processing
+            model::ListSpendStatus::Pending => return Self::Processing,
+            model::ListSpendStatus::Broadcasted => {
+                return Self::Broadcasted {
+                    view: SpendTransactionBroadcastedView::new(),
+                }
             }
             _ => {}
         };
@@ -218,9 +323,148 @@ impl SpendTransactionAction {
         ctx: &Context,
         psbt: &mut Psbt,
         status: &model::SpendTxStatus,
+        cpfp_index: usize,
         message: SpendTxMessage,
     ) -> Command<SpendTxMessage> {
         match message {
+            SpendTxMessage::SelectBumpFee => {
+                *self = Self::BumpFee {
+                    parent: None,
+                    feerate: form::Value::default(),
+                    signer: None,
+                    processing: false,
+                    success: false,
+                    warning: None,
+                    view: SpendTransactionBumpFeeView::new(),
+                };
+                return Command::perform(
+                    get_mempool_entry(
+                        BitcoinD::new(&ctx.config.daemon.bitcoind_config),
+                        psbt.global.unsigned_tx.txid(),
+                    ),
+                    SpendTxMessage::MempoolEntry,
+                );
+            }
+            SpendTxMessage::UnselectBumpFee => {
+                *self = Self::new(
+                    ctx.managers_threshold,
+                    ctx.user_signed(psbt),
+                    &ctx.managers_xpubs(),
+                    psbt,
+                    status,
+                );
+            }
+            SpendTxMessage::MempoolEntry(res) => {
+                if let Self::BumpFee {
+                    parent,
+                    feerate,
+                    warning,
+                    ..
+                } = self
+                {
+                    match res {
+                        Ok(entry) => {
+                            // Suggests to double the current feerate.
+                            feerate.value = format!("{}", (entry.feerate() * 2.0).ceil() as u64);
+                            feerate.valid = true;
+                            *parent = Some(entry);
+                        }
+                        Err(e) => *warning = Error::from(e).into(),
+                    }
+                }
+            }
+            SpendTxMessage::CpfpFeerateEdited(value) => {
+                if let Self::BumpFee {
+                    feerate, signer, ..
+                } = self
+                {
+                    if signer.is_none() {
+                        feerate.value = value;
+                        feerate.valid = true;
+                    }
+                }
+            }
+            SpendTxMessage::CreateCpfp => {
+                if let Self::BumpFee {
+                    parent: Some(parent),
+                    feerate,
+                    signer,
+                    warning,
+                    ..
+                } = self
+                {
+                    match feerate.value.parse::<u64>() {
+                        Ok(f) if f as f64 > parent.feerate() => {
+                            feerate.valid = true;
+                            match cpfp_psbt(
+                                &ctx.config.daemon.scripts_config.cpfp_descriptor,
+                                psbt,
+                                cpfp_index,
+                                parent.vsize,
+                                parent.fee().as_sat(),
+                                f,
+                            ) {
+                                Ok(cpfp_tx) => {
+                                    *warning = None;
+                                    *signer =
+                                        Some(Signer::new(CpfpTransactionTarget::new(cpfp_tx)));
+                                }
+                                Err(e) => *warning = Some(Error::Unexpected(e.to_string())),
+                            }
+                        }
+                        _ => feerate.valid = false,
+                    }
+                }
+            }
+            SpendTxMessage::CpfpSign(msg) => {
+                if let Self::BumpFee {
+                    signer: Some(signer),
+                    processing,
+                    warning,
+                    ..
+                } = self
+                {
+                    let cmd = signer.update(ctx, msg);
+                    if signer.signed() && !*processing {
+                        match finalize_cpfp(&signer.target.cpfp_tx) {
+                            Ok(tx) => {
+                                *processing = true;
+                                return Command::perform(
+                                    send_raw_transaction(
+                                        BitcoinD::new(&ctx.config.daemon.bitcoind_config),
+                                        tx,
+                                    ),
+                                    SpendTxMessage::CpfpBroadcasted,
+                                );
+                            }
+                            Err(e) => *warning = Some(Error::Unexpected(e.to_string())),
+                        }
+                    }
+                    return cmd.map(SpendTxMessage::CpfpSign);
+                }
+            }
+            SpendTxMessage::CpfpBroadcasted(res) => {
+                if let Self::BumpFee {
+                    signer,
+                    processing,
+                    success,
+                    warning,
+                    ..
+                } = self
+                {
+                    *processing = false;
+                    match res {
+                        Ok(txid) => {
+                            log::info!("CPFP transaction {} broadcasted", txid);
+                            *success = true;
+                        }
+                        Err(e) => {
+                            *warning = Error::from(e).into();
+                            *signer = None;
+                        }
+                    }
+                }
+            }
             SpendTxMessage::Delete => {
                 if let Self::Delete { processing, .. } = self {
                     *processing = true;
@@ -464,17 +708,56 @@ impl SpendTransactionAction {
         Command::none()
     }
 
-    fn view(&mut self, ctx: &Context, psbt: &Psbt) -> Element<Message> {
+    fn view(
+        &mut self,
+        ctx: &Context,
+        psbt: &Psbt,
+        inspection: &PsbtInspection,
+        blockheight: Option<u32>,
+    ) -> Element<Message> {
         match self {
             Self::Processing => spend_tx_processing(),
             Self::Deprecated => spend_tx_deprecated(),
-            Self::Confirmed => spend_tx_confirmed(),
+            Self::Confirmed => spend_tx_confirmed(ctx.confirmations(blockheight)),
+            Self::Broadcasted { view } => view.view(ctx.confirmations(blockheight)),
+            Self::BumpFee {
+                parent,
+                feerate,
+                signer,
+                processing,
+                success,
+                warning,
+                view,
+            } => {
+                let cpfp_fee = signer.as_ref().and_then(|signer| {
+                    let tx = &signer.target.cpfp_tx;
+                    let input = tx.inputs.first()?.witness_utxo.as_ref()?;
+                    let output = tx.global.unsigned_tx.output.first()?;
+                    Some(bitcoin::Amount::from_sat(input.value - output.value))
+                });
+                view.view(
+                    ctx,
+                    parent.as_ref(),
+                    feerate,
+                    cpfp_fee,
+                    signer.as_mut().map(|signer| {
+                        signer
+                            .view(ctx)
+                            .map(|msg| Message::SpendTx(SpendTxMessage::CpfpSign(msg)))
+                    }),
+                    *processing,
+                    *success,
+                    warning.as_ref(),
+                )
+            }
             Self::Sign {
                 signer,
                 warning,
                 view,
                 ..
             } => view.view(
+                ctx,
+                inspection,
                 signer
                     .view(ctx)
                     .map(|msg| Message::SpendTx(SpendTxMessage::Sign(msg))),
@@ -622,5 +905,14 @@ mod tests {
             &model::ListSpendStatus::NonFinal,
         );
         assert!(matches!(action, SpendTransactionAction::Broadcast { .. }));
+
+        let action = SpendTransactionAction::new(
+            0,
+            true,
+            &Vec::new(),
+            &psbt,
+            &model::ListSpendStatus::Broadcasted,
+        );
+        assert!(matches!(action, SpendTransactionAction::Broadcasted { .. }));
     }
 }
@@ -3,7 +3,10 @@ use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use bitcoin::{util::bip32::Fingerprint, OutPoint};
+use bitcoin::{
+    util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt},
+    OutPoint,
+};
 use iced::{Command, Element, Subscription};
 
 use revaultd::revault_tx::transactions::RevaultTransaction;
@@ -15,22 +18,27 @@ use crate::daemon::{
 
 use crate::app::{
     context::Context,
+    emergency_kit::KitStatus,
     error::Error,
+    inspector::PsbtInspection,
     menu::Menu,
-    message::{Message, SignMessage, VaultFilterMessage},
+    message::{LabelMessage, Message, SignMessage, VaultFilterMessage},
     state::{
         cmd::list_vaults,
         history::{HistoryEventListItemState, HistoryEventState},
         sign::Device,
+        trend::TrendState,
         vault::{Vault, VaultListItem},
         State,
     },
+    verify::TransactionRebuilder,
     view::{
         stakeholder::DelegateVaultsFilter,
         vault::{DelegateVaultListItemView, VaultListItemView},
         LoadingDashboard, LoadingModal, StakeholderCreateVaultsView, StakeholderDelegateVaultsView,
         StakeholderHomeView, StakeholderSelecteVaultsToDelegateView,
     },
+    watcher::WatcherEvent,
 };
 
 #[derive(Debug)]
@@ -45,12 +53,14 @@ pub enum StakeholderHomeState {
         warning: Option<Error>,
 
         balance: HashMap<VaultStatus, (u64, u64)>,
+        emergency_kit: KitStatus,
 
         spending_vaults: Vec<VaultListItem<VaultListItemView>>,
         selected_vault: Option<Vault>,
 
         latest_events: Vec<HistoryEventListItemState>,
         selected_event: Option<HistoryEventState>,
+        trend: TrendState,
 
         view: StakeholderHomeView,
     },
@@ -64,7 +74,8 @@ impl StakeholderHomeState {
         }
     }
 
-    pub fn update_vaults(&mut self, vaults: Vec<model::Vault>) {
+    pub fn update_vaults(&mut self, ctx: &Context, vaults: Vec<model::Vault>) {
+        let kit_status = KitStatus::check(ctx.config.gui.emergency_kit_path.as_deref(), &vaults);
         let mut total_balance = HashMap::new();
         for vault in &vaults {
             if vault.status == VaultStatus::Unconfirmed {
@@ -93,20 +104,24 @@ impl StakeholderHomeState {
             Self::Loading { .. } => {
                 *self = Self::Loaded {
                     balance: total_balance,
+                    emergency_kit: kit_status,
                     warning: None,
                     spending_vaults: spending_vlts,
                     selected_vault: None,
                     selected_event: None,
                     latest_events: Vec::new(),
+                    trend: TrendState::new(),
                     view: StakeholderHomeView::default(),
                 };
             }
             Self::Loaded {
                 balance,
+                emergency_kit,
                 spending_vaults,
                 ..
             } => {
                 *balance = total_balance;
+                *emergency_kit = kit_status;
                 *spending_vaults = spending_vlts;
             }
         }
@@ -120,11 +135,19 @@ impl StakeholderHomeState {
             .unwrap();
         let revaultd = ctx.revaultd.clone();
         Command::perform(
-            async move { revaultd.get_history(&ALL_HISTORY_EVENTS, 0, now, 5) },
+            async move { revaultd.get_history(&ALL_HISTORY_EVENTS, 0, now, 5).await },
             Message::HistoryEvents,
         )
     }
 
+    fn load_trend(&mut self, ctx: &Context) -> Command<Message> {
+        if let Self::Loaded { trend, .. } = self {
+            trend.refresh(ctx)
+        } else {
+            Command::none()
+        }
+    }
+
     fn load_vaults(ctx: &Context) -> Command<Message> {
         Command::perform(
             list_vaults(ctx.revaultd.clone(), Some(&CURRENT_VAULT_STATUSES), None),
@@ -140,8 +163,11 @@ impl State for StakeholderHomeState {
                 if let Message::Vaults(res) = message {
                     match res {
                         Ok(vaults) => {
-                            self.update_vaults(vaults);
-                            return Self::load_history(ctx);
+                            self.update_vaults(ctx, vaults);
+                            return Command::batch(vec![
+                                Self::load_history(ctx),
+                                self.load_trend(ctx),
+                            ]);
                         }
                         Err(e) => *fail = Some(e.into()),
                     }
@@ -153,13 +179,20 @@ impl State for StakeholderHomeState {
                 selected_vault,
                 selected_event,
                 spending_vaults,
+                trend,
                 ..
             } => match message {
                 Message::Reload => return self.load(ctx),
+                Message::Trend(msg) => return trend.update(ctx, msg),
+                Message::Watcher(Ok(events)) => {
+                    if events.iter().any(WatcherEvent::is_vault_change) {
+                        return Self::load_vaults(ctx);
+                    }
+                }
                 Message::Vaults(res) => match res {
                     Ok(vaults) => {
-                        self.update_vaults(vaults);
-                        return Self::load_history(ctx);
+                        self.update_vaults(ctx, vaults);
+                        return Command::batch(vec![Self::load_history(ctx), self.load_trend(ctx)]);
                     }
                     Err(e) => *warning = Error::from(e).into(),
                 },
@@ -179,6 +212,7 @@ impl State for StakeholderHomeState {
                         return selected.update(ctx, msg).map(Message::Vault);
                     }
                 }
+                Message::Label(LabelMessage::Written(Err(e))) => *warning = Some(e),
                 Message::SelectHistoryEvent(i) => {
                     if let Some(item) = latest_events.get(i) {
                         let state = HistoryEventState::new(item.event.clone());
@@ -227,6 +261,8 @@ impl State for StakeholderHomeState {
                 spending_vaults,
                 latest_events,
                 balance,
+                emergency_kit,
+                trend,
                 view,
                 warning,
             } => {
@@ -238,9 +274,11 @@ impl State for StakeholderHomeState {
                     return v.view(ctx);
                 }
 
+                let total = balance.values().map(|(_, amount)| amount).sum();
                 view.view(
                     ctx,
                     warning.as_ref(),
+                    trend.view(ctx, total),
                     spending_vaults.iter_mut().map(|v| v.view(ctx)).collect(),
                     latest_events
                         .iter_mut()
@@ -248,6 +286,7 @@ impl State for StakeholderHomeState {
                         .map(|(i, evt)| evt.view(ctx, i))
                         .collect(),
                     &balance,
+                    *emergency_kit,
                 )
             }
         }
@@ -274,6 +313,8 @@ pub enum StakeholderCreateVaultsState {
         device: Device,
         processing: bool,
         deposits: Vec<model::Vault>,
+        /// Revocation transactions to sign, rebuilt from the descriptors.
+        transactions: Vec<(String, PsbtInspection)>,
         warning: Option<Error>,
         view: StakeholderCreateVaultsView,
     },
@@ -298,6 +339,7 @@ impl State for StakeholderCreateVaultsState {
                             *self = Self::Loaded {
                                 device: Device::new(),
                                 processing: false,
+                                transactions: inspect_revocation_txs(ctx, &deposits),
                                 deposits,
                                 warning: None,
                                 view: StakeholderCreateVaultsView::new(),
@@ -331,6 +373,7 @@ impl State for StakeholderCreateVaultsState {
                             Command::perform(
                                 secure_deposits(
                                     ctx.revaultd.clone(),
+                                    TransactionRebuilder::from_config(&ctx.config.daemon),
                                     device.clone(),
                                     deposits_to_secure.clone(),
                                 ),
@@ -349,7 +392,12 @@ impl State for StakeholderCreateVaultsState {
                     *processing = true;
                     if !deposits.is_empty() {
                         Command::perform(
-                            secure_deposits(ctx.revaultd.clone(), device.clone(), deposits.clone()),
+                            secure_deposits(
+                                ctx.revaultd.clone(),
+                                TransactionRebuilder::from_config(&ctx.config.daemon),
+                                device.clone(),
+                                deposits.clone(),
+                            ),
                             Message::DepositsSecured,
                         )
                     } else {
@@ -376,12 +424,13 @@ impl State for StakeholderCreateVaultsState {
                 view,
                 warning,
                 deposits,
+                transactions,
                 processing,
                 device,
-                ..
             } => view.view(
                 ctx,
                 deposits,
+                transactions,
                 *processing,
                 device.is_connected(),
                 warning.as_ref(),
@@ -397,8 +446,40 @@ impl State for StakeholderCreateVaultsState {
     }
 }
 
+/// Decodes the emergency, unvault-emergency and lowest feerate cancel
+/// transactions of the deposits.
+fn inspect_revocation_txs(
+    ctx: &Context,
+    deposits: &[model::Vault],
+) -> Vec<(String, PsbtInspection)> {
+    let rebuilder = TransactionRebuilder::from_config(&ctx.config.daemon);
+    let mut transactions = Vec::new();
+    for deposit in deposits {
+        match rebuilder.revocation_txs(deposit) {
+            Ok((emergency_tx, emergency_unvault_tx, cancel_txs)) => {
+                let outpoint = outpoint(deposit);
+                transactions.push((
+                    format!("Emergency transaction of {}", outpoint),
+                    ctx.inspect(&emergency_tx),
+                ));
+                transactions.push((
+                    format!("Unvault-emergency transaction of {}", outpoint),
+                    ctx.inspect(&emergency_unvault_tx),
+                ));
+                transactions.push((
+                    format!("Cancel transaction of {} (lowest of 5 feerates)", outpoint),
+                    ctx.inspect(&cancel_txs[0]),
+                ));
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    transactions
+}
+
 pub async fn secure_deposits(
     revaultd: Arc<dyn Daemon + Send + Sync>,
+    rebuilder: TransactionRebuilder,
     device: Device,
     deposits: Vec<model::Vault>,
 ) -> Result<Vec<OutPoint>, Error> {
@@ -407,12 +488,14 @@ pub async fn secure_deposits(
             for (i, (emergency_tx, emergency_unvault_tx, cancel_txs)) in
                 revocation_txs.into_iter().enumerate()
             {
-                revaultd.set_revocation_txs(
-                    &outpoint(&deposits[i]),
-                    &emergency_tx,
-                    &emergency_unvault_tx,
-                    &cancel_txs,
-                )?;
+                revaultd
+                    .set_revocation_txs(
+                        &outpoint(&deposits[i]),
+                        &emergency_tx,
+                        &emergency_unvault_tx,
+                        &cancel_txs,
+                    )
+                    .await?;
             }
 
             return Ok(deposits.iter().map(outpoint).collect());
@@ -426,7 +509,8 @@ pub async fn secure_deposits(
     // Batching is not supported, so we secure only the first one.
     if let Some(deposit) = deposits.into_iter().nth(0) {
         let outpoint = outpoint(&deposit);
-        let revocation_txs = revaultd.get_revocation_txs(&outpoint)?;
+        let revocation_txs = revaultd.get_revocation_txs(&outpoint).await?;
+        rebuilder.verify_revocation_txs(&deposit, &revocation_txs)?;
 
         let (emergency_tx, emergency_unvault_tx, cancel_txs) = device
             .sign_revocation_txs(
@@ -442,12 +526,9 @@ pub async fn secure_deposits(
             )
             .await?;
 
-        revaultd.set_revocation_txs(
-            &outpoint,
-            &emergency_tx,
-            &emergency_unvault_tx,
-            &cancel_txs,
-        )?;
+        revaultd
+            .set_revocation_txs(&outpoint, &emergency_tx, &emergency_unvault_tx, &cancel_txs)
+            .await?;
 
         Ok(vec![outpoint])
     } else {
@@ -463,15 +544,17 @@ impl From<StakeholderCreateVaultsState> for Box<dyn State> {
 #[derive(Debug)]
 pub struct DelegateVaultListItem {
     vault: model::Vault,
+    unvault_tx: Psbt,
     sigs: Vec<Fingerprint>,
     selected: bool,
     view: DelegateVaultListItemView,
 }
 
 impl DelegateVaultListItem {
-    pub fn new(vault: model::Vault, sigs: Vec<Fingerprint>) -> Self {
+    pub fn new(vault: model::Vault, unvault_tx: Psbt, sigs: Vec<Fingerprint>) -> Self {
         Self {
             vault,
+            unvault_tx,
             sigs,
             selected: false,
             view: DelegateVaultListItemView::new(),
@@ -500,6 +583,8 @@ pub enum StakeholderDelegateVaultsState {
         device: Device,
         processing: bool,
         vaults: Vec<model::Vault>,
+        /// Unvault transactions to sign.
+        transactions: Vec<(String, PsbtInspection)>,
         warning: Option<Error>,
         view: StakeholderDelegateVaultsView,
     },
@@ -541,6 +626,7 @@ impl State for StakeholderDelegateVaultsState {
                                         let unvault = txs.unvault.psbt();
                                         Some(DelegateVaultListItem::new(
                                             vault,
+                                            unvault.clone(),
                                             unvault.inputs[0]
                                                 .partial_sigs
                                                 .keys()
@@ -598,6 +684,16 @@ impl State for StakeholderDelegateVaultsState {
                 }
                 Message::Next => {
                     *self = Self::Signing {
+                        transactions: vaults
+                            .iter()
+                            .filter(|vlt| vlt.selected)
+                            .map(|vlt| {
+                                (
+                                    format!("Unvault transaction of {}", outpoint(&vlt.vault)),
+                                    ctx.inspect(&vlt.unvault_tx),
+                                )
+                            })
+                            .collect(),
                         vaults: vaults
                             .iter()
                             .filter(|vlt| vlt.selected)
@@ -635,6 +731,7 @@ impl State for StakeholderDelegateVaultsState {
                             Command::perform(
                                 delegate_vaults(
                                     ctx.revaultd.clone(),
+                                    TransactionRebuilder::from_config(&ctx.config.daemon),
                                     device.clone(),
                                     vaults_to_delegate.clone(),
                                 ),
@@ -653,7 +750,12 @@ impl State for StakeholderDelegateVaultsState {
                     *processing = true;
                     if !vaults.is_empty() {
                         Command::perform(
-                            delegate_vaults(ctx.revaultd.clone(), device.clone(), vaults.clone()),
+                            delegate_vaults(
+                                ctx.revaultd.clone(),
+                                TransactionRebuilder::from_config(&ctx.config.daemon),
+                                device.clone(),
+                                vaults.clone(),
+                            ),
                             Message::VaultsDelegated,
                         )
                     } else {
@@ -708,12 +810,13 @@ impl State for StakeholderDelegateVaultsState {
                 view,
                 warning,
                 vaults,
+                transactions,
                 processing,
                 device,
-                ..
             } => view.view(
                 ctx,
                 vaults,
+                transactions,
                 *processing,
                 device.is_connected(),
                 warning.as_ref(),
@@ -725,16 +828,20 @@ impl State for StakeholderDelegateVaultsState {
         let revaultd = ctx.revaultd.clone();
         Command::perform(
             async move {
-                let vaults = revaultd.list_vaults(
-                    Some(&[
-                        VaultStatus::Secured,
-                        VaultStatus::Activating,
-                        VaultStatus::Active,
-                    ]),
-                    None,
-                )?;
+                let vaults = revaultd
+                    .list_vaults(
+                        Some(&[
+                            VaultStatus::Secured,
+                            VaultStatus::Activating,
+                            VaultStatus::Active,
+                        ]),
+                        None,
+                    )
+                    .await?;
                 let outpoints: Vec<OutPoint> = vaults.iter().map(model::outpoint).collect();
-                let vaults_txs = revaultd.list_presigned_transactions(outpoints.as_slice())?;
+                let vaults_txs = revaultd
+                    .list_presigned_transactions(outpoints.as_slice())
+                    .await?;
 
                 let res: Vec<(model::Vault, model::VaultPresignedTransactions)> = vaults
                     .into_iter()
@@ -756,13 +863,16 @@ impl State for StakeholderDelegateVaultsState {
 
 pub async fn delegate_vaults(
     revaultd: Arc<dyn Daemon + Send + Sync>,
+    rebuilder: TransactionRebuilder,
     device: Device,
     vaults: Vec<model::Vault>,
 ) -> Result<Vec<OutPoint>, Error> {
     match device.clone().delegate_batch(&vaults).await {
         Ok(revocation_txs) => {
             for (i, unvault_tx) in revocation_txs.into_iter().enumerate() {
-                revaultd.set_unvault_tx(&outpoint(&vaults[i]), &unvault_tx)?;
+                revaultd
+                    .set_unvault_tx(&outpoint(&vaults[i]), &unvault_tx)
+                    .await?;
             }
 
             return Ok(vaults.iter().map(outpoint).collect());
@@ -776,9 +886,10 @@ pub async fn delegate_vaults(
     // Batching is not supported, so we secure only the first one.
     if let Some(vault) = vaults.into_iter().nth(0) {
         let outpoint = outpoint(&vault);
-        let res = revaultd.get_unvault_tx(&outpoint)?;
+        let res = revaultd.get_unvault_tx(&outpoint).await?;
+        rebuilder.verify_unvault_tx(&vault, &res)?;
         let unvault_tx = device.sign_unvault_tx(res).await?;
-        revaultd.set_unvault_tx(&outpoint, &unvault_tx)?;
+        revaultd.set_unvault_tx(&outpoint, &unvault_tx).await?;
 
         Ok(vec![outpoint])
     } else {
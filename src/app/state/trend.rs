@@ -0,0 +1,158 @@
+use std::convert::TryInto;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use iced::{Command, Element};
+
+use crate::{
+    app::{
+        context::Context,
+        error::Error,
+        export::{merge_history_page, EXPORT_PAGE_SIZE},
+        message::{Message, TrendMessage},
+        trend::{trend, Period},
+        view::TrendView,
+    },
+    daemon::model::{HistoryEvent, ALL_HISTORY_EVENTS},
+};
+
+/// TrendState is the dashboard panel charting the balance and the flows
+/// of funds of the wallet, it pages through the history events of the period.
+#[derive(Debug)]
+pub struct TrendState {
+    period: Period,
+    /// End of the period, set when the panel is loaded.
+    now: u32,
+    events: Vec<HistoryEvent>,
+    /// Start of the fetched events, the start of the period or the date of
+    /// the latest event once the panel is refreshed.
+    start: u32,
+    cursor: u32,
+    limit: u64,
+    /// Incremented by each new paging, the pages of the previous ones are dropped.
+    generation: u64,
+    loading: bool,
+    warning: Option<Error>,
+    view: TrendView,
+}
+
+impl TrendState {
+    pub fn new() -> Self {
+        Self {
+            period: Period::Month,
+            now: 0,
+            events: Vec::new(),
+            start: 0,
+            cursor: 0,
+            limit: EXPORT_PAGE_SIZE,
+            generation: 0,
+            loading: false,
+            warning: None,
+            view: TrendView::default(),
+        }
+    }
+
+    /// Pages through the history events of the whole period.
+    pub fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.now = now();
+        self.events = Vec::new();
+        self.start = self.now.saturating_sub(self.period.duration());
+        self.loading = true;
+        self.warning = None;
+        self.page(ctx)
+    }
+
+    /// Fetches only the events since the latest known one, the period is
+    /// loaded again if it was not loaded yet.
+    pub fn refresh(&mut self, ctx: &Context) -> Command<Message> {
+        match self.events.iter().map(|evt| evt.date).max() {
+            Some(latest) if !self.loading => {
+                self.now = now();
+                self.start = latest;
+                self.warning = None;
+                self.page(ctx)
+            }
+            _ => self.load(ctx),
+        }
+    }
+
+    /// Starts a new paging from the end of the period.
+    fn page(&mut self, ctx: &Context) -> Command<Message> {
+        self.cursor = self.now;
+        self.limit = EXPORT_PAGE_SIZE;
+        self.generation += 1;
+        self.fetch(ctx)
+    }
+
+    fn fetch(&self, ctx: &Context) -> Command<Message> {
+        let revaultd = ctx.revaultd.clone();
+        let (start, cursor, limit) = (self.start, self.cursor, self.limit);
+        let generation = self.generation;
+        Command::perform(
+            async move {
+                revaultd
+                    .get_history(&ALL_HISTORY_EVENTS, start, cursor, limit)
+                    .await
+            },
+            move |res| Message::Trend(TrendMessage::HistoryEvents(generation, res)),
+        )
+    }
+
+    pub fn update(&mut self, ctx: &Context, message: TrendMessage) -> Command<Message> {
+        match message {
+            TrendMessage::PeriodSelected(period) => {
+                if period != self.period {
+                    self.period = period;
+                    return self.load(ctx);
+                }
+            }
+            TrendMessage::HistoryEvents(generation, _) if generation != self.generation => {}
+            TrendMessage::HistoryEvents(_, res) => match res {
+                Ok(events) => {
+                    let full = events.len() as u64 == self.limit;
+                    let oldest = events.last().map(|evt| evt.date);
+                    merge_history_page(&mut self.events, events);
+                    match oldest {
+                        Some(oldest) if full => {
+                            // gethistory cursor is inclusive and uses the blocktime,
+                            // if all the events of the page are in the same block,
+                            // the next ones can only be retrieved with a larger limit.
+                            if oldest < self.cursor {
+                                self.cursor = oldest;
+                                self.limit = EXPORT_PAGE_SIZE;
+                            } else {
+                                self.limit += EXPORT_PAGE_SIZE;
+                            }
+                            return self.fetch(ctx);
+                        }
+                        _ => self.loading = false,
+                    }
+                }
+                Err(e) => {
+                    self.loading = false;
+                    self.warning = Some(e.into());
+                }
+            },
+        }
+        Command::none()
+    }
+
+    /// The balance is the current one, the past balances are rebuilt from it.
+    pub fn view(&mut self, ctx: &Context, balance: u64) -> Element<Message> {
+        let trend = if self.loading {
+            None
+        } else {
+            Some(trend(&self.events, balance, self.now, self.period))
+        };
+        self.view
+            .view(ctx, self.period, trend.as_ref(), self.warning.as_ref())
+    }
+}
+
+fn now() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        .try_into()
+        .unwrap()
+}
@@ -6,14 +6,15 @@ use crate::{
         context::Context,
         error::Error,
         message::{Message, VaultMessage},
-        state::cmd::get_onchain_txs,
+        state::cmd::{get_onchain_txs, get_presigned_txs},
+        timeline::{timeline, Step},
         view::{
             vault::{VaultModal, VaultView},
             LoadingModal,
         },
     },
     daemon::{
-        model::{self, outpoint, VaultTransactions},
+        model::{self, outpoint, VaultStatus, VaultTransactions},
         Daemon,
     },
 };
@@ -48,7 +49,11 @@ pub enum Vault {
     },
     Loaded {
         txs: VaultTransactions,
+        /// Built again once the presigned transactions are loaded.
+        timeline: Vec<Step>,
         vault: model::Vault,
+        /// Label being edited, the saved one is displayed if None.
+        label: Option<String>,
         view: VaultModal,
     },
 }
@@ -69,20 +74,54 @@ impl Vault {
         }
     }
 
-    pub fn update(&mut self, _ctx: &Context, message: VaultMessage) -> Command<VaultMessage> {
-        if let Self::Loading { fail, vault, .. } = self {
-            if let VaultMessage::OnChainTransactions(res) = message {
-                match res {
-                    Ok(txs) => {
-                        *self = Self::Loaded {
-                            vault: vault.clone(),
-                            txs,
-                            view: VaultModal::new(),
+    pub fn update(&mut self, ctx: &Context, message: VaultMessage) -> Command<VaultMessage> {
+        match self {
+            Self::Loading { fail, vault, .. } => {
+                if let VaultMessage::OnChainTransactions(res) = message {
+                    match res {
+                        Ok(txs) => {
+                            let vault = vault.clone();
+                            // The presigned transactions only exist once the
+                            // vault is secured.
+                            let cmd = if matches!(
+                                vault.status,
+                                VaultStatus::Unconfirmed | VaultStatus::Funded
+                            ) {
+                                Command::none()
+                            } else {
+                                Command::perform(
+                                    get_presigned_txs(ctx.revaultd.clone(), outpoint(&vault)),
+                                    VaultMessage::PresignedTransactions,
+                                )
+                            };
+                            *self = Self::Loaded {
+                                timeline: timeline(&vault, &txs, None),
+                                vault,
+                                txs,
+                                label: None,
+                                view: VaultModal::new(),
+                            };
+                            return cmd;
                         }
+                        Err(e) => *fail = Some(e.into()),
                     }
-                    Err(e) => *fail = Some(e.into()),
                 }
             }
+            Self::Loaded {
+                label,
+                timeline: steps,
+                vault,
+                txs,
+                ..
+            } => match message {
+                VaultMessage::LabelEdited(value) => *label = Some(value),
+                VaultMessage::PresignedTransactions(res) => match res {
+                    Ok(presigned) => *steps = timeline(vault, txs, Some(&presigned)),
+                    // The timeline is still displayed without the pending transactions.
+                    Err(e) => log::warn!("Failed to list the presigned transactions: {}", e),
+                },
+                _ => {}
+            },
         }
         Command::none()
     }
@@ -90,7 +129,13 @@ impl Vault {
     pub fn view(&mut self, ctx: &Context) -> Element<Message> {
         match self {
             Self::Loading { view, fail, .. } => view.view(ctx, fail.as_ref(), Message::Close),
-            Self::Loaded { view, vault, txs } => view.view(ctx, vault, &txs),
+            Self::Loaded {
+                view,
+                vault,
+                txs,
+                timeline,
+                label,
+            } => view.view(ctx, vault, &txs, &timeline, label.as_deref()),
         }
     }
 
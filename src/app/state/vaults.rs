@@ -20,8 +20,9 @@ use crate::app::{
     context::Context,
     error::Error,
     menu::VaultsMenu,
-    message::{Message, VaultFilterMessage},
+    message::{LabelMessage, Message, VaultFilterMessage},
     view::{vault::VaultListItemView, LoadingDashboard, VaultsView},
+    watcher::WatcherEvent,
 };
 
 #[derive(Debug)]
@@ -118,6 +119,11 @@ impl State for VaultsState {
     fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
         match message {
             Message::Reload => return self.load(ctx),
+            Message::Watcher(Ok(events)) => {
+                if events.iter().any(WatcherEvent::is_vault_change) {
+                    return self.load(ctx);
+                }
+            }
             Message::Vaults(res) => match res {
                 Ok(vaults) => self.update_vaults(vaults),
                 Err(e) => self.on_error(Error::from(e)),
@@ -137,6 +143,7 @@ impl State for VaultsState {
                     }
                 }
             }
+            Message::Label(LabelMessage::Written(Err(e))) => self.on_error(e),
             Message::FilterVaults(VaultFilterMessage::Status(statuses)) => {
                 if let Self::Loaded {
                     vault_status_filter,
@@ -0,0 +1,360 @@
+//! Lifecycle of a vault, from its deposit to the transaction moving its
+//! funds, built from its onchain and presigned transactions.
+
+use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, Amount, Transaction, Txid};
+use revaultd::revault_tx::transactions::RevaultTransaction;
+
+use crate::daemon::model::{
+    transaction_from_hex, Vault, VaultPresignedTransactions, VaultStatus, VaultTransactions,
+    WalletTransaction,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    Deposit,
+    /// The stakeholders signed the revocation transactions.
+    Secured,
+    /// The stakeholders signed the unvault transaction.
+    Activated,
+    Unvault,
+    Cancel,
+    Emergency,
+    UnvaultEmergency,
+    Spend,
+    /// The transaction moving the funds of the vault, not known yet.
+    Exit,
+}
+
+impl std::fmt::Display for StepKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "Deposit"),
+            Self::Secured => write!(f, "Secured"),
+            Self::Activated => write!(f, "Activated"),
+            Self::Unvault => write!(f, "Unvault broadcast"),
+            Self::Cancel => write!(f, "Canceled"),
+            Self::Emergency => write!(f, "Emergency"),
+            Self::UnvaultEmergency => write!(f, "Unvault emergency"),
+            Self::Spend => write!(f, "Spent"),
+            Self::Exit => write!(f, "Spend, cancel or emergency"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub kind: StepKind,
+    /// False for the steps still to come.
+    pub done: bool,
+    /// Timestamp of the step, None if it is not known.
+    pub time: Option<u32>,
+    pub blockheight: Option<u32>,
+    pub txid: Option<Txid>,
+    /// None if the value of an input is not known.
+    pub fee: Option<Amount>,
+}
+
+impl Step {
+    fn event(kind: StepKind, done: bool, time: Option<u32>) -> Self {
+        Self {
+            kind,
+            done,
+            time,
+            blockheight: None,
+            txid: None,
+            fee: None,
+        }
+    }
+
+    fn pending(kind: StepKind, psbt: Option<&Psbt>) -> Self {
+        Self {
+            kind,
+            done: false,
+            time: None,
+            blockheight: None,
+            txid: psbt.map(|psbt| psbt.global.unsigned_tx.txid()),
+            fee: psbt.and_then(psbt_fee),
+        }
+    }
+}
+
+/// Returns the steps of the vault, in order. The presigned transactions
+/// give the txid and the fee of the unvault transaction before it is
+/// broadcast.
+pub fn timeline(
+    vault: &Vault,
+    txs: &VaultTransactions,
+    presigned: Option<&VaultPresignedTransactions>,
+) -> Vec<Step> {
+    let known: Vec<Transaction> = [
+        Some(&txs.deposit),
+        txs.unvault.as_ref(),
+        txs.cancel.as_ref(),
+        txs.emergency.as_ref(),
+        txs.unvault_emergency.as_ref(),
+        txs.spend.as_ref(),
+    ]
+    .iter()
+    .flatten()
+    .map(|tx| transaction_from_hex(&tx.hex))
+    .collect();
+    let onchain = |kind: StepKind, tx: &WalletTransaction| {
+        let transaction = transaction_from_hex(&tx.hex);
+        Step {
+            kind,
+            done: true,
+            time: Some(tx.received_time),
+            blockheight: tx.blockheight,
+            txid: Some(transaction.txid()),
+            fee: fee(&transaction, &known),
+        }
+    };
+
+    let mut steps = vec![onchain(StepKind::Deposit, &txs.deposit)];
+    steps.push(Step::event(
+        StepKind::Secured,
+        vault.secured_at.is_some()
+            || !matches!(
+                vault.status,
+                VaultStatus::Unconfirmed | VaultStatus::Funded | VaultStatus::Securing
+            ),
+        vault.secured_at,
+    ));
+
+    let activated = vault.delegated_at.is_some()
+        || vault.status == VaultStatus::Active
+        || txs.unvault.is_some();
+
+    // The emergency transaction spends the deposit, the vault may never
+    // have been activated.
+    if let Some(tx) = &txs.emergency {
+        if activated {
+            steps.push(Step::event(StepKind::Activated, true, vault.delegated_at));
+        }
+        steps.push(onchain(StepKind::Emergency, tx));
+        return steps;
+    }
+
+    steps.push(Step::event(
+        StepKind::Activated,
+        activated,
+        vault.delegated_at,
+    ));
+    steps.push(match &txs.unvault {
+        Some(tx) => onchain(StepKind::Unvault, tx),
+        None => Step::pending(
+            StepKind::Unvault,
+            presigned.map(|presigned| presigned.unvault.psbt()),
+        ),
+    });
+
+    steps.push(if let Some(tx) = &txs.spend {
+        onchain(StepKind::Spend, tx)
+    } else if let Some(tx) = &txs.cancel {
+        onchain(StepKind::Cancel, tx)
+    } else if let Some(tx) = &txs.unvault_emergency {
+        onchain(StepKind::UnvaultEmergency, tx)
+    } else {
+        Step::pending(StepKind::Exit, None)
+    });
+    steps
+}
+
+/// Fee of the transaction, None if one of its inputs does not spend one of
+/// the known transactions.
+fn fee(tx: &Transaction, known: &[Transaction]) -> Option<Amount> {
+    let mut input_amount = 0;
+    for input in &tx.input {
+        let previous = known
+            .iter()
+            .find(|k| k.txid() == input.previous_output.txid)?;
+        input_amount += previous
+            .output
+            .get(input.previous_output.vout as usize)?
+            .value;
+    }
+    input_amount
+        .checked_sub(tx.output.iter().map(|o| o.value).sum())
+        .map(Amount::from_sat)
+}
+
+fn psbt_fee(psbt: &Psbt) -> Option<Amount> {
+    psbt.inputs
+        .iter()
+        .map(|input| input.witness_utxo.as_ref().map(|utxo| utxo.value))
+        .sum::<Option<u64>>()
+        .and_then(|total| {
+            total.checked_sub(psbt.global.unsigned_tx.output.iter().map(|o| o.value).sum())
+        })
+        .map(Amount::from_sat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{
+        consensus::encode::serialize_hex, util::bip32::ChildNumber, Address, OutPoint, TxIn, TxOut,
+    };
+    use std::str::FromStr;
+
+    fn tx(inputs: Vec<OutPoint>, outputs: Vec<u64>) -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: 0,
+            input: inputs
+                .into_iter()
+                .map(|previous_output| TxIn {
+                    previous_output,
+                    ..TxIn::default()
+                })
+                .collect(),
+            output: outputs
+                .into_iter()
+                .map(|value| TxOut {
+                    value,
+                    ..TxOut::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn vault(status: VaultStatus, delegated_at: Option<u32>) -> Vault {
+        Vault {
+            address: Address::from_str(
+                "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            )
+            .unwrap(),
+            amount: Amount::from_sat(100_000),
+            derivation_index: ChildNumber::from_normal_idx(0).unwrap(),
+            status,
+            txid: Txid::from_str(
+                "a9735f42110ce529386f612194a1e137a2a2679ac0e789ad7f470cd70c3c2c24",
+            )
+            .unwrap(),
+            vout: 1,
+            blockheight: Some(1),
+            delegated_at,
+            secured_at: Some(1),
+            funded_at: Some(1),
+            moved_at: None,
+        }
+    }
+
+    fn wallet_tx(tx: &Transaction, time: u32) -> WalletTransaction {
+        WalletTransaction {
+            blockheight: Some(time),
+            hex: serialize_hex(tx),
+            received_time: time,
+            blocktime: Some(time),
+        }
+    }
+
+    fn vault_txs(deposit: &Transaction) -> VaultTransactions {
+        VaultTransactions {
+            vault_outpoint: OutPoint::new(deposit.txid(), 1),
+            deposit: wallet_tx(deposit, 1),
+            unvault: None,
+            spend: None,
+            cancel: None,
+            emergency: None,
+            unvault_emergency: None,
+        }
+    }
+
+    fn kinds(steps: &[Step]) -> Vec<(StepKind, bool)> {
+        steps.iter().map(|step| (step.kind, step.done)).collect()
+    }
+
+    #[test]
+    fn test_timeline() {
+        let deposit = tx(vec![OutPoint::default()], vec![5_000, 100_000]);
+        let unvault = tx(vec![OutPoint::new(deposit.txid(), 1)], vec![90_000, 330]);
+
+        // The emergency transaction was broadcast before the vault was activated.
+        let emergency = tx(vec![OutPoint::new(deposit.txid(), 1)], vec![95_000]);
+        let mut txs = vault_txs(&deposit);
+        txs.emergency = Some(wallet_tx(&emergency, 2));
+        let steps = timeline(&vault(VaultStatus::EmergencyVaulted, None), &txs, None);
+        assert_eq!(
+            kinds(&steps),
+            vec![
+                (StepKind::Deposit, true),
+                (StepKind::Secured, true),
+                (StepKind::Emergency, true),
+            ]
+        );
+        assert_eq!(steps[2].fee, Some(Amount::from_sat(5_000)));
+
+        // The vault is active, the unvault transaction is not broadcast yet.
+        let txs = vault_txs(&deposit);
+        let steps = timeline(&vault(VaultStatus::Active, Some(2)), &txs, None);
+        assert_eq!(
+            kinds(&steps),
+            vec![
+                (StepKind::Deposit, true),
+                (StepKind::Secured, true),
+                (StepKind::Activated, true),
+                (StepKind::Unvault, false),
+                (StepKind::Exit, false),
+            ]
+        );
+        assert_eq!(steps[2].time, Some(2));
+        assert_eq!(steps[3].txid, None);
+
+        // The unvault transaction is spent or canceled.
+        let mut txs = vault_txs(&deposit);
+        txs.unvault = Some(wallet_tx(&unvault, 3));
+        let spend = tx(vec![OutPoint::new(unvault.txid(), 0)], vec![80_000]);
+        txs.spend = Some(wallet_tx(&spend, 4));
+        let steps = timeline(&vault(VaultStatus::Spent, Some(2)), &txs, None);
+        assert_eq!(
+            kinds(&steps),
+            vec![
+                (StepKind::Deposit, true),
+                (StepKind::Secured, true),
+                (StepKind::Activated, true),
+                (StepKind::Unvault, true),
+                (StepKind::Spend, true),
+            ]
+        );
+        assert_eq!(steps[3].fee, Some(Amount::from_sat(9_670)));
+        assert_eq!(steps[4].txid, Some(spend.txid()));
+        assert_eq!(steps[4].fee, Some(Amount::from_sat(10_000)));
+
+        let cancel = tx(vec![OutPoint::new(unvault.txid(), 0)], vec![85_000]);
+        txs.spend = None;
+        txs.cancel = Some(wallet_tx(&cancel, 4));
+        let steps = timeline(&vault(VaultStatus::Canceled, None), &txs, None);
+        assert_eq!(steps[2].kind, StepKind::Activated);
+        assert!(steps[2].done);
+        assert_eq!(steps[4].kind, StepKind::Cancel);
+        assert_eq!(steps[4].fee, Some(Amount::from_sat(5_000)));
+    }
+
+    #[test]
+    fn test_fee() {
+        let deposit = tx(vec![OutPoint::default()], vec![5_000, 100_000]);
+        let unvault = tx(vec![OutPoint::new(deposit.txid(), 1)], vec![90_000, 330]);
+        let known = vec![deposit.clone(), unvault.clone()];
+        assert_eq!(fee(&deposit, &known), None);
+        assert_eq!(fee(&unvault, &known), Some(Amount::from_sat(9_670)));
+
+        let bumped = tx(
+            vec![OutPoint::new(unvault.txid(), 0), OutPoint::default()],
+            vec![80_000],
+        );
+        assert_eq!(fee(&bumped, &known), None);
+    }
+
+    #[test]
+    fn test_psbt_fee() {
+        let unvault = tx(vec![OutPoint::default()], vec![90_000, 330]);
+        let mut psbt = Psbt::from_unsigned_tx(unvault).unwrap();
+        assert_eq!(psbt_fee(&psbt), None);
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 100_000,
+            ..TxOut::default()
+        });
+        assert_eq!(psbt_fee(&psbt), Some(Amount::from_sat(9_670)));
+    }
+}
@@ -0,0 +1,176 @@
+//! Balance of the wallet over a period, rebuilt backward from the current
+//! balance with the history events, and the funds moved in and out of it.
+
+use chrono::NaiveDateTime;
+
+use crate::daemon::model::{HistoryEvent, HistoryEventKind};
+
+const DAY: u32 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    pub const ALL: [Period; 3] = [Period::Week, Period::Month, Period::Year];
+
+    /// Number of intervals of the period in the charts.
+    pub fn intervals(&self) -> u32 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Year => 12,
+        }
+    }
+
+    /// Length of an interval in seconds.
+    pub fn interval(&self) -> u32 {
+        match self {
+            Self::Week | Self::Month => DAY,
+            Self::Year => 365 * DAY / 12,
+        }
+    }
+
+    pub fn duration(&self) -> u32 {
+        self.intervals() * self.interval()
+    }
+
+    fn label_format(&self) -> &'static str {
+        match self {
+            Self::Week | Self::Month => "%d/%m",
+            Self::Year => "%m/%Y",
+        }
+    }
+}
+
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Week => write!(f, "Week"),
+            Self::Month => write!(f, "Month"),
+            Self::Year => write!(f, "Year"),
+        }
+    }
+}
+
+/// Trend has one value per interval of the period, amounts are in satoshis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend {
+    /// Start date of each interval.
+    pub labels: Vec<String>,
+    /// Balance at the end of each interval.
+    pub balance: Vec<u64>,
+    /// Deposits of each interval.
+    pub inflows: Vec<u64>,
+    /// Amounts spent, with their fees, and fees of the cancel transactions,
+    /// the canceled funds are back in a new vault.
+    pub outflows: Vec<u64>,
+}
+
+impl Trend {
+    pub fn total_inflows(&self) -> u64 {
+        self.inflows.iter().sum()
+    }
+
+    pub fn total_outflows(&self) -> u64 {
+        self.outflows.iter().sum()
+    }
+}
+
+/// Returns the trend of the period ending at now, the events outside of
+/// the period are ignored.
+pub fn trend(events: &[HistoryEvent], balance: u64, now: u32, period: Period) -> Trend {
+    let intervals = period.intervals() as usize;
+    let start = now.saturating_sub(period.duration());
+    let mut inflows = vec![0; intervals];
+    let mut outflows = vec![0; intervals];
+    for event in events {
+        if event.date < start || event.date > now {
+            continue;
+        }
+        let i = std::cmp::min(
+            ((event.date - start) / period.interval()) as usize,
+            intervals - 1,
+        );
+        match event.kind {
+            HistoryEventKind::Deposit => inflows[i] += event.amount.unwrap_or(0),
+            HistoryEventKind::Spend => {
+                outflows[i] += event.amount.unwrap_or(0) + event.miner_fee.unwrap_or(0)
+            }
+            HistoryEventKind::Cancel => outflows[i] += event.miner_fee.unwrap_or(0),
+        }
+    }
+
+    // The balance at the end of an interval is the current balance without
+    // the flows of the following intervals.
+    let mut balances = vec![0; intervals];
+    let mut current = balance as i64;
+    for i in (0..intervals).rev() {
+        balances[i] = current.max(0) as u64;
+        current -= inflows[i] as i64 - outflows[i] as i64;
+    }
+
+    let labels = (0..intervals)
+        .map(|i| {
+            NaiveDateTime::from_timestamp((start + i as u32 * period.interval()).into(), 0)
+                .format(period.label_format())
+                .to_string()
+        })
+        .collect();
+
+    Trend {
+        labels,
+        balance: balances,
+        inflows,
+        outflows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::OutPoint;
+
+    fn event(kind: HistoryEventKind, date: u32, amount: u64, fee: Option<u64>) -> HistoryEvent {
+        HistoryEvent {
+            kind,
+            date,
+            blockheight: 10,
+            amount: Some(amount),
+            cpfp_amount: None,
+            miner_fee: fee,
+            txid: OutPoint::default().txid,
+            vaults: vec![OutPoint::default()],
+        }
+    }
+
+    #[test]
+    fn test_trend() {
+        let now = 100 * DAY;
+        let events = vec![
+            event(HistoryEventKind::Spend, now - DAY / 2, 30_000, Some(1_000)),
+            event(
+                HistoryEventKind::Cancel,
+                now - 2 * DAY - 1,
+                50_000,
+                Some(500),
+            ),
+            event(HistoryEventKind::Deposit, now - 3 * DAY, 100_000, None),
+            event(HistoryEventKind::Deposit, now - 8 * DAY, 200_000, None),
+        ];
+        let trend = trend(&events, 268_500, now, Period::Week);
+        assert_eq!(trend.labels.len(), 7);
+        assert_eq!(trend.labels[0], "04/04");
+        assert_eq!(trend.inflows, vec![0, 0, 0, 0, 100_000, 0, 0]);
+        assert_eq!(trend.outflows, vec![0, 0, 0, 0, 500, 0, 31_000]);
+        assert_eq!(
+            trend.balance,
+            vec![200_000, 200_000, 200_000, 200_000, 299_500, 299_500, 268_500]
+        );
+        assert_eq!(trend.total_inflows(), 100_000);
+        assert_eq!(trend.total_outflows(), 31_500);
+    }
+}
@@ -0,0 +1,438 @@
+//! The presigned transactions given by the daemon are rebuilt from the
+//! descriptors of the configuration before being signed, a compromised daemon
+//! could otherwise have the stakeholders sign transactions paying anywhere.
+
+use bitcoin::{
+    secp256k1::{Secp256k1, VerifyOnly},
+    util::{bip32::ChildNumber, psbt::PartiallySignedTransaction as Psbt},
+    Address, Amount, Network, Script,
+};
+
+use revaultd::{
+    config::Config as DaemonConfig,
+    revault_tx::{
+        scripts::{CpfpDescriptor, DepositDescriptor, EmergencyAddress, UnvaultDescriptor},
+        transactions::{
+            transaction_chain, transaction_chain_manager, RevaultTransaction, UnvaultTransaction,
+        },
+        txins::DepositTxIn,
+        txouts::{CpfpTxOut, DepositTxOut, RevaultTxOut, UnvaultTxOut},
+    },
+};
+
+use crate::{
+    app::{error::Error, inspector::ScriptKind},
+    daemon::model::{outpoint, RevocationTransactions, Vault},
+};
+
+/// Rebuilds the transactions of a vault like the daemon and the signing
+/// devices do.
+#[derive(Debug, Clone)]
+pub struct TransactionRebuilder {
+    network: Network,
+    deposit_descriptor: DepositDescriptor,
+    unvault_descriptor: UnvaultDescriptor,
+    cpfp_descriptor: CpfpDescriptor,
+    /// Only the stakeholders know the emergency address.
+    emergency_address: Option<EmergencyAddress>,
+    secp: Secp256k1<VerifyOnly>,
+}
+
+impl TransactionRebuilder {
+    pub fn new(
+        network: Network,
+        deposit_descriptor: DepositDescriptor,
+        unvault_descriptor: UnvaultDescriptor,
+        cpfp_descriptor: CpfpDescriptor,
+        emergency_address: Option<EmergencyAddress>,
+    ) -> Self {
+        Self {
+            network,
+            deposit_descriptor,
+            unvault_descriptor,
+            cpfp_descriptor,
+            emergency_address,
+            secp: Secp256k1::verification_only(),
+        }
+    }
+
+    pub fn from_config(config: &DaemonConfig) -> Self {
+        Self::new(
+            config.bitcoind_config.network,
+            config.scripts_config.deposit_descriptor.clone(),
+            config.scripts_config.unvault_descriptor.clone(),
+            config.scripts_config.cpfp_descriptor.clone(),
+            config
+                .stakeholder_config
+                .as_ref()
+                .map(|cfg| cfg.emergency_address.clone()),
+        )
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns the scripts of the wallet outputs derived at the given indexes.
+    pub fn wallet_scripts(&self, indexes: &[ChildNumber]) -> Vec<(Script, ScriptKind)> {
+        let mut scripts = Vec::new();
+        for index in indexes {
+            if index.is_hardened() {
+                continue;
+            }
+            let amount = Amount::from_sat(0);
+            scripts.push((
+                DepositTxOut::new(amount, &self.deposit_descriptor.derive(*index, &self.secp))
+                    .into_txout()
+                    .script_pubkey,
+                ScriptKind::Deposit,
+            ));
+            scripts.push((
+                UnvaultTxOut::new(amount, &self.unvault_descriptor.derive(*index, &self.secp))
+                    .into_txout()
+                    .script_pubkey,
+                ScriptKind::Unvault,
+            ));
+            scripts.push((
+                CpfpTxOut::new(amount, &self.cpfp_descriptor.derive(*index, &self.secp))
+                    .into_txout()
+                    .script_pubkey,
+                ScriptKind::Cpfp,
+            ));
+        }
+        if let Some(address) = &self.emergency_address {
+            scripts.push((address.address().script_pubkey(), ScriptKind::Emergency));
+        }
+        scripts
+    }
+
+    /// Returns the cancel transactions of the vault, from the lowest to the
+    /// highest feerate. They do not need the emergency address.
+    pub fn cancel_txs(&self, vault: &Vault) -> Result<[Psbt; 5], Error> {
+        let (_, cancel_txs) = transaction_chain_manager(
+            outpoint(vault),
+            vault.amount,
+            &self.deposit_descriptor,
+            &self.unvault_descriptor,
+            &self.cpfp_descriptor,
+            vault.derivation_index,
+            &self.secp,
+        )
+        .map_err(|e| Error::Unexpected(format!("Failed to rebuild the transactions: {}", e)))?;
+        let cancel_txs = cancel_txs.all_feerates();
+        Ok([
+            cancel_txs[0].psbt().clone(),
+            cancel_txs[1].psbt().clone(),
+            cancel_txs[2].psbt().clone(),
+            cancel_txs[3].psbt().clone(),
+            cancel_txs[4].psbt().clone(),
+        ])
+    }
+
+    /// Returns the emergency, unvault-emergency and cancel transactions of the vault.
+    pub fn revocation_txs(&self, vault: &Vault) -> Result<(Psbt, Psbt, [Psbt; 5]), Error> {
+        let emergency_address = self.emergency_address.clone().ok_or_else(|| {
+            Error::Unexpected("The emergency address is not configured".to_string())
+        })?;
+        let (_, cancel_txs, emergency_tx, emergency_unvault_tx) = transaction_chain(
+            outpoint(vault),
+            vault.amount,
+            &self.deposit_descriptor,
+            &self.unvault_descriptor,
+            &self.cpfp_descriptor,
+            vault.derivation_index,
+            emergency_address,
+            &self.secp,
+        )
+        .map_err(|e| Error::Unexpected(format!("Failed to rebuild the transactions: {}", e)))?;
+        let cancel_txs = cancel_txs.all_feerates();
+        Ok((
+            emergency_tx.into_psbt(),
+            emergency_unvault_tx.into_psbt(),
+            [
+                cancel_txs[0].psbt().clone(),
+                cancel_txs[1].psbt().clone(),
+                cancel_txs[2].psbt().clone(),
+                cancel_txs[3].psbt().clone(),
+                cancel_txs[4].psbt().clone(),
+            ],
+        ))
+    }
+
+    pub fn unvault_tx(&self, vault: &Vault) -> Result<Psbt, Error> {
+        let deposit_descriptor = self
+            .deposit_descriptor
+            .derive(vault.derivation_index, &self.secp);
+        let deposit_txin = DepositTxIn::new(
+            outpoint(vault),
+            DepositTxOut::new(vault.amount, &deposit_descriptor),
+        );
+        let unvault_tx = UnvaultTransaction::new(
+            deposit_txin,
+            &self
+                .unvault_descriptor
+                .derive(vault.derivation_index, &self.secp),
+            &self
+                .cpfp_descriptor
+                .derive(vault.derivation_index, &self.secp),
+        )
+        .map_err(|e| Error::Unexpected(format!("Failed to rebuild the transaction: {}", e)))?;
+        Ok(unvault_tx.into_psbt())
+    }
+
+    /// Fails with the list of the differences if the revocation transactions
+    /// of the daemon are not the expected ones.
+    pub fn verify_revocation_txs(
+        &self,
+        vault: &Vault,
+        txs: &RevocationTransactions,
+    ) -> Result<(), Error> {
+        let (emergency_tx, emergency_unvault_tx, cancel_txs) = self.revocation_txs(vault)?;
+        let mut differences = diff(
+            "Emergency transaction",
+            &emergency_tx,
+            txs.emergency_tx.psbt(),
+            self.network,
+        );
+        differences.extend(diff(
+            "Unvault-emergency transaction",
+            &emergency_unvault_tx,
+            txs.emergency_unvault_tx.psbt(),
+            self.network,
+        ));
+        for (i, cancel_tx) in cancel_txs.iter().enumerate() {
+            differences.extend(diff(
+                &format!("Cancel transaction #{}", i + 1),
+                cancel_tx,
+                txs.cancel_txs[i].psbt(),
+                self.network,
+            ));
+        }
+        check(vault, differences)
+    }
+
+    /// Fails with the list of the differences if the unvault transaction of
+    /// the daemon is not the expected one.
+    pub fn verify_unvault_tx(&self, vault: &Vault, unvault_tx: &Psbt) -> Result<(), Error> {
+        let expected = self.unvault_tx(vault)?;
+        check(
+            vault,
+            diff("Unvault transaction", &expected, unvault_tx, self.network),
+        )
+    }
+}
+
+fn check(vault: &Vault, differences: Vec<String>) -> Result<(), Error> {
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::TransactionMismatch(outpoint(vault), differences))
+    }
+}
+
+/// Lists what the signature of the received transaction would commit to
+/// differently than the signature of the expected one.
+pub fn diff(name: &str, expected: &Psbt, received: &Psbt, network: Network) -> Vec<String> {
+    let mut differences = Vec::new();
+    let (exp, got) = (&expected.global.unsigned_tx, &received.global.unsigned_tx);
+    if exp.version != got.version {
+        differences.push(format!(
+            "{}: version {} instead of {}",
+            name, got.version, exp.version
+        ));
+    }
+    if exp.lock_time != got.lock_time {
+        differences.push(format!(
+            "{}: locktime {} instead of {}",
+            name, got.lock_time, exp.lock_time
+        ));
+    }
+
+    if exp.input.len() != got.input.len() {
+        differences.push(format!(
+            "{}: {} inputs instead of {}",
+            name,
+            got.input.len(),
+            exp.input.len()
+        ));
+    }
+    for (i, (exp_in, got_in)) in exp.input.iter().zip(got.input.iter()).enumerate() {
+        if exp_in.previous_output != got_in.previous_output {
+            differences.push(format!(
+                "{}: input #{} spends {} instead of {}",
+                name, i, got_in.previous_output, exp_in.previous_output
+            ));
+        }
+        if exp_in.sequence != got_in.sequence {
+            differences.push(format!(
+                "{}: input #{} has the sequence {} instead of {}",
+                name, i, got_in.sequence, exp_in.sequence
+            ));
+        }
+    }
+    for (i, (exp_in, got_in)) in expected
+        .inputs
+        .iter()
+        .zip(received.inputs.iter())
+        .enumerate()
+    {
+        if exp_in.witness_utxo != got_in.witness_utxo {
+            differences.push(format!(
+                "{}: input #{} has an unexpected previous output",
+                name, i
+            ));
+        }
+        if exp_in.witness_script != got_in.witness_script {
+            differences.push(format!(
+                "{}: input #{} has an unexpected witness script",
+                name, i
+            ));
+        }
+        if exp_in.sighash_type != got_in.sighash_type {
+            differences.push(format!(
+                "{}: input #{} has the sighash type {:?} instead of {:?}",
+                name, i, got_in.sighash_type, exp_in.sighash_type
+            ));
+        }
+    }
+
+    if exp.output.len() != got.output.len() {
+        differences.push(format!(
+            "{}: {} outputs instead of {}",
+            name,
+            got.output.len(),
+            exp.output.len()
+        ));
+    }
+    for (i, (exp_out, got_out)) in exp.output.iter().zip(got.output.iter()).enumerate() {
+        if exp_out.script_pubkey != got_out.script_pubkey {
+            differences.push(format!(
+                "{}: output #{} pays to {} instead of {}",
+                name,
+                i,
+                destination(&got_out.script_pubkey, network),
+                destination(&exp_out.script_pubkey, network)
+            ));
+        }
+        if exp_out.value != got_out.value {
+            differences.push(format!(
+                "{}: output #{} has a value of {} sats instead of {} sats",
+                name, i, got_out.value, exp_out.value
+            ));
+        }
+    }
+    differences
+}
+
+fn destination(script: &Script, network: Network) -> String {
+    Address::from_script(script, network)
+        .map(|address| address.to_string())
+        .unwrap_or_else(|| script.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::model::VaultStatus;
+    use bitcoin::{base64, consensus::encode, util::bip32::ChildNumber, Amount, Txid};
+    use revaultd::revault_tx::transactions::UNVAULT_CPFP_VALUE;
+    use std::str::FromStr;
+
+    // Descriptors and transactions of the dummysigner examples.
+    const DEPOSIT_DESCRIPTOR: &str = "wsh(multi(2,xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg/*,xpub6F7Ltmsut73cbUNAzh44DkxncMeQfPtRzx7aoXjFbUdd7yofR2intU4b6QcsXot1jgmVjHB3iMybCLhtqvhAx3L4VPbGUz5fwuyNeTkypUP/*))#asfyu8z9";
+    const UNVAULT_DESCRIPTOR: &str = "wsh(andor(thresh(1,pk(xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9/*)),older(8),thresh(2,pkh(xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg/*),a:pkh(xpub6F7Ltmsut73cbUNAzh44DkxncMeQfPtRzx7aoXjFbUdd7yofR2intU4b6QcsXot1jgmVjHB3iMybCLhtqvhAx3L4VPbGUz5fwuyNeTkypUP/*))))#7kgsjsga";
+    const CPFP_DESCRIPTOR: &str = "wsh(thresh(1,pk(xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx/*)))#r9m50cqk";
+    const EMERGENCY_ADDRESS: &str =
+        "bcrt1qewc2348370pgw8kjz8gy09z8xyh0d9fxde6nzamd3txc9gkmjqmq8m4cdq";
+    const UNVAULT_TX: &str = "cHNidP8BAIkCAAAAAUeuD/NEqc88sk3DoBrKoVKjXbN2xW8Jr/4GO5q87JqJAQAAAAD9////AriGJgcAAAAAIgAgSOjPZes2prPdrcgiv+IG1sjXyTCc4KDr9+C9F+xk6LwwdQAAAAAAACIAIAjkMa8elv7dHUmYpDATWBtmMmpv9yyKFawMunvGQ1AMAAAAAAABASsADicHAAAAACIAIHXyaRd0yBZ3gxhGsCgiAOKIssWXELWPdDGD1JJVB9vFAQVHUiECWC3tv0T0ZWTl2M2wZ1NtYOvjTNHRgBz/Ubv516wom0MhA0cE3stVtaqI/9HvXQY2YkjBMU4ZZVETb/FOq4u6SkkOUq4iBgJYLe2/RPRlZOXYzbBnU21g6+NM0dGAHP9Ru/nXrCibQwjWfX/pAAAAACIGA0cE3stVtaqI/9HvXQY2YkjBMU4ZZVETb/FOq4u6SkkOCHKpXyIAAAAAACICAgKTOrEDfq0KpKeFjG1J1nBeH7O8X2awCRive58A7NUmCCUdYAkAAAAAIgICWC3tv0T0ZWTl2M2wZ1NtYOvjTNHRgBz/Ubv516wom0MI1n1/6QAAAAAiAgNHBN7LVbWqiP/R710GNmJIwTFOGWVRE2/xTquLukpJDghyqV8iAAAAAAAiAgJQcvTgdleKXInUHXXx7VKav5LxzWq2Nrjot7sPMwJpQwj73ezNAAAAAAA=";
+
+    fn rebuilder() -> TransactionRebuilder {
+        TransactionRebuilder::new(
+            Network::Regtest,
+            DepositDescriptor::from_str(DEPOSIT_DESCRIPTOR).unwrap(),
+            UnvaultDescriptor::from_str(UNVAULT_DESCRIPTOR).unwrap(),
+            CpfpDescriptor::from_str(CPFP_DESCRIPTOR).unwrap(),
+            Some(EmergencyAddress::from(Address::from_str(EMERGENCY_ADDRESS).unwrap()).unwrap()),
+        )
+    }
+
+    fn vault() -> Vault {
+        Vault {
+            address: Address::from_str(EMERGENCY_ADDRESS).unwrap(),
+            amount: Amount::from_sat(120_000_000),
+            derivation_index: ChildNumber::from_normal_idx(0).unwrap(),
+            status: VaultStatus::Funded,
+            txid: Txid::from_str(
+                "899aecbc9a3b06feaf096fc576b35da352a1ca1aa0c34db23ccfa944f30fae47",
+            )
+            .unwrap(),
+            vout: 1,
+            blockheight: Some(1),
+            delegated_at: None,
+            secured_at: None,
+            funded_at: Some(1),
+            moved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_unvault_tx() {
+        let rebuilder = rebuilder();
+        let vault = vault();
+        let unvault_tx: Psbt = encode::deserialize(&base64::decode(UNVAULT_TX).unwrap()).unwrap();
+        assert_eq!(rebuilder.unvault_tx(&vault).unwrap(), unvault_tx);
+        rebuilder.verify_unvault_tx(&vault, &unvault_tx).unwrap();
+
+        // The unvault output receives the deposit minus the cpfp output and the fee.
+        let deposit = unvault_tx.inputs[0].witness_utxo.as_ref().unwrap().value;
+        assert_eq!(deposit, vault.amount.as_sat());
+        let fee = deposit
+            - unvault_tx
+                .global
+                .unsigned_tx
+                .output
+                .iter()
+                .map(|output| output.value)
+                .sum::<u64>();
+        let value = deposit - UNVAULT_CPFP_VALUE - fee;
+
+        let mut tampered = unvault_tx.clone();
+        tampered.global.unsigned_tx.output[0].value -= 1_000;
+        tampered.global.unsigned_tx.output[1].script_pubkey = Address::from_str(EMERGENCY_ADDRESS)
+            .unwrap()
+            .script_pubkey();
+        match rebuilder.verify_unvault_tx(&vault, &tampered) {
+            Err(Error::TransactionMismatch(_, differences)) => assert_eq!(
+                differences,
+                vec![
+                    format!(
+                        "Unvault transaction: output #0 has a value of {} sats instead of {} sats",
+                        value - 1_000,
+                        value
+                    ),
+                    format!(
+                        "Unvault transaction: output #1 pays to {} instead of {}",
+                        EMERGENCY_ADDRESS,
+                        destination(
+                            &unvault_tx.global.unsigned_tx.output[1].script_pubkey,
+                            Network::Regtest
+                        )
+                    ),
+                ]
+            ),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_rebuild_revocation_txs() {
+        let (emergency_tx, emergency_unvault_tx, cancel_txs) =
+            rebuilder().revocation_txs(&vault()).unwrap();
+        assert_eq!(
+            base64::encode(&encode::serialize(&emergency_tx)),
+            "cHNidP8BAF4CAAAAAUeuD/NEqc88sk3DoBrKoVKjXbN2xW8Jr/4GO5q87JqJAQAAAAD9////ARDEJAcAAAAAIgAgy7Co1PHzwoce0hHQR5RHMS72lSZudTF3bYrNgqLbkDYAAAAAAAEBKwAOJwcAAAAAIgAgdfJpF3TIFneDGEawKCIA4oiyxZcQtY90MYPUklUH28UBBUdSIQJYLe2/RPRlZOXYzbBnU21g6+NM0dGAHP9Ru/nXrCibQyEDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ5SriIGAlgt7b9E9GVk5djNsGdTbWDr40zR0YAc/1G7+desKJtDCNZ9f+kAAAAAIgYDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ4IcqlfIgAAAAAAAA=="
+        );
+        assert!(!diff("", &emergency_tx, &emergency_unvault_tx, Network::Regtest).is_empty());
+        assert!(diff("", &cancel_txs[0], &cancel_txs[0], Network::Regtest).is_empty());
+    }
+}
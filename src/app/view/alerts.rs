@@ -0,0 +1,189 @@
+use iced::{alignment, Alignment, Column, Container, Element, Length, Row};
+
+use revaultd::revault_tx::transactions::RevaultTransaction;
+
+use revault_ui::{
+    color,
+    component::{button, card, separation, text::Text},
+    icon,
+    util::Collection,
+};
+
+use crate::{
+    app::{
+        context::Context,
+        error::Error,
+        label::LabelKind,
+        message::Message,
+        view::{
+            layout,
+            vault::{timelock_countdown, vault_badge},
+        },
+    },
+    daemon::model::{outpoint, SpendTx, Vault, VaultStatus},
+};
+
+/// UnvaultAlertsView renders the list of the moving vaults.
+#[derive(Debug, Default)]
+pub struct UnvaultAlertsView {
+    dashboard: layout::Dashboard,
+}
+
+impl UnvaultAlertsView {
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        warning: Option<&Error>,
+        alerts: Vec<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        let mut col = Column::new()
+            .push(Text::new("Unvault alerts").bold())
+            .spacing(20);
+        if alerts.is_empty() {
+            col = col.push(card::white(Container::new(
+                Text::new("No vault is moving").color(color::DARK_GREY),
+            )));
+        } else {
+            col = col.push(Column::with_children(alerts).spacing(10));
+        }
+        self.dashboard.view(ctx, warning, col)
+    }
+}
+
+/// UnvaultAlertView renders a moving vault, the spend transaction it is
+/// unvaulted for and the countdown of the unvault timelock.
+#[derive(Debug, Default)]
+pub struct UnvaultAlertView {
+    cancel_button: iced::button::State,
+}
+
+impl UnvaultAlertView {
+    #[allow(clippy::too_many_arguments)]
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        vault: &Vault,
+        spend_tx: Option<&SpendTx>,
+        blocks_before_timelock: Option<u32>,
+        can_cancel: bool,
+        processing: bool,
+        warning: Option<&Error>,
+    ) -> Element<'a, Message> {
+        let timelock = timelock_countdown(blocks_before_timelock);
+
+        let action: Element<'a, Message> = if processing {
+            button::primary_disable(
+                &mut self.cancel_button,
+                button::button_content(None, "Canceling..."),
+            )
+            .into()
+        } else if can_cancel {
+            button::important(
+                &mut self.cancel_button,
+                button::button_content(Some(icon::turnback_icon()), "Cancel"),
+            )
+            .on_press(Message::CancelUnvault(outpoint(vault)))
+            .into()
+        } else if vault.status == VaultStatus::Canceling {
+            Text::new("Canceling").small().bold().into()
+        } else {
+            Column::new().into()
+        };
+
+        card::white(Container::new(
+            Column::new()
+                .push(
+                    Row::new()
+                        .push(vault_badge(vault))
+                        .push(
+                            Column::new()
+                                .push(
+                                    Text::new(&format!(
+                                        "{} {}",
+                                        ctx.converter.converts(vault.amount),
+                                        ctx.converter.unit
+                                    ))
+                                    .bold(),
+                                )
+                                .push(
+                                    Text::new(
+                                        &ctx.labels.label_or(
+                                            LabelKind::Output,
+                                            outpoint(vault).to_string(),
+                                        ),
+                                    )
+                                    .small(),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            Column::new()
+                                .push(Text::new(&vault.status.to_string()).small())
+                                .push(timelock)
+                                .align_items(Alignment::End),
+                        )
+                        .push(
+                            Container::new(action)
+                                .width(Length::Units(150))
+                                .align_x(alignment::Horizontal::Right),
+                        )
+                        .align_items(Alignment::Center)
+                        .spacing(20),
+                )
+                .push(separation().width(Length::Fill))
+                .push(spend_destinations(ctx, spend_tx))
+                .push_maybe(warning.map(|e| Text::new(&e.to_string()).small().color(color::ALERT)))
+                .spacing(10),
+        ))
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+/// Lists the recipients of the spend transaction, without the change and the CPFP outputs.
+fn spend_destinations<'a>(ctx: &Context, spend_tx: Option<&SpendTx>) -> Element<'a, Message> {
+    let tx = if let Some(tx) = spend_tx {
+        tx
+    } else {
+        return Text::new("No known spend transaction matches this unvault")
+            .small()
+            .bold()
+            .color(color::ALERT)
+            .into();
+    };
+
+    let psbt = tx.psbt.psbt();
+    let mut col = Column::new()
+        .push(
+            Text::new(&format!(
+                "Spend transaction {}",
+                psbt.global.unsigned_tx.txid()
+            ))
+            .small()
+            .bold(),
+        )
+        .spacing(5);
+    for (i, output) in psbt.global.unsigned_tx.output.iter().enumerate() {
+        if Some(i) == tx.change_index || i == tx.cpfp_index {
+            continue;
+        }
+        let address = bitcoin::Address::from_script(&output.script_pubkey, ctx.network())
+            .map(|addr| ctx.labels.label_or(LabelKind::Addr, addr.to_string()))
+            .unwrap_or_else(|| output.script_pubkey.to_string());
+        col = col.push(
+            Row::new()
+                .push(Text::new(&address).small().width(Length::Fill))
+                .push(
+                    Text::new(&format!(
+                        "{} {}",
+                        ctx.converter
+                            .converts(bitcoin::Amount::from_sat(output.value)),
+                        ctx.converter.unit
+                    ))
+                    .small()
+                    .bold(),
+                ),
+        );
+    }
+    col.into()
+}
@@ -1,18 +1,35 @@
-use bitcoin::Amount;
-use iced::{Alignment, Column, Container, Element, Length, Row};
+use std::path::PathBuf;
+
+use bitcoin::{Amount, OutPoint, Transaction, Txid};
+use iced::{Alignment, Checkbox, Column, Container, Element, Length, Row};
 
 use revault_ui::{
     color,
-    component::{button, card, text::Text},
+    component::{button, card, form, text::Text},
     icon::warning_icon,
 };
 
-use crate::app::{context::Context, error::Error, menu::Menu, message::Message, view::layout};
+use crate::{
+    app::{
+        context::Context,
+        emergency_kit::{KitStatus, KitTransaction, KitTransactionKind},
+        error::Error,
+        menu::Menu,
+        message::{EmergencyKitMessage, Message, SelectedEmergencyMessage},
+        view::layout,
+    },
+    daemon::{
+        model::{outpoint, Vault},
+        RevaultDError,
+    },
+};
 
 #[derive(Debug)]
 pub struct EmergencyView {
     modal: layout::Modal,
     emergency_button: iced::button::State,
+    kit: EmergencyKitView,
+    selection: SelectedEmergencyView,
 }
 
 impl EmergencyView {
@@ -20,9 +37,12 @@ impl EmergencyView {
         EmergencyView {
             modal: layout::Modal::default(),
             emergency_button: iced::button::State::new(),
+            kit: EmergencyKitView::default(),
+            selection: SelectedEmergencyView::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
@@ -30,7 +50,29 @@ impl EmergencyView {
         funds_amount: u64,
         warning: Option<&Error>,
         processing: bool,
+        kit_status: KitStatus,
+        kit_path: &form::Value<String>,
+        kit_processing: bool,
+        kit_exported: Option<&(usize, PathBuf)>,
+        vaults: &[Vault],
+        selected: &[OutPoint],
+        transactions: Option<&[(KitTransaction, Transaction)]>,
+        broadcasted: Option<&[Result<Txid, RevaultDError>]>,
+        selection_processing: bool,
     ) -> Element<'a, Message> {
+        // Once the selected vaults are reviewed, the confirmation is the only
+        // content of the screen.
+        if let Some(transactions) = transactions {
+            return self.modal.view(
+                ctx,
+                warning,
+                self.selection
+                    .confirmation(ctx, transactions, broadcasted, selection_processing),
+                None,
+                Message::Menu(Menu::Home),
+            );
+        }
+
         let mut emergency_button = button::primary(
             &mut self.emergency_button,
             button::button_content(None, "Emergency"),
@@ -77,16 +119,374 @@ impl EmergencyView {
         self.modal.view(
             ctx,
             warning,
-            card::border_primary(Container::new(content))
-                .padding(20)
-                .center_x()
-                .width(Length::Fill),
+            Column::new()
+                .push(
+                    card::border_primary(Container::new(content))
+                        .padding(20)
+                        .center_x()
+                        .width(Length::Fill),
+                )
+                .push(
+                    self.selection
+                        .view(ctx, vaults, selected, selection_processing),
+                )
+                .push(
+                    self.kit
+                        .view(kit_status, kit_path, kit_processing, kit_exported),
+                )
+                .spacing(20),
             None,
             Message::Menu(Menu::Home),
         )
     }
 }
 
+#[derive(Debug, Default)]
+struct EmergencyKitView {
+    path_input: iced::text_input::State,
+    export_button: iced::button::State,
+}
+
+impl EmergencyKitView {
+    fn view<'a>(
+        &'a mut self,
+        status: KitStatus,
+        path: &form::Value<String>,
+        processing: bool,
+        exported: Option<&(usize, PathBuf)>,
+    ) -> Element<'a, Message> {
+        let status = match status {
+            KitStatus::UpToDate => None,
+            KitStatus::Missing => Some("No emergency kit was exported"),
+            KitStatus::Outdated(_) => {
+                Some("Vaults were secured since the last export, the emergency kit is out of date")
+            }
+        };
+
+        let mut export_button = button::primary(
+            &mut self.export_button,
+            button::button_content(
+                None,
+                if processing {
+                    "Exporting..."
+                } else {
+                    "Export emergency kit"
+                },
+            ),
+        );
+        if !processing {
+            export_button =
+                export_button.on_press(Message::EmergencyKit(EmergencyKitMessage::Export));
+        }
+
+        let mut col = Column::new()
+            .push(Text::new("Emergency kit").bold())
+            .push(
+                Text::new(
+                    "The fully signed emergency transactions of the vaults, \
+                    written in hex with a manifest to broadcast them with any bitcoin node \
+                    if the daemon is not reachable.",
+                )
+                .small(),
+            )
+            .spacing(20);
+
+        if let Some(status) = status {
+            col = col.push(Text::new(status).small().bold().color(color::ALERT));
+        }
+
+        col = col.push(
+            Row::new()
+                .push(
+                    form::Form::new(&mut self.path_input, "Directory", path, |s| {
+                        Message::EmergencyKit(EmergencyKitMessage::PathEdited(s))
+                    })
+                    .warning("Please enter a directory path")
+                    .padding(10)
+                    .render()
+                    .width(Length::Fill),
+                )
+                .push(export_button)
+                .spacing(20)
+                .align_items(Alignment::Center),
+        );
+
+        if let Some((count, path)) = exported {
+            col = col.push(
+                Text::new(&format!(
+                    "Emergency kit of {} vaults exported to {}",
+                    count,
+                    path.to_string_lossy()
+                ))
+                .small()
+                .success(),
+            );
+        }
+
+        card::white(Container::new(col))
+            .padding(20)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+#[derive(Debug, Default)]
+struct SelectedEmergencyView {
+    review_button: iced::button::State,
+    back_button: iced::button::State,
+    broadcast_button: iced::button::State,
+}
+
+impl SelectedEmergencyView {
+    fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        vaults: &[Vault],
+        selected: &[OutPoint],
+        processing: bool,
+    ) -> Element<'a, Message> {
+        let mut col = Column::new()
+            .push(Text::new("Selected vaults").bold())
+            .push(
+                Text::new(
+                    "Send the funds of the selected vaults only to the Emergency Deep Vault, \
+                    with their emergency or unvault emergency transactions.",
+                )
+                .small(),
+            )
+            .spacing(20);
+
+        if vaults.is_empty() {
+            col = col.push(
+                Text::new("No vault can be sent to the Emergency Deep Vault")
+                    .small()
+                    .color(color::DARK_GREY),
+            );
+        } else {
+            let mut col_vaults = Column::new().spacing(10);
+            for vault in vaults {
+                let vault_outpoint = outpoint(vault);
+                col_vaults = col_vaults.push(
+                    Row::new()
+                        .push(
+                            Checkbox::new(
+                                selected.contains(&vault_outpoint),
+                                vault_outpoint.to_string(),
+                                move |checked| {
+                                    Message::SelectedEmergency(
+                                        SelectedEmergencyMessage::VaultToggled(
+                                            vault_outpoint,
+                                            checked,
+                                        ),
+                                    )
+                                },
+                            )
+                            .text_size(15)
+                            .width(Length::Fill),
+                        )
+                        .push(Text::new(&vault.status.to_string()).small())
+                        .push(
+                            Text::new(&format!(
+                                "{} {}",
+                                ctx.converter.converts(vault.amount),
+                                ctx.converter.unit
+                            ))
+                            .small()
+                            .bold(),
+                        )
+                        .spacing(20)
+                        .align_items(Alignment::Center),
+                );
+            }
+            col = col.push(col_vaults);
+        }
+
+        let amount = vaults
+            .iter()
+            .filter(|vault| selected.contains(&outpoint(vault)))
+            .fold(0, |acc, vault| acc + vault.amount.as_sat());
+        let mut review_button = button::primary(
+            &mut self.review_button,
+            button::button_content(None, if processing { "Loading..." } else { "Review" }),
+        );
+        if !selected.is_empty() && !processing {
+            review_button = review_button
+                .on_press(Message::SelectedEmergency(SelectedEmergencyMessage::Review));
+        }
+        col = col.push(
+            Row::new()
+                .push(
+                    Text::new(&format!(
+                        "{} selected vaults, {} {}",
+                        selected.len(),
+                        ctx.converter.converts(Amount::from_sat(amount)),
+                        ctx.converter.unit
+                    ))
+                    .small()
+                    .width(Length::Fill),
+                )
+                .push(review_button)
+                .spacing(20)
+                .align_items(Alignment::Center),
+        );
+
+        card::white(Container::new(col))
+            .padding(20)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn confirmation<'a>(
+        &'a mut self,
+        ctx: &Context,
+        transactions: &[(KitTransaction, Transaction)],
+        broadcasted: Option<&[Result<Txid, RevaultDError>]>,
+        processing: bool,
+    ) -> Element<'a, Message> {
+        let amount: u64 = transactions.iter().map(|(entry, _)| entry.amount).sum();
+        let mut addresses: Vec<&str> = Vec::new();
+        for (entry, _) in transactions {
+            if !addresses.contains(&entry.emergency_address.as_str()) {
+                addresses.push(&entry.emergency_address);
+            }
+        }
+
+        let mut col_destination = Column::new()
+            .push(Text::new("to the Emergency Deep Vault address"))
+            .align_items(Alignment::Center);
+        for address in addresses {
+            col_destination = col_destination.push(Text::new(address).bold());
+        }
+
+        let mut col_txs = Column::new().spacing(10);
+        for (i, (entry, tx)) in transactions.iter().enumerate() {
+            let received: u64 = tx.output.iter().map(|output| output.value).sum();
+            let mut col_tx = Column::new().push(
+                Row::new()
+                    .push(
+                        Text::new(&entry.vault.to_string())
+                            .small()
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        Text::new(match entry.kind {
+                            KitTransactionKind::Emergency => "Emergency",
+                            KitTransactionKind::UnvaultEmergency => "Unvault emergency",
+                        })
+                        .small(),
+                    )
+                    .push(
+                        Text::new(&format!(
+                            "{} {}",
+                            ctx.converter.converts(Amount::from_sat(entry.amount)),
+                            ctx.converter.unit
+                        ))
+                        .small()
+                        .bold(),
+                    )
+                    .push(
+                        Text::new(&format!(
+                            "{} {} received",
+                            ctx.converter.converts(Amount::from_sat(received)),
+                            ctx.converter.unit
+                        ))
+                        .small()
+                        .color(color::DARK_GREY),
+                    )
+                    .spacing(20)
+                    .align_items(Alignment::Center),
+            );
+            match broadcasted.and_then(|results| results.get(i)) {
+                Some(Ok(txid)) => {
+                    col_tx = col_tx.push(
+                        Text::new(&format!("Broadcast: {}", txid))
+                            .small()
+                            .color(color::SUCCESS),
+                    );
+                }
+                Some(Err(e)) => {
+                    col_tx = col_tx.push(
+                        Text::new(&format!("Rejected: {}", e))
+                            .small()
+                            .color(color::ALERT),
+                    );
+                }
+                None => {}
+            }
+            col_txs = col_txs.push(col_tx.spacing(5));
+        }
+
+        let mut back_button =
+            button::transparent(&mut self.back_button, button::button_content(None, "Back"));
+        let mut broadcast_button = button::important(
+            &mut self.broadcast_button,
+            button::button_content(
+                None,
+                if processing {
+                    "Broadcasting..."
+                } else if broadcasted.is_some() {
+                    "Broadcast again"
+                } else {
+                    "Broadcast"
+                },
+            ),
+        );
+        if !processing {
+            back_button =
+                back_button.on_press(Message::SelectedEmergency(SelectedEmergencyMessage::Back));
+            broadcast_button = broadcast_button.on_press(Message::SelectedEmergency(
+                SelectedEmergencyMessage::Broadcast,
+            ));
+        }
+
+        card::border_primary(Container::new(
+            Column::new()
+                .push(warning_icon().color(color::PRIMARY))
+                .push(
+                    Column::new()
+                        .push(
+                            Row::new()
+                                .push(Text::new("This action will send"))
+                                .push(
+                                    Text::new(&format!(
+                                        " {} ",
+                                        ctx.converter.converts(Amount::from_sat(amount))
+                                    ))
+                                    .bold(),
+                                )
+                                .push(Text::new(&ctx.converter.unit.to_string()))
+                                .push(Text::new(" from"))
+                                .push(Text::new(&format!(" {} ", transactions.len())).bold())
+                                .push(Text::new("vaults")),
+                        )
+                        .push(col_destination)
+                        .align_items(Alignment::Center),
+                )
+                .push(col_txs)
+                .push(
+                    Text::new(
+                        "The received amounts are the ones of the vaults without the fees \
+                        of the presigned transactions.",
+                    )
+                    .small()
+                    .color(color::DARK_GREY),
+                )
+                .push(
+                    Row::new()
+                        .push(back_button)
+                        .push(broadcast_button)
+                        .spacing(20),
+                )
+                .spacing(30)
+                .align_items(Alignment::Center),
+        ))
+        .padding(20)
+        .center_x()
+        .width(Length::Fill)
+        .into()
+    }
+}
+
 #[derive(Debug)]
 pub struct EmergencyTriggeredView {
     modal: layout::Modal,
@@ -1,107 +1,189 @@
+use std::path::PathBuf;
+
 use bitcoin::Amount;
 use chrono::NaiveDateTime;
-use iced::{alignment, pick_list, Alignment, Column, Container, Element, Length, Row};
+use iced::{
+    alignment, pick_list, text_input, Alignment, Checkbox, Column, Container, Element, Length,
+    ProgressBar, Row,
+};
 
 use revault_ui::{
-    component::{badge, button, card, separation, text::Text, TransparentPickListStyle},
+    component::{badge, button, card, form, separation, text::Text},
     icon,
 };
 
 use crate::{
-    app::{context::Context, error::Error, message::Message, view::layout},
-    daemon::model::{transaction_from_hex, HistoryEvent, HistoryEventKind, VaultTransactions},
+    app::{
+        context::Context,
+        error::Error,
+        export::ExportFormat,
+        message::{HistoryExportMessage, HistoryFilterMessage, Message},
+        view::{fiat_amount, layout},
+    },
+    daemon::model::{
+        transaction_from_hex, HistoryEvent, HistoryEventKind, VaultTransactions, ALL_HISTORY_EVENTS,
+    },
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum HistoryFilter {
-    Cancel,
-    Spend,
-    Deposit,
-    All,
-}
-
-impl HistoryFilter {
-    pub const ALL: [HistoryFilter; 4] = [
-        HistoryFilter::Cancel,
-        HistoryFilter::Spend,
-        HistoryFilter::Deposit,
-        HistoryFilter::All,
-    ];
-
-    pub fn new(kind: &Option<HistoryEventKind>) -> HistoryFilter {
-        if let Some(kind) = kind {
-            match kind {
-                HistoryEventKind::Deposit => HistoryFilter::Deposit,
-                HistoryEventKind::Cancel => HistoryFilter::Cancel,
-                HistoryEventKind::Spend => HistoryFilter::Spend,
-            }
-        } else {
-            HistoryFilter::All
-        }
-    }
-}
-
-impl std::fmt::Display for HistoryFilter {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Self::Cancel => write!(f, "Cancel"),
-            Self::Deposit => write!(f, "Deposit"),
-            Self::Spend => write!(f, "Spend"),
-            Self::All => write!(f, "All"),
-        }
-    }
-}
-
-/// HistoryView renders a list of vaults filtered by the status filter.
-/// If the loading field is true, only the status pick_list component is displayed.
+/// HistoryView renders the filter bar and the list of history events.
 #[derive(Debug)]
 pub struct HistoryView {
     dashboard: layout::Dashboard,
-    pick_filter: pick_list::State<HistoryFilter>,
+    from_input: text_input::State,
+    to_input: text_input::State,
+    min_amount_input: text_input::State,
+    max_amount_input: text_input::State,
+    search_input: text_input::State,
+    apply_button: iced::button::State,
+    clear_button: iced::button::State,
     next_button: iced::button::State,
+    export_button: iced::button::State,
 }
 
 impl HistoryView {
     pub fn new() -> Self {
         HistoryView {
             dashboard: layout::Dashboard::default(),
-            pick_filter: pick_list::State::default(),
+            from_input: text_input::State::default(),
+            to_input: text_input::State::default(),
+            min_amount_input: text_input::State::default(),
+            max_amount_input: text_input::State::default(),
+            search_input: text_input::State::default(),
+            apply_button: iced::button::State::default(),
+            clear_button: iced::button::State::default(),
             next_button: iced::button::State::default(),
+            export_button: iced::button::State::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         warning: Option<&Error>,
+        kinds: &[HistoryEventKind],
+        from: &form::Value<String>,
+        to: &form::Value<String>,
+        min_amount: &form::Value<String>,
+        max_amount: &form::Value<String>,
+        search: &form::Value<String>,
         events: Vec<Element<'a, Message>>,
-        event_kind_filter: &Option<HistoryEventKind>,
         has_next: bool,
     ) -> Element<'a, Message> {
-        let mut col = Column::new().push(
-            Row::new()
-                .push(Column::new().width(Length::Fill))
-                .push(
-                    pick_list::PickList::new(
-                        &mut self.pick_filter,
-                        &HistoryFilter::ALL[..],
-                        Some(HistoryFilter::new(event_kind_filter)),
-                        |filter| {
-                            Message::FilterHistoryEvents(match filter {
-                                HistoryFilter::Cancel => Some(HistoryEventKind::Cancel),
-                                HistoryFilter::Deposit => Some(HistoryEventKind::Deposit),
-                                HistoryFilter::Spend => Some(HistoryEventKind::Spend),
-                                HistoryFilter::All => None,
-                            })
-                        },
+        let mut kinds_row = Row::new().spacing(20).align_items(Alignment::Center);
+        for kind in &ALL_HISTORY_EVENTS {
+            let kind = kind.clone();
+            kinds_row = kinds_row.push(Checkbox::new(
+                kinds.contains(&kind),
+                kind.to_string(),
+                move |checked| {
+                    Message::HistoryFilter(HistoryFilterMessage::KindToggled(kind.clone(), checked))
+                },
+            ));
+        }
+
+        let unit = ctx.converter.unit.to_string();
+        let filter_bar = Column::new()
+            .push(
+                Row::new()
+                    .push(kinds_row.width(Length::Fill))
+                    .push(
+                        button::transparent(
+                            &mut self.export_button,
+                            Container::new(
+                                Row::new()
+                                    .push(icon::download_icon())
+                                    .push(Text::new("Export"))
+                                    .spacing(10)
+                                    .align_items(Alignment::Center),
+                            )
+                            .padding(5),
+                        )
+                        .on_press(Message::ExportHistory),
                     )
-                    .text_size(20)
-                    .padding(10)
-                    .width(Length::Units(200))
-                    .style(TransparentPickListStyle),
-                )
-                .align_items(Alignment::Center),
-        );
+                    .align_items(Alignment::Center),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(&mut self.from_input, "From: YYYY-MM-DD", from, |s| {
+                            Message::HistoryFilter(HistoryFilterMessage::FromEdited(s))
+                        })
+                        .warning("Please enter a valid date")
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(1)),
+                    )
+                    .push(
+                        form::Form::new(&mut self.to_input, "To: YYYY-MM-DD", to, |s| {
+                            Message::HistoryFilter(HistoryFilterMessage::ToEdited(s))
+                        })
+                        .warning("Please enter a valid date, after the start date")
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(1)),
+                    )
+                    .push(
+                        form::Form::new(
+                            &mut self.min_amount_input,
+                            &format!("Min amount in {}", unit),
+                            min_amount,
+                            |s| Message::HistoryFilter(HistoryFilterMessage::MinAmountEdited(s)),
+                        )
+                        .warning("Please enter a valid amount")
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(1)),
+                    )
+                    .push(
+                        form::Form::new(
+                            &mut self.max_amount_input,
+                            &format!("Max amount in {}", unit),
+                            max_amount,
+                            |s| Message::HistoryFilter(HistoryFilterMessage::MaxAmountEdited(s)),
+                        )
+                        .warning("Please enter a valid amount, above the minimum")
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(1)),
+                    )
+                    .spacing(10),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.search_input,
+                            "Search a txid or a vault outpoint",
+                            search,
+                            |s| Message::HistoryFilter(HistoryFilterMessage::SearchEdited(s)),
+                        )
+                        .padding(10)
+                        .render()
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        button::transparent(
+                            &mut self.clear_button,
+                            button::button_content(None, "Clear"),
+                        )
+                        .on_press(Message::HistoryFilter(HistoryFilterMessage::Clear))
+                        .width(Length::Units(100)),
+                    )
+                    .push(
+                        button::primary(
+                            &mut self.apply_button,
+                            button::button_content(None, "Apply"),
+                        )
+                        .on_press(Message::HistoryFilter(HistoryFilterMessage::Apply))
+                        .width(Length::Units(100)),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .spacing(10);
+
+        let mut col = Column::new().push(card::white(filter_bar).padding(20).width(Length::Fill));
 
         if !events.is_empty() {
             col = col.push(Column::with_children(events).spacing(5));
@@ -199,6 +281,7 @@ impl HistoryEventListItemView {
                             .bold(),
                         )
                         .push(Text::new(&format!("{}", ctx.converter.unit)).small())
+                        .push(fiat_amount(ctx, Amount::from_sat(amount)))
                         .spacing(5)
                         .align_items(Alignment::Center),
                 )
@@ -273,6 +356,171 @@ impl HistoryEventView {
     }
 }
 
+#[derive(Debug)]
+pub struct HistoryExportView {
+    modal: layout::Modal,
+    from_input: text_input::State,
+    to_input: text_input::State,
+    path_input: text_input::State,
+    pick_format: pick_list::State<ExportFormat>,
+    export_button: iced::button::State,
+}
+
+impl HistoryExportView {
+    pub fn new() -> Self {
+        Self {
+            modal: layout::Modal::default(),
+            from_input: text_input::State::new(),
+            to_input: text_input::State::new(),
+            path_input: text_input::State::new(),
+            pick_format: pick_list::State::default(),
+            export_button: iced::button::State::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        warning: Option<&Error>,
+        from: &form::Value<String>,
+        to: &form::Value<String>,
+        kinds: &[HistoryEventKind],
+        format: ExportFormat,
+        path: &form::Value<String>,
+        // progress between 0 and 1 and number of events fetched.
+        progress: Option<(f32, usize)>,
+        exported: Option<&(usize, PathBuf)>,
+    ) -> Element<'a, Message> {
+        let mut kinds_row = Row::new().spacing(20);
+        for kind in &ALL_HISTORY_EVENTS {
+            let kind = kind.clone();
+            kinds_row = kinds_row.push(Checkbox::new(
+                kinds.contains(&kind),
+                kind.to_string(),
+                move |checked| {
+                    Message::HistoryExport(HistoryExportMessage::KindToggled(kind.clone(), checked))
+                },
+            ));
+        }
+
+        let mut col = Column::new()
+            .push(Text::new("Export history").bold().size(50))
+            .push(
+                Row::new()
+                    .push(
+                        Column::new()
+                            .push(Text::new("From:").bold())
+                            .push(
+                                form::Form::new(&mut self.from_input, "YYYY-MM-DD", from, |s| {
+                                    Message::HistoryExport(HistoryExportMessage::FromEdited(s))
+                                })
+                                .warning("Please enter a valid date (YYYY-MM-DD)")
+                                .padding(10)
+                                .render(),
+                            )
+                            .spacing(10)
+                            .width(Length::FillPortion(1)),
+                    )
+                    .push(
+                        Column::new()
+                            .push(Text::new("To:").bold())
+                            .push(
+                                form::Form::new(&mut self.to_input, "YYYY-MM-DD", to, |s| {
+                                    Message::HistoryExport(HistoryExportMessage::ToEdited(s))
+                                })
+                                .warning(
+                                    "Please enter a valid date (YYYY-MM-DD), after the start date",
+                                )
+                                .padding(10)
+                                .render(),
+                            )
+                            .spacing(10)
+                            .width(Length::FillPortion(1)),
+                    )
+                    .spacing(20),
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Events:").bold())
+                    .push(kinds_row)
+                    .spacing(10),
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("File:").bold())
+                    .push(
+                        Row::new()
+                            .push(
+                                form::Form::new(&mut self.path_input, "Path", path, |s| {
+                                    Message::HistoryExport(HistoryExportMessage::PathEdited(s))
+                                })
+                                .warning("Please enter a file path")
+                                .padding(10)
+                                .render()
+                                .width(Length::Fill),
+                            )
+                            .push(
+                                pick_list::PickList::new(
+                                    &mut self.pick_format,
+                                    &ExportFormat::ALL[..],
+                                    Some(format),
+                                    |format| {
+                                        Message::HistoryExport(
+                                            HistoryExportMessage::FormatSelected(format),
+                                        )
+                                    },
+                                )
+                                .padding(10)
+                                .width(Length::Units(100)),
+                            )
+                            .spacing(20)
+                            .align_items(Alignment::Center),
+                    )
+                    .spacing(10),
+            )
+            .spacing(30);
+
+        if let Some((progress, count)) = progress {
+            col = col.push(
+                Column::new()
+                    .push(ProgressBar::new(0.0..=1.0, progress).height(Length::Units(10)))
+                    .push(Text::new(&format!("{} events fetched", count)).small())
+                    .spacing(10),
+            );
+        } else if let Some((count, path)) = exported {
+            col = col.push(
+                card::success(Text::new(&format!(
+                    "{} events exported to {}",
+                    count,
+                    path.to_string_lossy()
+                )))
+                .padding(20)
+                .width(Length::Fill),
+            );
+        }
+
+        let mut export_button = button::primary(
+            &mut self.export_button,
+            button::button_content(None, "Export"),
+        )
+        .width(Length::Units(200));
+        if progress.is_none() && !kinds.is_empty() {
+            export_button =
+                export_button.on_press(Message::HistoryExport(HistoryExportMessage::Start));
+        }
+        col = col.push(Container::new(export_button).width(Length::Fill).center_x());
+
+        self.modal.view(
+            ctx,
+            warning,
+            Container::new(col.max_width(800)).padding(20),
+            None,
+            Message::Close,
+        )
+    }
+}
+
 fn date_and_blockheight<'a, T: 'a>(event: &HistoryEvent) -> Container<'a, T> {
     Container::new(
         Row::new()
@@ -20,6 +20,7 @@ use revault_ui::{
 use crate::{
     app::{
         context::Context,
+        emergency_kit::KitStatus,
         error::Error,
         menu::{Menu, VaultsMenu},
         message::Message,
@@ -40,12 +41,14 @@ impl ManagerHomeView {
         &'a mut self,
         ctx: &Context,
         warning: Option<&Error>,
+        trend: Element<'a, Message>,
         moving_vaults: Vec<Element<'a, Message>>,
         latest_events: Vec<Element<'a, Message>>,
         balance: &HashMap<VaultStatus, (u64, u64)>,
     ) -> Element<'a, Message> {
         let content = Column::new()
             .push(manager_overview(ctx, balance))
+            .push(trend)
             .push_maybe(self.moving_vaults_section.view(ctx, moving_vaults, balance))
             .push_maybe(if !latest_events.is_empty() {
                 let length = latest_events.len();
@@ -132,20 +135,29 @@ pub struct StakeholderHomeView {
     moving_vaults_section: MovingVaultsSection,
     history_button: iced::button::State,
     deposit_button: iced::button::State,
+    emergency_kit_button: iced::button::State,
 }
 
 impl StakeholderHomeView {
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         warning: Option<&Error>,
+        trend: Element<'a, Message>,
         moving_vaults: Vec<Element<'a, Message>>,
         latest_events: Vec<Element<'a, Message>>,
         balance: &HashMap<VaultStatus, (u64, u64)>,
+        emergency_kit: KitStatus,
     ) -> Element<'a, Message> {
         let col_body = Column::new()
             .spacing(20)
+            .push_maybe(emergency_kit_warning(
+                &mut self.emergency_kit_button,
+                emergency_kit,
+            ))
             .push(self.overview.view(ctx, balance))
+            .push(trend)
             .push_maybe(if balance.is_empty() && latest_events.is_empty() {
                 Some(card::simple(Container::new(
                     Row::new()
@@ -201,6 +213,28 @@ impl StakeholderHomeView {
     }
 }
 
+/// Warns the stakeholder that the emergency kit does not cover every vault.
+fn emergency_kit_warning<'a>(
+    state: &'a mut iced::button::State,
+    status: KitStatus,
+) -> Option<Container<'a, Message>> {
+    let message = match status {
+        KitStatus::UpToDate => return None,
+        KitStatus::Missing => "No emergency kit was exported for your vaults".to_string(),
+        KitStatus::Outdated(1) => "1 vault is not in your emergency kit".to_string(),
+        KitStatus::Outdated(n) => format!("{} vaults are not in your emergency kit", n),
+    };
+    Some(card::alert_warning(Container::new(
+        Row::new()
+            .push(Container::new(Text::new(&message).bold().small()).width(Length::Fill))
+            .push(
+                button::primary(state, button::button_content(None, "Export emergency kit"))
+                    .on_press(Message::Menu(Menu::Emergency)),
+            )
+            .align_items(Alignment::Center),
+    )))
+}
+
 #[derive(Debug, Default)]
 struct MovingVaultsSection {
     revault_button: iced::button::State,
@@ -0,0 +1,165 @@
+use iced::{Alignment, Column, Container, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{card, separation, text::Text},
+    util::Collection,
+};
+
+use crate::app::{
+    context::Context,
+    inspector::{PsbtInspection, ScriptKind},
+    label::LabelKind,
+};
+
+/// Displays what the signature of the psbt commits to.
+pub fn psbt_inspector<'a, T: 'a>(
+    ctx: &Context,
+    title: &str,
+    inspection: &PsbtInspection,
+) -> Container<'a, T> {
+    let user_fingerprints = ctx.user_fingerprints();
+
+    let mut col_inputs = Column::new()
+        .spacing(10)
+        .push(Text::new(&format!("{} inputs", inspection.inputs.len())).bold());
+    for input in &inspection.inputs {
+        let mut signatures = Row::new().spacing(5).push(Text::new("Signed by:").small());
+        if input.finalized {
+            signatures = signatures.push(Text::new("finalized").small().success());
+        } else if input.signed_by.is_empty() {
+            signatures = signatures.push(Text::new("nobody").small().color(color::DARK_GREY));
+        }
+        for fingerprint in &input.signed_by {
+            signatures = signatures.push(if user_fingerprints.contains(fingerprint) {
+                Text::new(&format!("#{} (you)", fingerprint))
+                    .small()
+                    .success()
+            } else {
+                Text::new(&format!("#{}", fingerprint)).small()
+            });
+        }
+
+        col_inputs = col_inputs.push(card::simple(Container::new(
+            Column::new()
+                .spacing(5)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Container::new(
+                                Text::new(&ctx.labels.label_or(
+                                    LabelKind::Output,
+                                    input.previous_output.to_string(),
+                                ))
+                                .small(),
+                            )
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            Text::new(
+                                &input
+                                    .amount
+                                    .map(|amount| ctx.converter.converts(amount))
+                                    .unwrap_or_else(|| "unknown amount".to_string()),
+                            )
+                            .bold()
+                            .small(),
+                        ),
+                )
+                .push(
+                    Row::new()
+                        .spacing(20)
+                        .push(kind_label(input.kind, "Not a coin of the wallet"))
+                        .push(Text::new(&format!("sequence: {:#x}", input.sequence)).small()),
+                )
+                .push(signatures),
+        )));
+    }
+
+    let mut col_outputs = Column::new()
+        .spacing(10)
+        .push(Text::new(&format!("{} outputs", inspection.outputs.len())).bold());
+    for output in &inspection.outputs {
+        col_outputs = col_outputs.push(card::simple(Container::new(
+            Column::new()
+                .spacing(5)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Container::new(
+                                Column::new()
+                                    .push_maybe(
+                                        ctx.labels
+                                            .get(LabelKind::Addr, &output.address)
+                                            .map(|label| Text::new(label).bold().small()),
+                                    )
+                                    .push(Text::new(&output.address).small()),
+                            )
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            Text::new(&ctx.converter.converts(output.amount))
+                                .bold()
+                                .small(),
+                        ),
+                )
+                .push(kind_label(output.kind, "External recipient")),
+        )));
+    }
+
+    let fee = inspection
+        .fee
+        .map(|fee| format!("{} {}", ctx.converter.converts(fee), ctx.converter.unit))
+        .unwrap_or_else(|| "unknown".to_string());
+    let feerate = inspection
+        .feerate()
+        .map(|feerate| format!("at least {} sats/vbyte", feerate))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    card::white(Container::new(
+        Column::new()
+            .spacing(20)
+            .push(
+                Column::new()
+                    .spacing(5)
+                    .push(Text::new(title).bold())
+                    .push(Text::new(&inspection.txid.to_string()).small()),
+            )
+            .push(col_inputs)
+            .push(col_outputs)
+            .push(separation().width(Length::Fill))
+            .push(
+                Column::new()
+                    .spacing(5)
+                    .push(detail("Fee", &fee))
+                    .push(detail("Feerate", &feerate))
+                    .push(detail(
+                        "Weight",
+                        &format!("{} WU at most", inspection.max_weight),
+                    ))
+                    .push(detail("Version", &inspection.version.to_string()))
+                    .push(detail("Locktime", &inspection.locktime.to_string())),
+            ),
+    ))
+    .width(Length::Fill)
+}
+
+fn kind_label<'a, T: 'a>(kind: Option<ScriptKind>, external: &str) -> Container<'a, T> {
+    Container::new(match kind {
+        Some(ScriptKind::Emergency) => Text::new(&ScriptKind::Emergency.to_string())
+            .small()
+            .color(color::ALERT),
+        Some(kind) => Text::new(&format!("{} (wallet)", kind)).small().success(),
+        None => Text::new(external).small().color(color::WARNING),
+    })
+}
+
+fn detail<'a, T: 'a>(label: &str, value: &str) -> Row<'a, T> {
+    Row::new()
+        .push(Text::new(label).bold().small().width(Length::Fill))
+        .push(Text::new(value).small())
+}
@@ -14,16 +14,22 @@ use revault_ui::{
         ContainerForegroundStyle, ProgressBar, TooltipStyle, TransparentPickListStyle,
     },
     icon::{tooltip_icon, trash_icon},
+    util::Collection,
 };
 
 use crate::{
     app::{
+        coin_selection::{Selection, SelectionStrategy},
         context::Context,
         error::Error,
+        fee::{FeeEstimates, FeeratePreset},
+        inspector::PsbtInspection,
         menu::Menu,
         message::{InputMessage, Message, RecipientMessage, SpendTxMessage},
-        view::{layout, warning::warn},
+        payouts::PayoutsImport,
+        view::{inspector::psbt_inspector, layout, warning::warn},
     },
+    conversion::Unit,
     daemon::model,
 };
 
@@ -231,6 +237,8 @@ pub struct ManagerSelectOutputsView {
     cancel_button: iced::button::State,
     next_button: iced::button::State,
     new_output_button: iced::button::State,
+    recipients_input: text_input::State,
+    import_button: iced::button::State,
 }
 
 impl ManagerSelectOutputsView {
@@ -240,12 +248,18 @@ impl ManagerSelectOutputsView {
             next_button: iced::button::State::new(),
             scroll: scrollable::State::new(),
             new_output_button: iced::button::State::new(),
+            recipients_input: text_input::State::new(),
+            import_button: iced::button::State::new(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
+        ctx: &Context,
         selected_outputs: Vec<Element<'a, Message>>,
+        recipients_path: &form::Value<String>,
+        recipients_import: Option<&Result<PayoutsImport, String>>,
         valid: bool,
         no_duplicate: bool,
     ) -> Element<'a, Message> {
@@ -282,6 +296,14 @@ impl ManagerSelectOutputsView {
         }
         let element: Element<_> = col_outputs.max_width(1000).into();
 
+        let import = recipients_file(
+            ctx,
+            &mut self.recipients_input,
+            &mut self.import_button,
+            recipients_path,
+            recipients_import,
+        );
+
         let mut footer = Row::new()
             .spacing(20)
             .push(Space::with_width(Length::Fill))
@@ -333,6 +355,7 @@ impl ManagerSelectOutputsView {
                             &mut self.scroll,
                             Container::new(
                                 Column::new()
+                                    .push(Container::new(import).width(Length::Fill).center_x())
                                     .push(Container::new(element).width(Length::Fill).center_x())
                                     .spacing(20),
                             ),
@@ -366,11 +389,80 @@ impl ManagerSelectOutputsView {
     }
 }
 
+/// Displays the form to import the recipients from a file and the result
+/// of the last import.
+fn recipients_file<'a>(
+    ctx: &Context,
+    input: &'a mut text_input::State,
+    import_button: &'a mut iced::button::State,
+    path: &form::Value<String>,
+    import: Option<&Result<PayoutsImport, String>>,
+) -> Container<'a, Message> {
+    let result = import.map(|import| match import {
+        Err(e) => Column::new().push(Text::new(e).small().color(color::ALERT)),
+        Ok(import) => {
+            let mut col = Column::new().spacing(5).push(
+                Text::new(&format!(
+                    "{} recipients imported for a total of {} {}",
+                    import.payouts.len(),
+                    ctx.converter.converts(import.total()),
+                    ctx.converter.unit,
+                ))
+                .small()
+                .success(),
+            );
+            for (line, error) in &import.errors {
+                col = col.push(
+                    Text::new(&format!("Line {}: {}", line, error))
+                        .small()
+                        .color(color::ALERT),
+                );
+            }
+            col
+        }
+    });
+
+    card::white(Container::new(
+        Column::new()
+            .spacing(10)
+            .push(Text::new("Import recipients from a CSV or JSON file").bold())
+            .push(
+                Text::new("One recipient per line: address, amount in BTC and an optional label")
+                    .small(),
+            )
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .align_items(Alignment::Center)
+                    .push(
+                        form::Form::new(input, "File path", path, |s| {
+                            Message::SpendTx(SpendTxMessage::RecipientsPathEdited(s))
+                        })
+                        .warning("Please enter a file path")
+                        .padding(10)
+                        .render()
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        button::primary(import_button, button::button_content(None, "Import"))
+                            .on_press(Message::SpendTx(SpendTxMessage::ImportRecipients))
+                            .width(Length::Units(100)),
+                    ),
+            )
+            .push_maybe(result),
+    ))
+    .max_width(1000)
+    .width(Length::Fill)
+}
+
 #[derive(Debug)]
 pub struct ManagerSendOutputView {
     address_input: text_input::State,
     amount_input: text_input::State,
     delete_button: iced::button::State,
+    label_input: text_input::State,
+    save_label_button: iced::button::State,
+    suggestion_buttons: Vec<iced::button::State>,
 }
 
 impl ManagerSendOutputView {
@@ -379,30 +471,95 @@ impl ManagerSendOutputView {
             address_input: text_input::State::focused(),
             amount_input: text_input::State::new(),
             delete_button: iced::button::State::new(),
+            label_input: text_input::State::new(),
+            save_label_button: iced::button::State::new(),
+            suggestion_buttons: Vec::new(),
         }
     }
+
+    /// The label of a valid address is given with true if it was edited
+    /// and can be saved. The suggestions are the saved addresses matching
+    /// the address input with their label.
     pub fn view(
         &mut self,
         address: &form::Value<String>,
         amount: &form::Value<String>,
+        unit: Unit,
+        label: Option<(&str, bool)>,
+        suggestions: &[(&str, &str)],
     ) -> Element<RecipientMessage> {
+        self.suggestion_buttons
+            .resize_with(suggestions.len(), iced::button::State::new);
+        let mut col_suggestions = Column::new().spacing(5);
+        for ((suggestion, label), state) in
+            suggestions.iter().zip(self.suggestion_buttons.iter_mut())
+        {
+            col_suggestions = col_suggestions.push(
+                button::transparent(
+                    state,
+                    Container::new(
+                        Row::new()
+                            .push(Text::new(label).bold().small())
+                            .push(Text::new(suggestion).small())
+                            .spacing(10),
+                    ),
+                )
+                .on_press(RecipientMessage::AddressSelected(suggestion.to_string())),
+            );
+        }
+
+        let label_row = if let Some((label, edited)) = label {
+            let mut save_button = button::transparent(
+                &mut self.save_label_button,
+                Container::new(Text::new("Save label").small()),
+            );
+            if edited {
+                save_button = save_button.on_press(RecipientMessage::SaveLabel);
+            }
+            Some(
+                Row::new()
+                    .push(
+                        TextInput::new(
+                            &mut self.label_input,
+                            "Label (optional)",
+                            label,
+                            RecipientMessage::LabelEdited,
+                        )
+                        .size(15)
+                        .padding(5)
+                        .width(Length::Fill),
+                    )
+                    .push(save_button)
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+        } else {
+            None
+        };
+
         Row::new()
             .push(
-                form::Form::new(
-                    &mut self.address_input,
-                    "Address",
-                    address,
-                    RecipientMessage::AddressEdited,
-                )
-                .warning("Please enter a valid bitcoin address")
-                .padding(10)
-                .render()
-                .width(Length::FillPortion(2)),
+                Column::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.address_input,
+                            "Address or label",
+                            address,
+                            RecipientMessage::AddressEdited,
+                        )
+                        .warning("Please enter a valid bitcoin address")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push_maybe(label_row)
+                    .push(col_suggestions)
+                    .spacing(5)
+                    .width(Length::FillPortion(2)),
             )
             .push(
                 form::Form::new(
                     &mut self.amount_input,
-                    "Amount in BTC, ex: 0.123",
+                    &format!("Amount in {}", unit),
                     amount,
                     RecipientMessage::AmountEdited,
                 )
@@ -430,6 +587,7 @@ pub struct ManagerSelectInputsView {
     back_button: iced::button::State,
     cancel_button: iced::button::State,
     next_button: iced::button::State,
+    strategy_buttons: [iced::button::State; 3],
 }
 
 impl ManagerSelectInputsView {
@@ -439,15 +597,22 @@ impl ManagerSelectInputsView {
             back_button: iced::button::State::new(),
             next_button: iced::button::State::new(),
             scroll: scrollable::State::new(),
+            strategy_buttons: Default::default(),
         }
     }
 
+    /// auto_selection is the strategy of the last automatic selection,
+    /// its result and whether the manager adjusted it by hand.
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         inputs: Vec<Element<'a, Message>>,
         input_amount: u64,
         output_amount: u64,
+        estimated_fee: Option<u64>,
+        estimated_vsize: u64,
+        auto_selection: Option<(SelectionStrategy, Option<&Selection>, bool)>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let header = Column::new()
@@ -502,8 +667,27 @@ impl ManagerSelectInputsView {
                                     .bold(),
                                 )
                                 .push(Text::new(&format!(" {}", ctx.converter.unit)))
+                                .push(
+                                    Text::new(&format!(
+                                        " of {} {} to send",
+                                        ctx.converter.converts(Amount::from_sat(output_amount)),
+                                        ctx.converter.unit
+                                    ))
+                                    .small(),
+                                )
+                                .align_items(Alignment::Center)
                                 .width(Length::Fill),
                         )
+                        .push_maybe(estimated_fee.map(|fee| {
+                            Column::new()
+                                .push(Text::new(&format!(
+                                    "Estimated fee: {} {}",
+                                    ctx.converter.converts(Amount::from_sat(fee)),
+                                    ctx.converter.unit
+                                )))
+                                .push(Text::new(&format!("{} vbytes", estimated_vsize)).small())
+                                .width(Length::Fill)
+                        }))
                         .push(
                             Container::new(if input_amount > output_amount {
                                 button::primary(
@@ -569,6 +753,11 @@ impl ManagerSelectInputsView {
                             &mut self.scroll,
                             Container::new(
                                 Column::new()
+                                    .push(selection_strategies(
+                                        ctx,
+                                        &mut self.strategy_buttons,
+                                        auto_selection,
+                                    ))
                                     .push(
                                         Container::new(
                                             Column::with_children(inputs)
@@ -601,6 +790,75 @@ impl ManagerSelectInputsView {
     }
 }
 
+fn selection_strategies<'a>(
+    ctx: &Context,
+    buttons: &'a mut [iced::button::State; 3],
+    auto_selection: Option<(SelectionStrategy, Option<&Selection>, bool)>,
+) -> Container<'a, Message> {
+    let mut row_strategies = Row::new().spacing(20);
+    for (strategy, state) in SelectionStrategy::ALL.iter().zip(buttons.iter_mut()) {
+        let content = Container::new(Text::new(&strategy.to_string()).bold())
+            .width(Length::Units(150))
+            .padding(10)
+            .center_x();
+        let strategy_button = if auto_selection.map(|(s, _, _)| s) == Some(*strategy) {
+            button::primary(state, content)
+        } else {
+            button::white_card_button(state, content)
+        };
+        row_strategies = row_strategies.push(strategy_button.on_press(Message::SpendTx(
+            SpendTxMessage::SelectionStrategySelected(*strategy),
+        )));
+    }
+
+    let amount = |sats: u64| {
+        format!(
+            "{} {}",
+            ctx.converter.converts(Amount::from_sat(sats)),
+            ctx.converter.unit
+        )
+    };
+    let explanation = auto_selection.map(|(strategy, selection, adjusted)| match selection {
+        None => card::alert_warning(Container::new(
+            Text::new("The active vaults do not cover the amount to send and the fee").small(),
+        )),
+        Some(_) if adjusted => card::simple(Container::new(
+            Text::new(&format!(
+                "The selection of the {} strategy was adjusted by hand",
+                strategy.to_string().to_lowercase()
+            ))
+            .small(),
+        )),
+        Some(selection) => card::simple(Container::new(
+            Column::new()
+                .spacing(5)
+                .push(Text::new(strategy.description()).small())
+                .push(
+                    Text::new(&format!(
+                        "{} vaults selected for {}, with an estimated fee of {} and {} of change",
+                        selection.indexes.len(),
+                        amount(selection.input_amount),
+                        amount(selection.fee),
+                        amount(selection.change),
+                    ))
+                    .small(),
+                ),
+        )),
+    });
+
+    Container::new(
+        Column::new()
+            .spacing(10)
+            .max_width(1000)
+            .align_items(Alignment::Center)
+            .push(Text::new("Select automatically:").small())
+            .push(row_strategies)
+            .push_maybe(explanation),
+    )
+    .width(Length::Fill)
+    .center_x()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ManagerSendInputView {
     select_button: iced::button::State,
@@ -659,6 +917,7 @@ pub struct ManagerSelectFeeView {
     back_button: iced::button::State,
     generate_button: iced::button::State,
     feerate_input: iced::text_input::State,
+    preset_buttons: [iced::button::State; 3],
 }
 
 impl ManagerSelectFeeView {
@@ -668,13 +927,20 @@ impl ManagerSelectFeeView {
             back_button: iced::button::State::new(),
             generate_button: iced::button::State::new(),
             feerate_input: iced::text_input::State::new(),
+            preset_buttons: Default::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
+        ctx: &Context,
         feerate: Option<u64>,
         valid_feerate: bool,
+        preset: Option<FeeratePreset>,
+        estimates: Option<&FeeEstimates>,
+        estimated_vsize: u64,
+        feerate_warning: Option<String>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let header = Column::new()
@@ -733,6 +999,39 @@ impl ManagerSelectFeeView {
             )
         };
 
+        let mut row_presets = Row::new().spacing(20);
+        for (p, state) in FeeratePreset::ALL
+            .iter()
+            .zip(self.preset_buttons.iter_mut())
+        {
+            let estimate = estimates.and_then(|estimates| estimates.get(*p));
+            let content = Container::new(
+                Column::new()
+                    .push(Text::new(&p.to_string()).bold())
+                    .push(
+                        Text::new(&estimate.map_or_else(
+                            || "No estimate".to_string(),
+                            |f| format!("{} sats/vbyte", f),
+                        ))
+                        .small(),
+                    )
+                    .push(Text::new(&format!("~{} blocks", p.conf_target())).small())
+                    .align_items(Alignment::Center),
+            )
+            .width(Length::Units(150))
+            .center_x();
+            let mut preset_button = if preset == Some(*p) {
+                button::primary(state, content.padding(10))
+            } else {
+                button::white_card_button(state, content)
+            };
+            if estimate.is_some() {
+                preset_button = preset_button
+                    .on_press(Message::SpendTx(SpendTxMessage::FeeratePresetSelected(*p)));
+            }
+            row_presets = row_presets.push(preset_button);
+        }
+
         let mut col_fee = Column::new()
             .push(
                 Column::new()
@@ -742,6 +1041,8 @@ impl ManagerSelectFeeView {
                             .width(Length::Fill)
                             .center_x(),
                     )
+                    .push(row_presets)
+                    .push(Text::new("or set a custom feerate:").small())
                     .push(
                         Row::new()
                             .push(
@@ -760,11 +1061,39 @@ impl ManagerSelectFeeView {
                             .spacing(5)
                             .align_items(Alignment::Center),
                     )
+                    .spacing(20)
                     .align_items(Alignment::Center),
             )
+            .spacing(20)
             .height(Length::Fill)
             .align_items(Alignment::Center);
 
+        if let Some(f) = feerate {
+            col_fee = col_fee.push(
+                Column::new()
+                    .push(Text::new(&format!(
+                        "Estimated fee: {} {}",
+                        ctx.converter
+                            .converts(Amount::from_sat(f * estimated_vsize)),
+                        ctx.converter.unit,
+                    )))
+                    .push(
+                        Text::new(&format!(
+                            "for an estimated size of {} vbytes",
+                            estimated_vsize
+                        ))
+                        .small(),
+                    )
+                    .align_items(Alignment::Center),
+            );
+        }
+
+        if let Some(feerate_warning) = feerate_warning {
+            col_fee = col_fee.push(card::alert_warning(Container::new(
+                Text::new(&feerate_warning).small(),
+            )));
+        }
+
         if let Some(error) = warning {
             col_fee = col_fee.push(card::alert_warning(Container::new(
                 Text::new(&error.to_string()).small(),
@@ -953,6 +1282,7 @@ impl ManagerStepSignView {
         ctx: &Context,
         inputs: &[model::Vault],
         tx: &model::SpendTx,
+        inspection: &PsbtInspection,
         feerate: &u64,
         warning: Option<&Error>,
         signer: Element<'a, Message>,
@@ -1095,6 +1425,11 @@ impl ManagerStepSignView {
                                                                 tx.change_index,
                                                                 tx.cpfp_index,
                                                             ))
+                                                            .push(psbt_inspector(
+                                                                ctx,
+                                                                "Transaction to sign",
+                                                                inspection,
+                                                            ))
                                                             .spacing(20),
                                                     ),
                                                 )
@@ -1,23 +1,29 @@
+mod alerts;
 mod deposit;
 mod emergency;
 mod history;
 mod home;
+mod inspector;
 mod layout;
 pub mod manager;
+mod notifications;
 mod revault;
 pub mod settings;
 mod sidebar;
 pub mod sign;
 pub mod spend_transaction;
 pub mod stakeholder;
+mod trend;
 pub mod vault;
 mod vaults;
 mod warning;
 
+pub use alerts::{UnvaultAlertView, UnvaultAlertsView};
 pub use deposit::DepositView;
 pub use emergency::{EmergencyTriggeredView, EmergencyView};
-pub use history::{HistoryEventListItemView, HistoryEventView, HistoryView};
+pub use history::{HistoryEventListItemView, HistoryEventView, HistoryExportView, HistoryView};
 pub use home::{ManagerHomeView, StakeholderHomeView};
+pub use notifications::NotificationsView;
 pub use revault::{RevaultSelectVaultsView, RevaultSuccessView, RevaultVaultListItemView};
 pub use settings::SettingsView;
 pub use spend_transaction::{SpendTransactionListItemView, SpendTransactionView};
@@ -25,13 +31,27 @@ pub use stakeholder::{
     StakeholderCreateVaultsView, StakeholderDelegateVaultsView,
     StakeholderSelecteVaultsToDelegateView,
 };
+pub use trend::TrendView;
 pub use vault::VaultView;
 pub use vaults::VaultsView;
 
-use iced::{Column, Element};
+use iced::{Column, Container, Element, Length};
+
+use revault_ui::{color, component::text::Text};
 
 use crate::app::{context::Context, error::Error, message::Message};
 
+/// Displays the fiat value of the amount, or nothing if the converter has no price.
+fn fiat_amount<'a, T: 'a>(ctx: &Context, amount: bitcoin::Amount) -> Container<'a, T> {
+    if let Some(value) = ctx.converter.converts_fiat(amount) {
+        Container::new(Text::new(&value).small().color(color::DARK_GREY))
+            .padding([0, 0, 0, 10])
+            .width(Length::Shrink)
+    } else {
+        Container::new(Column::new())
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct LoadingDashboard {
     dashboard: layout::Dashboard,
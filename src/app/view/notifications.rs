@@ -0,0 +1,154 @@
+use chrono::NaiveDateTime;
+use iced::{alignment, Alignment, Column, Container, Element, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{badge, button, card, text::Text},
+    icon,
+};
+
+use crate::app::{
+    context::Context,
+    menu::Menu,
+    message::{Message, NotificationMessage},
+    notification::{Notification, NotificationKind},
+    view::layout,
+};
+
+/// NotificationsView renders the notification center.
+#[derive(Debug, Default)]
+pub struct NotificationsView {
+    dashboard: layout::Dashboard,
+    clear_button: iced::button::State,
+    items: Vec<NotificationItemView>,
+}
+
+impl NotificationsView {
+    pub fn view<'a>(&'a mut self, ctx: &Context) -> Element<'a, Message> {
+        let notifications = ctx.notifications.notifications();
+        self.items
+            .resize_with(notifications.len(), NotificationItemView::default);
+
+        let mut header = Row::new()
+            .push(Container::new(Text::new("Notifications").bold()).width(Length::Fill))
+            .align_items(Alignment::Center);
+        if !notifications.is_empty() {
+            header = header.push(
+                button::cancel(
+                    &mut self.clear_button,
+                    button::button_content(Some(icon::trash_icon()), "Clear all"),
+                )
+                .on_press(Message::Notification(NotificationMessage::ClearAll)),
+            );
+        }
+
+        let mut col = Column::new().spacing(20).push(header);
+        if notifications.is_empty() {
+            col = col.push(card::white(Container::new(
+                Text::new("No notification").color(color::DARK_GREY),
+            )));
+        } else {
+            col = col.push(
+                Column::with_children(
+                    self.items
+                        .iter_mut()
+                        .zip(notifications.iter())
+                        .enumerate()
+                        .map(|(i, (item, notification))| item.view(ctx, notification, i))
+                        .collect(),
+                )
+                .spacing(10),
+            );
+        }
+
+        self.dashboard.view(ctx, None, col)
+    }
+}
+
+#[derive(Debug, Default)]
+struct NotificationItemView {
+    history_button: iced::button::State,
+    alerts_button: iced::button::State,
+    dismiss_button: iced::button::State,
+}
+
+impl NotificationItemView {
+    fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        notification: &Notification,
+        index: usize,
+    ) -> Element<'a, Message> {
+        let date = NaiveDateTime::from_timestamp(notification.date as i64, 0);
+        let mut content = Column::new()
+            .push(Text::new(&notification.title()).bold())
+            .push(Text::new(&notification.outpoint.to_string()).small())
+            .spacing(5);
+        if let Some(amount) = notification.amount {
+            content = content.push(
+                Text::new(&format!(
+                    "{} {}",
+                    ctx.converter.converts(amount),
+                    ctx.converter.unit
+                ))
+                .small(),
+            );
+        }
+
+        let mut actions = Row::new().spacing(10).align_items(Alignment::Center);
+        if notification.kind == NotificationKind::Unvaulting
+            || notification.kind == NotificationKind::Unvaulted
+        {
+            actions = actions.push(
+                button::transparent(
+                    &mut self.alerts_button,
+                    button::button_content(Some(icon::unlock_icon()), "Alerts"),
+                )
+                .on_press(Message::Menu(Menu::UnvaultAlerts)),
+            );
+        }
+        if notification.kind.history_event_kind().is_some() {
+            actions = actions.push(
+                button::transparent(
+                    &mut self.history_button,
+                    button::button_content(Some(icon::history_icon()), "History"),
+                )
+                .on_press(Message::Menu(Menu::History)),
+            );
+        }
+        actions = actions.push(
+            button::transparent(
+                &mut self.dismiss_button,
+                button::button_content(Some(icon::cross_icon()), "Dismiss"),
+            )
+            .on_press(Message::Notification(NotificationMessage::Dismiss(index))),
+        );
+
+        card::white(Container::new(
+            Row::new()
+                .push(notification_badge(&notification.kind))
+                .push(content.width(Length::Fill))
+                .push(
+                    Column::new()
+                        .push(Text::new(&date.to_string()).small())
+                        .push(actions)
+                        .align_items(Alignment::End)
+                        .spacing(5),
+                )
+                .align_items(Alignment::Center)
+                .spacing(20),
+        ))
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+fn notification_badge<'a, T: 'a>(kind: &NotificationKind) -> Container<'a, T> {
+    match kind {
+        NotificationKind::Deposit => badge::tx_deposit(),
+        NotificationKind::Unvaulting | NotificationKind::Unvaulted => badge::vault_unvaulting(),
+        NotificationKind::Cancel | NotificationKind::Emergency => badge::shield_notif(),
+        NotificationKind::Spend => badge::vault(),
+    }
+    .align_x(alignment::Horizontal::Center)
+}
@@ -7,7 +7,14 @@ use revault_ui::{
     util::Collection,
 };
 
-use crate::app::{context::Context, error::Error, menu::Menu, message::Message, view::layout};
+use crate::app::{
+    context::Context,
+    error::Error,
+    inspector::PsbtInspection,
+    menu::Menu,
+    message::Message,
+    view::{inspector::psbt_inspector, layout},
+};
 
 use crate::daemon::model::{outpoint, Vault};
 
@@ -148,7 +155,13 @@ pub struct RevaultVaultListItemView {
 }
 
 impl RevaultVaultListItemView {
-    pub fn view(&mut self, ctx: &Context, vault: &Vault, selected: bool) -> iced::Element<Message> {
+    pub fn view(
+        &mut self,
+        ctx: &Context,
+        vault: &Vault,
+        cancel_tx: Option<&PsbtInspection>,
+        selected: bool,
+    ) -> iced::Element<Message> {
         let content = Container::new(
             Row::new()
                 .push(
@@ -190,8 +203,19 @@ impl RevaultVaultListItemView {
                 .align_items(Alignment::Center),
         );
 
-        button::white_card_button(&mut self.select_button, content)
-            .on_press(Message::SelectVault(outpoint(vault)))
-            .into()
+        let item = button::white_card_button(&mut self.select_button, content)
+            .on_press(Message::SelectVault(outpoint(vault)));
+        match cancel_tx {
+            Some(inspection) if selected => Column::new()
+                .spacing(10)
+                .push(item)
+                .push(psbt_inspector(
+                    ctx,
+                    "Cancel transaction (lowest of 5 feerates)",
+                    inspection,
+                ))
+                .into(),
+            _ => item.into(),
+        }
     }
 }
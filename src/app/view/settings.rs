@@ -1,18 +1,23 @@
-use iced::{alignment, text_input, Alignment, Column, Container, Element, Length, Row};
+use iced::{
+    alignment, pick_list, text_input, Alignment, Checkbox, Column, Container, Element, Length, Row,
+};
 
 use revault_ui::{
     color,
     component::{badge, button, card, form, separation, text::Text},
     icon,
+    util::Collection,
 };
 
 use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{Message, SettingsMessage},
+        message::{LabelMessage, Message, NotificationMessage, SettingsMessage},
+        notification::NotificationKind,
         view::layout,
     },
+    conversion::Denomination,
     revault::Role,
 };
 
@@ -20,17 +25,28 @@ use crate::{
 pub struct SettingsView {
     dashboard: layout::Dashboard,
     add_watchtower_button: iced::button::State,
+    unit_input: pick_list::State<Denomination>,
+    labels: LabelsSettingsView,
 }
 
 impl SettingsView {
+    /// labels_written is the number of the labels written by the last
+    /// import or export and true if it was an import.
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         warning: Option<&Error>,
         can_edit: bool,
+        labels_path: &form::Value<String>,
+        labels_written: Option<(usize, bool)>,
         settings: Vec<Element<'a, Message>>,
     ) -> Element<'a, Message> {
-        let mut col = Column::with_children(settings).spacing(20);
+        let mut col = Column::new()
+            .push(display_settings(ctx, &mut self.unit_input))
+            .push(notifications_settings(ctx))
+            .push(self.labels.view(labels_path, labels_written))
+            .push(Column::with_children(settings).spacing(20))
+            .spacing(20);
         if can_edit && ctx.role == Role::Stakeholder {
             col = col.push(
                 Container::new(
@@ -48,6 +64,172 @@ impl SettingsView {
     }
 }
 
+/// Displays the unit of the amounts, which can be changed at any time,
+/// and the fiat price if a price source is configured.
+fn display_settings<'a>(
+    ctx: &Context,
+    unit_input: &'a mut pick_list::State<Denomination>,
+) -> Element<'a, Message> {
+    let price = if let Some(price) = &ctx.converter.price {
+        format!("{:.2} {} / BTC", price.rate, price.currency)
+    } else if ctx.config.gui.price_source.is_some() {
+        "Price unavailable".to_string()
+    } else {
+        "No price source".to_string()
+    };
+    card::simple(Container::new(
+        Column::new()
+            .push(
+                Row::new()
+                    .push(badge::bitcoin_core())
+                    .push(Text::new("Display"))
+                    .padding(10)
+                    .spacing(20)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .push(separation().width(Length::Fill))
+            .push(
+                Row::new()
+                    .push(Container::new(Text::new("Unit:").bold().small()).width(Length::Fill))
+                    .push(
+                        pick_list::PickList::new(
+                            unit_input,
+                            &Denomination::ALL[..],
+                            Some(ctx.converter.unit.denomination),
+                            Message::DisplayUnit,
+                        )
+                        .text_size(15)
+                        .padding(5),
+                    )
+                    .align_items(Alignment::Center),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        Container::new(Text::new("Fiat price:").bold().small()).width(Length::Fill),
+                    )
+                    .push(Text::new(&price).small()),
+            )
+            .spacing(20),
+    ))
+    .width(Length::Fill)
+    .into()
+}
+
+#[derive(Debug, Default)]
+struct LabelsSettingsView {
+    path_input: text_input::State,
+    import_button: iced::button::State,
+    export_button: iced::button::State,
+}
+
+impl LabelsSettingsView {
+    fn view<'a>(
+        &'a mut self,
+        path: &form::Value<String>,
+        written: Option<(usize, bool)>,
+    ) -> Element<'a, Message> {
+        card::simple(Container::new(
+            Column::new()
+                .push(
+                    Row::new()
+                        .push(badge::badge(icon::pencil_icon()))
+                        .push(Text::new("Labels"))
+                        .padding(10)
+                        .spacing(20)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(separation().width(Length::Fill))
+                .push(
+                    Text::new(
+                        "Labels of the addresses, transactions and vaults, \
+                        imported from or exported to a BIP-329 file.",
+                    )
+                    .small(),
+                )
+                .push(
+                    Row::new()
+                        .push(
+                            form::Form::new(&mut self.path_input, "File path", path, |s| {
+                                Message::Label(LabelMessage::PathEdited(s))
+                            })
+                            .warning("Please enter a file path")
+                            .padding(10)
+                            .render()
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            button::primary(
+                                &mut self.import_button,
+                                button::button_content(None, "Import"),
+                            )
+                            .on_press(Message::Label(LabelMessage::Import))
+                            .width(Length::Units(100)),
+                        )
+                        .push(
+                            button::primary(
+                                &mut self.export_button,
+                                button::button_content(None, "Export"),
+                            )
+                            .on_press(Message::Label(LabelMessage::Export))
+                            .width(Length::Units(100)),
+                        )
+                        .spacing(20)
+                        .align_items(Alignment::Center),
+                )
+                .push_maybe(written.map(|(count, import)| {
+                    Text::new(&format!(
+                        "{} labels {}",
+                        count,
+                        if import { "imported" } else { "exported" }
+                    ))
+                    .small()
+                    .success()
+                }))
+                .spacing(20),
+        ))
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+/// Displays the notified events, which can be changed at any time.
+fn notifications_settings<'a>(ctx: &Context) -> Element<'a, Message> {
+    let config = ctx.config.gui.notifications();
+    let mut kinds_row = Row::new().spacing(20).align_items(Alignment::Center);
+    for kind in NotificationKind::ALL.iter().copied() {
+        kinds_row = kinds_row.push(Checkbox::new(
+            config.kinds.contains(&kind),
+            kind.to_string(),
+            move |checked| Message::Notification(NotificationMessage::KindToggled(kind, checked)),
+        ));
+    }
+    card::simple(Container::new(
+        Column::new()
+            .push(
+                Row::new()
+                    .push(badge::badge(icon::bell_icon()))
+                    .push(Text::new("Notifications"))
+                    .padding(10)
+                    .spacing(20)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .push(separation().width(Length::Fill))
+            .push(kinds_row)
+            .push(Checkbox::new(
+                config.desktop,
+                "Desktop notifications",
+                |checked| Message::Notification(NotificationMessage::DesktopToggled(checked)),
+            ))
+            .spacing(20),
+    ))
+    .width(Length::Fill)
+    .into()
+}
+
 #[derive(Debug, Default)]
 pub struct BitcoindSettingsEditView {
     cancel_button: iced::button::State,
@@ -4,8 +4,8 @@ use revault_ui::{
     color,
     component::{button, separation, text::Text, TransparentPickListStyle},
     icon::{
-        deposit_icon, history_icon, home_icon, person_check_icon, plus_icon, send_icon,
-        settings_icon, vaults_icon, warning_icon,
+        bell_icon, deposit_icon, history_icon, home_icon, person_check_icon, plus_icon, send_icon,
+        settings_icon, unlock_icon, vaults_icon, warning_icon,
     },
 };
 
@@ -28,6 +28,8 @@ pub struct Sidebar {
     vaults_menu_button: iced::button::State,
     spend_menu_button: iced::button::State,
     settings_menu_button: iced::button::State,
+    notifications_menu_button: iced::button::State,
+    alerts_menu_button: iced::button::State,
 }
 
 impl Sidebar {
@@ -89,6 +91,46 @@ impl Sidebar {
             )
             .on_press(Message::Menu(Menu::Vaults(VaultsMenu::Current)))
         };
+        let alerts_button = if context.menu == Menu::UnvaultAlerts {
+            button::primary(
+                &mut self.alerts_menu_button,
+                button::button_content(Some(unlock_icon()), "Unvault alerts"),
+            )
+            // UnvaultAlertsState supports reload
+            .on_press(Message::Reload)
+        } else {
+            button::transparent(
+                &mut self.alerts_menu_button,
+                button::button_content(Some(unlock_icon()), "Unvault alerts"),
+            )
+            .on_press(Message::Menu(Menu::UnvaultAlerts))
+        };
+
+        let unread = context.notifications.unread();
+        let notifications_label = if unread > 0 {
+            format!("Notifications ({})", unread)
+        } else {
+            "Notifications".to_string()
+        };
+        let notifications_button = if context.menu == Menu::Notifications {
+            button::primary(
+                &mut self.notifications_menu_button,
+                button::button_content(Some(bell_icon()), &notifications_label),
+            )
+            .on_press(Message::Menu(Menu::Notifications))
+        } else if unread > 0 {
+            button::important(
+                &mut self.notifications_menu_button,
+                button::button_content(Some(bell_icon()), &notifications_label),
+            )
+            .on_press(Message::Menu(Menu::Notifications))
+        } else {
+            button::transparent(
+                &mut self.notifications_menu_button,
+                button::button_content(Some(bell_icon()), &notifications_label),
+            )
+            .on_press(Message::Menu(Menu::Notifications))
+        };
 
         let settings_button = if context.menu == Menu::Settings {
             button::primary(
@@ -197,6 +239,8 @@ impl Sidebar {
                 Container::new(home_button.width(Length::Units(200))),
                 Container::new(history_button.width(Length::Units(200))),
                 Container::new(vaults_button.width(Length::Units(200))),
+                Container::new(alerts_button.width(Length::Units(200))),
+                Container::new(notifications_button.width(Length::Units(200))),
                 separation().width(Length::Units(200)),
                 Container::new(actions.width(Length::Units(200))),
             ]),
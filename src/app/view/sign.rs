@@ -1,6 +1,9 @@
-use iced::{Alignment, Column, Container, Element, Length};
+use bitcoin::util::bip32::Fingerprint;
+use iced::{Alignment, Column, Container, Element, Length, Row};
 
+use revault_hwi::DeviceDescriptor;
 use revault_ui::{
+    color,
     component::{button, card, text::Text},
     icon,
 };
@@ -10,19 +13,23 @@ use crate::app::{context::Context, message::SignMessage};
 #[derive(Debug)]
 pub struct SignerView {
     sign_button: iced::button::State,
+    device_buttons: Vec<iced::button::State>,
 }
 
 impl SignerView {
     pub fn new() -> Self {
         SignerView {
             sign_button: iced::button::State::default(),
+            device_buttons: Vec::new(),
         }
     }
 
     pub fn view(
         &mut self,
         _ctx: &Context,
-        connected: bool,
+        available: &[DeviceDescriptor],
+        selected: Option<&DeviceDescriptor>,
+        fingerprints: &[Fingerprint],
         processing: bool,
         signed: bool,
     ) -> Element<SignMessage> {
@@ -39,37 +46,88 @@ impl SignerView {
             .center_x()
             .into();
         }
-        if connected {
-            let mut sign_button = button::primary(
-                &mut self.sign_button,
-                button::button_content(None, " Sign ").width(Length::Units(200)),
-            );
-            if !processing {
-                sign_button = sign_button.on_press(SignMessage::SelectSign);
-            }
-            card::white(Container::new(
+
+        if available.is_empty() {
+            return card::white(Container::new(
                 Column::new()
                     .align_items(Alignment::Center)
                     .spacing(20)
-                    .push(icon::connected_device_icon().size(20))
-                    .push(sign_button),
+                    .push(icon::connect_device_icon().size(20))
+                    .push(Text::new("Connect hardware wallet")),
             ))
             .padding(50)
             .width(Length::Fill)
             .center_x()
-            .into()
+            .into();
+        }
+
+        let mut col = Column::new().align_items(Alignment::Center).spacing(20);
+        if let Some(device) = selected {
+            let mut sign_button = button::primary(
+                &mut self.sign_button,
+                button::button_content(None, " Sign ").width(Length::Units(200)),
+            );
+            if !processing {
+                sign_button = sign_button.on_press(SignMessage::SelectSign);
+            }
+            col = col
+                .push(icon::connected_device_icon().size(20))
+                .push(device_description(device, fingerprints))
+                .push(sign_button);
         } else {
-            card::white(Container::new(
-                Column::new()
-                    .align_items(Alignment::Center)
-                    .spacing(20)
-                    .push(icon::connect_device_icon().size(20))
-                    .push(Text::new("Connect hardware wallet")),
-            ))
+            col = col
+                .push(icon::connect_device_icon().size(20))
+                .push(Text::new("Select the hardware wallet to sign with"));
+        }
+
+        if available.len() > 1 || selected.is_none() {
+            self.device_buttons
+                .resize_with(available.len(), iced::button::State::default);
+            let mut list = Column::new().spacing(10).width(Length::Fill);
+            for (device, state) in available.iter().zip(self.device_buttons.iter_mut()) {
+                let mut item = button::white_card_button(
+                    state,
+                    Container::new(device_description(device, fingerprints)),
+                )
+                .width(Length::Fill);
+                if !processing && Some(device) != selected {
+                    item = item.on_press(SignMessage::SelectDevice(device.clone()));
+                }
+                list = list.push(item);
+            }
+            col = col.push(list);
+        }
+
+        card::white(Container::new(col))
             .padding(50)
             .width(Length::Fill)
             .center_x()
             .into()
-        }
+    }
+}
+
+/// Displays the kind, fingerprint and transport of the device
+/// and whether its fingerprint matches one of the keys of the transaction.
+fn device_description<'a>(
+    device: &DeviceDescriptor,
+    fingerprints: &[Fingerprint],
+) -> Row<'a, SignMessage> {
+    let row = Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(Text::new(&device.kind.to_string()).bold())
+        .push(Text::new(&device.transport.to_string()).small());
+    match device.fingerprint {
+        Some(fingerprint) if fingerprints.contains(&fingerprint) => row.push(
+            Text::new(&format!("#{} (key of the transaction)", fingerprint))
+                .small()
+                .success(),
+        ),
+        Some(fingerprint) => row.push(
+            Text::new(&format!("#{} (unknown key)", fingerprint))
+                .small()
+                .color(color::WARNING),
+        ),
+        None => row.push(Text::new("fingerprint unknown").small()),
     }
 }
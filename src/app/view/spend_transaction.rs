@@ -18,12 +18,17 @@ use revault_ui::{
 
 use crate::{
     app::{
+        chain,
         context::Context,
         error::Error,
+        inspector::PsbtInspection,
         message::{Message, SpendTxMessage},
-        view::{manager::spend_tx_with_feerate_view, warning::warn},
+        view::{
+            fiat_amount, inspector::psbt_inspector, manager::spend_tx_with_feerate_view,
+            warning::warn,
+        },
     },
-    daemon::model,
+    daemon::{bitcoind::MempoolEntry, model},
 };
 
 #[derive(Debug, Default)]
@@ -122,6 +127,7 @@ impl SpendTransactionView {
                                         .bold()
                                         .size(50),
                                     )
+                                    .push(fiat_amount(ctx, spend_amount))
                                     .push(Container::new(Text::new(&format!(
                                         "Miner Fee: {} {}",
                                         ctx.converter.converts(fees),
@@ -339,11 +345,34 @@ impl SpendTransactionSharePsbtView {
     }
 }
 
-pub fn spend_tx_confirmed<'a, T: 'a>() -> Element<'a, T> {
+/// Returns the progress of the spend transaction toward the settled depth.
+fn confirmation_progress(confirmations: u32) -> Text {
+    if confirmations == 0 {
+        Text::new("Waiting for the first confirmation").small()
+    } else if confirmations < chain::SETTLED_CONFIRMATIONS {
+        Text::new(&format!(
+            "{} / {} confirmations",
+            confirmations,
+            chain::SETTLED_CONFIRMATIONS
+        ))
+        .small()
+    } else {
+        Text::new(&format!("{} confirmations", confirmations)).small()
+    }
+}
+
+pub fn spend_tx_confirmed<'a, T: 'a>(confirmations: u32) -> Element<'a, T> {
     card::white(
         Row::new()
             .push(badge::Badge::new(icon::send_icon()).style(badge::Style::Success))
-            .push(Text::new("Transaction was confirmed in the blockchain").color(color::SUCCESS))
+            .push(
+                Column::new()
+                    .push(
+                        Text::new("Transaction was confirmed in the blockchain")
+                            .color(color::SUCCESS),
+                    )
+                    .push(confirmation_progress(confirmations)),
+            )
             .align_items(Alignment::Center)
             .spacing(20),
     )
@@ -392,6 +421,8 @@ impl SpendTransactionSignView {
 
     pub fn view<'a>(
         &'a mut self,
+        ctx: &Context,
+        inspection: &PsbtInspection,
         signer: Element<'a, Message>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
@@ -402,7 +433,11 @@ impl SpendTransactionSignView {
             )));
         }
 
-        Container::new(card::white(Container::new(col_action))).into()
+        Column::new()
+            .spacing(20)
+            .push(card::white(Container::new(col_action)))
+            .push(psbt_inspector(ctx, "Transaction to sign", inspection))
+            .into()
     }
 }
 
@@ -561,6 +596,182 @@ impl SpendTransactionBroadcastView {
     }
 }
 
+#[derive(Debug)]
+pub struct SpendTransactionBroadcastedView {
+    bump_fee_button: iced::button::State,
+}
+
+impl SpendTransactionBroadcastedView {
+    pub fn new() -> Self {
+        Self {
+            bump_fee_button: iced::button::State::new(),
+        }
+    }
+
+    pub fn view(&mut self, confirmations: u32) -> Element<Message> {
+        card::white(
+            Row::new()
+                .push(badge::Badge::new(icon::send_icon()).style(badge::Style::Warning))
+                .push(
+                    Column::new()
+                        .push(Text::new("Transaction is broadcasted").color(color::WARNING))
+                        .push(confirmation_progress(confirmations))
+                        .push(Text::new("If it does not confirm, you can bump its feerate").small())
+                        .width(Length::Fill),
+                )
+                .push(
+                    button::primary(
+                        &mut self.bump_fee_button,
+                        button::button_content(None, "Bump fee")
+                            .padding(5)
+                            .width(Length::Units(100))
+                            .align_x(Horizontal::Center),
+                    )
+                    .on_press(Message::SpendTx(SpendTxMessage::SelectBumpFee)),
+                )
+                .align_items(Alignment::Center)
+                .spacing(20),
+        )
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+#[derive(Debug)]
+pub struct SpendTransactionBumpFeeView {
+    feerate_input: iced::text_input::State,
+    cancel_button: iced::button::State,
+    confirm_button: iced::button::State,
+}
+
+impl SpendTransactionBumpFeeView {
+    pub fn new() -> Self {
+        Self {
+            feerate_input: iced::text_input::State::new(),
+            cancel_button: iced::button::State::new(),
+            confirm_button: iced::button::State::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        parent: Option<&MempoolEntry>,
+        feerate: &form::Value<String>,
+        cpfp_fee: Option<Amount>,
+        signer: Option<Element<'a, Message>>,
+        processing: bool,
+        success: bool,
+        warning: Option<&Error>,
+    ) -> Element<'a, Message> {
+        let mut col_action = Column::new();
+        if let Some(error) = warning {
+            col_action = col_action.push(card::alert_warning(Container::new(
+                Text::new(&error.to_string()).small(),
+            )));
+        }
+
+        if success {
+            col_action = col_action.push(
+                card::success(Text::new("CPFP transaction is broadcasted"))
+                    .padding(20)
+                    .width(Length::Fill)
+                    .align_x(Horizontal::Center),
+            );
+        } else if let Some(parent) = parent {
+            col_action = col_action
+                .push(
+                    Column::new()
+                        .push(Text::new(&format!(
+                            "Current feerate: {:.1} sats/vbyte",
+                            parent.feerate()
+                        )))
+                        .push(
+                            Text::new(&format!(
+                                "Fee: {} {}, size: {} vbytes",
+                                ctx.converter.converts(parent.fee()),
+                                ctx.converter.unit,
+                                parent.vsize
+                            ))
+                            .small(),
+                        )
+                        .width(Length::Fill),
+                )
+                .push(
+                    Row::new()
+                        .push(Text::new("Target feerate:").width(Length::Fill))
+                        .push(
+                            Container::new(
+                                form::Form::new(
+                                    &mut self.feerate_input,
+                                    "sats/vbyte",
+                                    feerate,
+                                    |f| Message::SpendTx(SpendTxMessage::CpfpFeerateEdited(f)),
+                                )
+                                .warning("Feerate must be higher than the current one")
+                                .size(20)
+                                .padding(10)
+                                .render(),
+                            )
+                            .width(Length::Units(200)),
+                        )
+                        .align_items(Alignment::Center),
+                );
+
+            if let Some(fee) = cpfp_fee {
+                col_action = col_action.push(
+                    Text::new(&format!(
+                        "The CPFP transaction pays {} {} from the CPFP output",
+                        ctx.converter.converts(fee),
+                        ctx.converter.unit,
+                    ))
+                    .small(),
+                );
+            }
+
+            if let Some(signer) = signer {
+                col_action = col_action.push(signer);
+                if processing {
+                    col_action = col_action.push(button::important(
+                        &mut self.confirm_button,
+                        button::button_content(None, "Broadcasting"),
+                    ));
+                }
+            } else {
+                let mut confirm_button = button::important(
+                    &mut self.confirm_button,
+                    button::button_content(None, "Create CPFP transaction"),
+                )
+                .width(Length::Units(250));
+                if !processing {
+                    confirm_button =
+                        confirm_button.on_press(Message::SpendTx(SpendTxMessage::CreateCpfp));
+                }
+                col_action = col_action.push(confirm_button);
+            }
+        } else if warning.is_none() {
+            col_action = col_action.push(Text::new("Fetching the transaction from the mempool..."));
+        }
+
+        col_action = col_action.push(
+            button::transparent(
+                &mut self.cancel_button,
+                button::button_content(None, if success { "Close" } else { "Cancel" }),
+            )
+            .on_press(Message::SpendTx(SpendTxMessage::UnselectBumpFee)),
+        );
+
+        card::white(Container::new(
+            col_action.align_items(Alignment::Center).spacing(20),
+        ))
+        .width(Length::Fill)
+        .align_x(Horizontal::Center)
+        .padding(20)
+        .into()
+    }
+}
+
 #[derive(Debug)]
 pub struct SpendTransactionListItemView {
     select_button: iced::button::State,
@@ -672,6 +883,7 @@ impl SpendTransactionListItemView {
                                                 Text::new(&format!(" {}", ctx.converter.unit))
                                                     .small(),
                                             )
+                                            .push(fiat_amount(ctx, spend_amount))
                                             .align_items(Alignment::Center),
                                     )
                                     .align_x(Horizontal::Right)
@@ -11,9 +11,10 @@ use crate::{
     app::{
         context::Context,
         error::Error,
+        inspector::PsbtInspection,
         menu::Menu,
         message::{Message, SignMessage, VaultFilterMessage},
-        view::layout,
+        view::{inspector::psbt_inspector, layout},
     },
     daemon::model::{Vault, VaultStatus},
 };
@@ -36,6 +37,7 @@ impl StakeholderCreateVaultsView {
         &'a mut self,
         ctx: &Context,
         deposits: &Vec<Vault>,
+        transactions: &[(String, PsbtInspection)],
         processing: bool,
         hw_connected: bool,
         warning: Option<&Error>,
@@ -119,10 +121,30 @@ impl StakeholderCreateVaultsView {
             )
         }
 
+        content = content.push_maybe(transactions_to_sign(ctx, transactions));
+
         self.modal.view(ctx, warning, Container::new(content).height(Length::Fill).center_y(), Some("A vault is a deposit with revocation transactions\nsigned and shared between stakeholders"), Message::Menu(Menu::Home))
     }
 }
 
+/// Displays the inspection of the transactions the user is about to sign.
+fn transactions_to_sign<'a>(
+    ctx: &Context,
+    transactions: &[(String, PsbtInspection)],
+) -> Option<Column<'a, Message>> {
+    if transactions.is_empty() {
+        return None;
+    }
+    Some(
+        transactions.iter().fold(
+            Column::new()
+                .spacing(20)
+                .push(Text::new("Transactions to sign").bold()),
+            |col, (title, inspection)| col.push(psbt_inspector(ctx, title, inspection)),
+        ),
+    )
+}
+
 #[derive(Debug)]
 pub struct StakeholderDelegateVaultsView {
     modal: layout::Modal,
@@ -141,6 +163,7 @@ impl StakeholderDelegateVaultsView {
         &'a mut self,
         ctx: &Context,
         deposits: &Vec<Vault>,
+        transactions: &[(String, PsbtInspection)],
         processing: bool,
         hw_connected: bool,
         warning: Option<&Error>,
@@ -233,6 +256,8 @@ impl StakeholderDelegateVaultsView {
             )
         }
 
+        content = content.push_maybe(transactions_to_sign(ctx, transactions));
+
         self.modal.view(ctx, warning, Container::new(content).height(Length::Fill).center_y(), Some("By delegating you allow managers to spend the funds,\n but you can still revert any undesired transaction."), Message::Menu(Menu::Home))
     }
 }
@@ -0,0 +1,147 @@
+use bitcoin::Amount;
+use iced::{Alignment, Column, Container, Element, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{
+        button, card,
+        chart::{Chart, Series},
+        text::Text,
+    },
+};
+
+use crate::{
+    app::{
+        context::Context,
+        error::Error,
+        message::{Message, TrendMessage},
+        trend::{Period, Trend},
+    },
+    conversion::{Denomination, Unit},
+};
+
+const CHART_HEIGHT: u16 = 200;
+
+#[derive(Debug, Default)]
+pub struct TrendView {
+    period_buttons: [iced::button::State; 3],
+}
+
+impl TrendView {
+    /// trend is None while the history events are loading.
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        period: Period,
+        trend: Option<&Trend>,
+        warning: Option<&Error>,
+    ) -> Element<'a, Message> {
+        let mut row_periods = Row::new().spacing(10);
+        for (p, state) in Period::ALL.iter().zip(self.period_buttons.iter_mut()) {
+            let content = Container::new(Text::new(&p.to_string()).small())
+                .width(Length::Units(80))
+                .padding(5)
+                .center_x();
+            row_periods = row_periods.push(
+                if *p == period {
+                    button::primary(state, content)
+                } else {
+                    button::transparent(state, content)
+                }
+                .on_press(Message::Trend(TrendMessage::PeriodSelected(*p))),
+            );
+        }
+
+        let unit = ctx.converter.unit;
+        let content: Element<'a, Message> = match (warning, trend) {
+            (Some(e), _) => card::alert_warning(Container::new(Text::new(&e.to_string()).small()))
+                .width(Length::Fill)
+                .into(),
+            (None, None) => Text::new("Loading the history...")
+                .small()
+                .color(color::DARK_GREY)
+                .into(),
+            (None, Some(trend)) => Column::new()
+                .spacing(20)
+                .push(Text::new("Balance").small().bold())
+                .push(
+                    Chart::new(trend.labels.clone())
+                        .push(Series::line(color::PRIMARY, values(&trend.balance, unit)))
+                        .format(move |v| axis_value(v, unit))
+                        .view(CHART_HEIGHT),
+                )
+                .push(
+                    Row::new()
+                        .spacing(20)
+                        .align_items(Alignment::Center)
+                        .push(Text::new("Flows").small().bold().width(Length::Fill))
+                        .push(
+                            Text::new(&format!(
+                                "In: +{} {}",
+                                ctx.converter
+                                    .converts(Amount::from_sat(trend.total_inflows())),
+                                unit
+                            ))
+                            .small()
+                            .success(),
+                        )
+                        .push(
+                            Text::new(&format!(
+                                "Out: -{} {}",
+                                ctx.converter
+                                    .converts(Amount::from_sat(trend.total_outflows())),
+                                unit
+                            ))
+                            .small()
+                            .color(color::ALERT),
+                        ),
+                )
+                .push(
+                    Chart::new(trend.labels.clone())
+                        .push(Series::bars(color::SUCCESS, values(&trend.inflows, unit)))
+                        .push(Series::bars(color::ALERT, values(&trend.outflows, unit)))
+                        .format(move |v| axis_value(v, unit))
+                        .view(CHART_HEIGHT),
+                )
+                .push(
+                    Text::new("Deposits come in, spends and the fees of the cancels go out")
+                        .small()
+                        .color(color::DARK_GREY),
+                )
+                .into(),
+        };
+
+        card::white(Container::new(
+            Column::new()
+                .spacing(20)
+                .push(
+                    Row::new()
+                        .align_items(Alignment::Center)
+                        .push(Text::new("Trends").bold().width(Length::Fill))
+                        .push(row_periods),
+                )
+                .push(content),
+        ))
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+/// Converts the amounts in satoshis to the denomination of the unit.
+fn values(amounts: &[u64], unit: Unit) -> Vec<f64> {
+    amounts
+        .iter()
+        .map(|amount| match unit.denomination {
+            Denomination::Btc => *amount as f64 / 100_000_000.0,
+            Denomination::MilliBtc => *amount as f64 / 100_000.0,
+            Denomination::Sat => *amount as f64,
+        })
+        .collect()
+}
+
+/// Value of the vertical axis without the trailing zeros.
+fn axis_value(value: f64, unit: Unit) -> String {
+    let value = format!("{:.8}", value);
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}", value, unit)
+}
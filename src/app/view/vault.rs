@@ -1,14 +1,24 @@
 use chrono::NaiveDateTime;
-use iced::{tooltip, Alignment, Column, Container, Element, Length, Row, Tooltip};
+use iced::{
+    text_input, tooltip, Alignment, Column, Container, Element, Length, Row, TextInput, Tooltip,
+};
 
 use bitcoin::{util::bip32::Fingerprint, Amount};
 use revault_ui::{
     color,
     component::{badge, button, card, separation, text::Text, TooltipStyle},
     icon,
+    util::Collection,
 };
 
-use crate::app::{context::Context, message::Message, view::layout};
+use crate::app::{
+    chain,
+    context::Context,
+    label::LabelKind,
+    message::{LabelMessage, Message, VaultMessage},
+    timeline::Step,
+    view::{fiat_amount, layout},
+};
 
 use crate::daemon::model::{
     outpoint, transaction_from_hex, Vault, VaultStatus, VaultTransactions, WalletTransaction,
@@ -17,6 +27,8 @@ use crate::daemon::model::{
 #[derive(Debug)]
 pub struct VaultModal {
     copy_button: iced::button::State,
+    label_input: text_input::State,
+    save_label_button: iced::button::State,
     modal: layout::Modal,
 }
 
@@ -24,6 +36,8 @@ impl VaultModal {
     pub fn new() -> Self {
         VaultModal {
             copy_button: iced::button::State::default(),
+            label_input: text_input::State::default(),
+            save_label_button: iced::button::State::default(),
             modal: layout::Modal::default(),
         }
     }
@@ -33,7 +47,36 @@ impl VaultModal {
         ctx: &Context,
         vlt: &Vault,
         txs: &VaultTransactions,
+        steps: &[Step],
+        label: Option<&str>,
     ) -> Element<'a, Message> {
+        let reference = outpoint(vlt).to_string();
+        let saved_label = ctx
+            .labels
+            .get(LabelKind::Output, &reference)
+            .unwrap_or_default();
+        let label = label.unwrap_or(saved_label);
+        let mut save_label_button = button::primary(
+            &mut self.save_label_button,
+            button::button_content(None, "Save label"),
+        );
+        if label != saved_label {
+            save_label_button = save_label_button.on_press(Message::Label(LabelMessage::Save(
+                LabelKind::Output,
+                reference,
+                label.to_string(),
+            )));
+        }
+
+        let timelock =
+            if vlt.status == VaultStatus::Unvaulting || vlt.status == VaultStatus::Unvaulted {
+                Some(timelock_countdown(ctx.blocks_before_timelock(
+                    txs.unvault.as_ref().and_then(|tx| tx.blockheight),
+                )))
+            } else {
+                None
+            };
+
         let mut col = Column::new().spacing(20);
         col = col.push(Container::new(Text::new("Onchain transactions:").bold()));
         if let Some(tx) = &txs.spend {
@@ -58,7 +101,26 @@ impl VaultModal {
             None,
             Container::new(
                 Column::new()
-                    .push(Container::new(vault(ctx, &mut self.copy_button, vlt)))
+                    .push(Container::new(vault(
+                        ctx,
+                        &mut self.copy_button,
+                        vlt,
+                        timelock,
+                    )))
+                    .push(
+                        Row::new()
+                            .push(
+                                TextInput::new(&mut self.label_input, "Label", label, |s| {
+                                    Message::Vault(VaultMessage::LabelEdited(s))
+                                })
+                                .padding(10)
+                                .width(Length::Fill),
+                            )
+                            .push(save_label_button.width(Length::Units(150)))
+                            .spacing(20)
+                            .align_items(Alignment::Center),
+                    )
+                    .push(timeline(ctx, steps))
                     .push(col)
                     .max_width(1000)
                     .spacing(20),
@@ -75,6 +137,7 @@ fn vault<'a>(
     ctx: &Context,
     copy_button: &'a mut iced::button::State,
     vlt: &Vault,
+    timelock: Option<Text>,
 ) -> Container<'a, Message> {
     card::simple(Container::new(
         Column::new()
@@ -86,6 +149,11 @@ fn vault<'a>(
                                 .push(vault_badge(&vlt))
                                 .push(
                                     Column::new()
+                                        .push_maybe(
+                                            ctx.labels
+                                                .outpoint(&outpoint(vlt))
+                                                .map(|label| Text::new(label).bold()),
+                                        )
                                         .push(
                                             Row::new()
                                                 .push(Text::new(&vlt.txid.to_string()).bold())
@@ -95,7 +163,9 @@ fn vault<'a>(
                                                 ))
                                                 .align_items(Alignment::Center),
                                         )
-                                        .push(Text::new(&format!("{}", &vlt.status,))),
+                                        .push(Text::new(&format!("{}", &vlt.status,)))
+                                        .push(confirmations(ctx, vlt.blockheight))
+                                        .push_maybe(timelock),
                                 )
                                 .align_items(Alignment::Center)
                                 .spacing(20),
@@ -109,7 +179,8 @@ fn vault<'a>(
                                     Text::new(&format!("{}", ctx.converter.converts(vlt.amount),))
                                         .bold(),
                                 )
-                                .push(Text::new(&ctx.converter.unit.to_string())),
+                                .push(Text::new(&ctx.converter.unit.to_string()))
+                                .push(fiat_amount(ctx, vlt.amount)),
                         )
                         .width(Length::Shrink),
                     )
@@ -120,6 +191,70 @@ fn vault<'a>(
     ))
 }
 
+/// Displays the steps of the vault lifecycle, the steps still to come are
+/// greyed out.
+fn timeline<'a, T: 'a>(ctx: &Context, steps: &[Step]) -> Container<'a, T> {
+    let mut col = Column::new()
+        .spacing(10)
+        .push(Text::new("Timeline:").bold());
+    for step in steps {
+        let mut details = Column::new().spacing(5).push(if step.done {
+            Text::new(&step.kind.to_string()).bold()
+        } else {
+            Text::new(&step.kind.to_string())
+                .bold()
+                .color(color::DARK_GREY)
+        });
+        if let Some(time) = step.time {
+            details = details.push(
+                Text::new(&NaiveDateTime::from_timestamp(time.into(), 0).to_string()).small(),
+            );
+        } else if !step.done {
+            details = details.push(Text::new("Pending").small().color(color::DARK_GREY));
+        }
+        if let Some(blockheight) = step.blockheight {
+            details = details.push(
+                Text::new(&format!(
+                    "Blockheight: {} ({})",
+                    blockheight,
+                    confirmations_label(ctx.confirmations(Some(blockheight)))
+                ))
+                .small(),
+            );
+        } else if step.done && step.txid.is_some() {
+            details = details.push(Text::new("Not in a block").small());
+        }
+        if let Some(txid) = step.txid {
+            details = details
+                .push(Text::new(&ctx.labels.label_or(LabelKind::Tx, txid.to_string())).small());
+        }
+        if let Some(fee) = step.fee {
+            details = details.push(
+                Text::new(&format!(
+                    "Fee: {} {}",
+                    ctx.converter.converts(fee),
+                    ctx.converter.unit
+                ))
+                .small(),
+            );
+        }
+
+        col = col.push(
+            Row::new()
+                .spacing(20)
+                .push(if step.done {
+                    Text::from(icon::done_icon()).size(20).success()
+                } else {
+                    Text::from(icon::todo_icon())
+                        .size(20)
+                        .color(color::DARK_GREY)
+                })
+                .push(details),
+        );
+    }
+    Container::new(col)
+}
+
 fn transaction<'a, T: 'a>(
     ctx: &Context,
     title: &str,
@@ -135,8 +270,14 @@ fn transaction<'a, T: 'a>(
                         Row::new()
                             .push(Container::new(Text::new(title).bold()).width(Length::Fill))
                             .push(
-                                Container::new(Text::new(&tx.txid().to_string()).bold().small())
-                                    .width(Length::Shrink),
+                                Container::new(
+                                    Text::new(
+                                        &ctx.labels.label_or(LabelKind::Tx, tx.txid().to_string()),
+                                    )
+                                    .bold()
+                                    .small(),
+                                )
+                                .width(Length::Shrink),
                             ),
                     )
                     .push(
@@ -148,7 +289,11 @@ fn transaction<'a, T: 'a>(
                     )
                     .push(
                         Text::new(&if let Some(blockheight) = &transaction.blockheight {
-                            format!("Blockheight: {}", blockheight)
+                            format!(
+                                "Blockheight: {} ({})",
+                                blockheight,
+                                confirmations_label(ctx.confirmations(Some(*blockheight)))
+                            )
                         } else {
                             "Not in a block".to_string()
                         })
@@ -174,7 +319,11 @@ fn input_and_outputs<'a, T: 'a>(
         col_input = col_input
             .push(
                 card::simple(Container::new(
-                    Text::new(&format!("{}", input.previous_output)).small(),
+                    Text::new(
+                        &ctx.labels
+                            .label_or(LabelKind::Output, input.previous_output.to_string()),
+                    )
+                    .small(),
                 ))
                 .width(Length::Fill),
             )
@@ -185,7 +334,7 @@ fn input_and_outputs<'a, T: 'a>(
         let addr = bitcoin::Address::from_script(&output.script_pubkey, ctx.network());
         let mut col = Column::new();
         if let Some(a) = addr {
-            col = col.push(Text::new(&a.to_string()).small())
+            col = col.push(Text::new(&ctx.labels.label_or(LabelKind::Addr, a.to_string())).small())
         } else {
             col = col.push(Text::new(&output.script_pubkey.to_string()).small())
         }
@@ -209,8 +358,47 @@ fn input_and_outputs<'a, T: 'a>(
     Container::new(Row::new().push(col_input).push(col_output).spacing(20))
 }
 
+fn confirmations_label(confirmations: u32) -> String {
+    match confirmations {
+        0 => "Unconfirmed".to_string(),
+        1 => "1 confirmation".to_string(),
+        n => format!("{} confirmations", n),
+    }
+}
+
+/// confirmations returns the number of confirmations of a transaction
+/// mined at the given height.
+pub fn confirmations(ctx: &Context, tx_blockheight: Option<u32>) -> Text {
+    let confirmations = ctx.confirmations(tx_blockheight);
+    let text = Text::new(&confirmations_label(confirmations)).small();
+    if confirmations < chain::SETTLED_CONFIRMATIONS {
+        text.color(color::DARK_GREY)
+    } else {
+        text
+    }
+}
+
+/// timelock_countdown returns the number of blocks left before an unvaulted
+/// vault can be spent, with an estimate of the time left.
+pub fn timelock_countdown(blocks_before_timelock: Option<u32>) -> Text {
+    match blocks_before_timelock {
+        None => Text::new("Unvault transaction is not confirmed yet").small(),
+        Some(0) => Text::new("Timelock expired, the vault can be spent")
+            .small()
+            .bold()
+            .color(color::ALERT),
+        Some(blocks) => Text::new(&format!(
+            "{} blocks left before the timelock expires ({})",
+            blocks,
+            chain::estimated_time(blocks)
+        ))
+        .small()
+        .bold(),
+    }
+}
+
 /// vault_badge returns a badge headlining the vault status.
-fn vault_badge<'a, T: 'a>(vault: &Vault) -> Element<'a, T> {
+pub fn vault_badge<'a, T: 'a>(vault: &Vault) -> Element<'a, T> {
     match &vault.status {
         VaultStatus::Unconfirmed => badge::vault_unconfirmed().into(),
         VaultStatus::Funded => badge::tx_deposit().into(),
@@ -258,6 +446,10 @@ impl VaultView for VaultListItemView {
                         Container::new(
                             Row::new()
                                 .push(vault_badge(&vault))
+                                .push_maybe(
+                                    vault_label(ctx, vault)
+                                        .map(|label| Text::new(label).bold().small()),
+                                )
                                 .push(if vault.status == VaultStatus::Activating {
                                     Text::new("Delegation approved").small()
                                 } else if vault.status == VaultStatus::Active {
@@ -269,6 +461,7 @@ impl VaultView for VaultListItemView {
                                 } else {
                                     Text::new(&format!("{}", &vault.status)).bold().small()
                                 })
+                                .push(confirmations(ctx, vault.blockheight))
                                 .align_items(Alignment::Center)
                                 .spacing(20),
                         )
@@ -283,6 +476,7 @@ impl VaultView for VaultListItemView {
                         )
                         .width(Length::Shrink),
                     )
+                    .push(fiat_amount(ctx, vault.amount))
                     .spacing(20)
                     .align_items(Alignment::Center),
             ),
@@ -354,6 +548,13 @@ fn vault_ack_signed<'a, T: 'a>(ctx: &Context, deposit: &Vault) -> Element<'a, T>
     .into()
 }
 
+/// Label of the vault outpoint, or of its deposit address.
+fn vault_label<'a>(ctx: &'a Context, vault: &Vault) -> Option<&'a str> {
+    ctx.labels
+        .outpoint(&outpoint(vault))
+        .or_else(|| ctx.labels.address(&vault.address))
+}
+
 fn vault_ack_pending<'a>(
     state: &'a mut iced::button::State,
     ctx: &Context,
@@ -459,6 +660,10 @@ impl DelegateVaultListItemView {
                                 badge::square()
                             })
                             .push(sigs_row)
+                            .push_maybe(
+                                vault_label(ctx, vault)
+                                    .map(|label| Text::new(label).bold().small()),
+                            )
                             .spacing(20)
                             .align_items(Alignment::Center),
                     )
@@ -59,6 +59,10 @@ impl From<&Error> for WarningMessage {
                     WarningMessage("Communication with Revault daemon failed".to_string())
                 }
             },
+            Error::Label(e) => WarningMessage(e.to_string()),
+            Error::TransactionMismatch(..) => WarningMessage(
+                "The daemon gave unexpected transactions, signing was refused. Stop all operations and report to your security team".to_string(),
+            ),
             Error::Unexpected(_) => WarningMessage("Unknown error".to_string()),
         }
     }
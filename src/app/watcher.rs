@@ -0,0 +1,301 @@
+//! The watcher polls the daemon at a fixed interval and compares the result
+//! with the previous poll, it replaces the reload of the states by a stream
+//! of the changes of the wallet.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use iced::{futures, Subscription};
+
+use revaultd::revault_tx::transactions::RevaultTransaction;
+
+use crate::daemon::{
+    model::{outpoint, SpendTxStatus, VaultStatus},
+    Daemon, RevaultDError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatcherEvent {
+    NewBlocks {
+        blockheight: i32,
+    },
+    NewDeposit {
+        outpoint: OutPoint,
+        amount: Amount,
+    },
+    VaultStatusChanged {
+        outpoint: OutPoint,
+        previous: VaultStatus,
+        status: VaultStatus,
+    },
+    /// The vault is no longer listed by the daemon, its deposit was
+    /// unconfirmed and then dropped by a reorg or replaced.
+    VaultRemoved {
+        outpoint: OutPoint,
+    },
+    /// previous is None if the spend transaction was created since the last poll.
+    SpendTxStatusChanged {
+        txid: Txid,
+        previous: Option<SpendTxStatus>,
+        status: SpendTxStatus,
+    },
+    SpendTxDeleted {
+        txid: Txid,
+    },
+}
+
+impl WatcherEvent {
+    /// Returns true if the event changes the list of vaults.
+    pub fn is_vault_change(&self) -> bool {
+        matches!(
+            self,
+            Self::NewDeposit { .. } | Self::VaultStatusChanged { .. } | Self::VaultRemoved { .. }
+        )
+    }
+
+    /// Returns true if the event changes the list of spend transactions.
+    pub fn is_spend_tx_change(&self) -> bool {
+        matches!(
+            self,
+            Self::SpendTxStatusChanged { .. } | Self::SpendTxDeleted { .. }
+        )
+    }
+}
+
+/// Snapshot is the part of the daemon state the watcher follows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub blockheight: i32,
+    pub vaults: BTreeMap<OutPoint, (Amount, VaultStatus)>,
+    pub spend_txs: BTreeMap<Txid, SpendTxStatus>,
+}
+
+impl Snapshot {
+    /// The spend transactions are only known by the managers daemon.
+    pub async fn fetch(
+        revaultd: &(dyn Daemon + Send + Sync),
+        with_spend_txs: bool,
+    ) -> Result<Self, RevaultDError> {
+        let info = revaultd.get_info().await?;
+        let vaults = revaultd.list_vaults(None, None).await?;
+        let spend_txs = if with_spend_txs {
+            revaultd.list_spend_txs(None).await?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            blockheight: info.blockheight,
+            vaults: vaults
+                .iter()
+                .map(|vault| (outpoint(vault), (vault.amount, vault.status)))
+                .collect(),
+            spend_txs: spend_txs
+                .iter()
+                .map(|tx| (tx.psbt.tx().txid(), tx.status.clone()))
+                .collect(),
+        })
+    }
+
+    /// Returns the changes from this snapshot to the next one.
+    pub fn diff(&self, next: &Snapshot) -> Vec<WatcherEvent> {
+        let mut events = Vec::new();
+        if next.blockheight > self.blockheight {
+            events.push(WatcherEvent::NewBlocks {
+                blockheight: next.blockheight,
+            });
+        }
+
+        for (outpoint, (amount, status)) in &next.vaults {
+            match self.vaults.get(outpoint) {
+                None => events.push(WatcherEvent::NewDeposit {
+                    outpoint: *outpoint,
+                    amount: *amount,
+                }),
+                Some((_, previous)) if previous != status => {
+                    events.push(WatcherEvent::VaultStatusChanged {
+                        outpoint: *outpoint,
+                        previous: *previous,
+                        status: *status,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        for outpoint in self.vaults.keys() {
+            if !next.vaults.contains_key(outpoint) {
+                events.push(WatcherEvent::VaultRemoved {
+                    outpoint: *outpoint,
+                });
+            }
+        }
+
+        for (txid, status) in &next.spend_txs {
+            let previous = self.spend_txs.get(txid);
+            if previous != Some(status) {
+                events.push(WatcherEvent::SpendTxStatusChanged {
+                    txid: *txid,
+                    previous: previous.cloned(),
+                    status: status.clone(),
+                });
+            }
+        }
+
+        for txid in self.spend_txs.keys() {
+            if !next.spend_txs.contains_key(txid) {
+                events.push(WatcherEvent::SpendTxDeleted { txid: *txid });
+            }
+        }
+
+        events
+    }
+}
+
+/// Polls the daemon every interval, the first poll only records the snapshot
+/// the next ones are compared to.
+pub fn watch(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    interval: Duration,
+    with_spend_txs: bool,
+) -> Subscription<Result<Vec<WatcherEvent>, RevaultDError>> {
+    Subscription::from_recipe(Watcher {
+        revaultd,
+        interval,
+        with_spend_txs,
+    })
+}
+
+pub struct Watcher {
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    interval: Duration,
+    with_spend_txs: bool,
+}
+
+struct WatcherState {
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    interval: Duration,
+    with_spend_txs: bool,
+    started: bool,
+    snapshot: Option<Snapshot>,
+}
+
+impl<H, I> iced_native::subscription::Recipe<H, I> for Watcher
+where
+    H: Hasher,
+{
+    type Output = Result<Vec<WatcherEvent>, RevaultDError>;
+
+    fn hash(&self, state: &mut H) {
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+        self.interval.hash(state);
+        self.with_spend_txs.hash(state);
+    }
+
+    fn stream(
+        self: Box<Self>,
+        _input: futures::stream::BoxStream<'static, I>,
+    ) -> futures::stream::BoxStream<'static, Self::Output> {
+        Box::pin(futures::stream::unfold(
+            WatcherState {
+                revaultd: self.revaultd,
+                interval: self.interval,
+                with_spend_txs: self.with_spend_txs,
+                started: false,
+                snapshot: None,
+            },
+            |mut state| async move {
+                if state.started {
+                    tokio::time::sleep(state.interval).await;
+                }
+                state.started = true;
+                match Snapshot::fetch(state.revaultd.as_ref(), state.with_spend_txs).await {
+                    Ok(snapshot) => {
+                        let events = state
+                            .snapshot
+                            .as_ref()
+                            .map(|previous| previous.diff(&snapshot))
+                            .unwrap_or_default();
+                        state.snapshot = Some(snapshot);
+                        Some((Ok(events), state))
+                    }
+                    Err(e) => Some((Err(e), state)),
+                }
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const TXID: &str = "a9735f42110ce529386f612194a1e137a2a2679ac0e789ad7f470cd70c3c2c24";
+
+    #[test]
+    fn test_snapshot_diff() {
+        let txid = Txid::from_str(TXID).unwrap();
+        let mut previous = Snapshot {
+            blockheight: 10,
+            ..Default::default()
+        };
+        previous.vaults.insert(
+            OutPoint::new(txid, 0),
+            (Amount::from_sat(1_000), VaultStatus::Secured),
+        );
+        previous.spend_txs.insert(txid, SpendTxStatus::NonFinal);
+
+        assert!(previous.diff(&previous).is_empty());
+
+        let mut next = previous.clone();
+        next.blockheight = 12;
+        next.vaults.insert(
+            OutPoint::new(txid, 0),
+            (Amount::from_sat(1_000), VaultStatus::Active),
+        );
+        next.vaults.insert(
+            OutPoint::new(txid, 1),
+            (Amount::from_sat(2_000), VaultStatus::Unconfirmed),
+        );
+        next.spend_txs.insert(txid, SpendTxStatus::Pending);
+        assert_eq!(
+            previous.diff(&next),
+            vec![
+                WatcherEvent::NewBlocks { blockheight: 12 },
+                WatcherEvent::VaultStatusChanged {
+                    outpoint: OutPoint::new(txid, 0),
+                    previous: VaultStatus::Secured,
+                    status: VaultStatus::Active,
+                },
+                WatcherEvent::NewDeposit {
+                    outpoint: OutPoint::new(txid, 1),
+                    amount: Amount::from_sat(2_000),
+                },
+                WatcherEvent::SpendTxStatusChanged {
+                    txid,
+                    previous: Some(SpendTxStatus::NonFinal),
+                    status: SpendTxStatus::Pending,
+                },
+            ]
+        );
+
+        next.spend_txs.clear();
+        assert_eq!(
+            previous.diff(&next).last(),
+            Some(&WatcherEvent::SpendTxDeleted { txid })
+        );
+
+        next = previous.clone();
+        next.vaults.clear();
+        assert_eq!(
+            previous.diff(&next),
+            vec![WatcherEvent::VaultRemoved {
+                outpoint: OutPoint::new(txid, 0),
+            }]
+        );
+    }
+}
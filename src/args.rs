@@ -0,0 +1,178 @@
+use std::{error::Error, path::PathBuf, str::FromStr};
+
+use crate::app::config::default_datadir;
+
+#[derive(Debug, PartialEq)]
+pub enum Arg {
+    ConfigPath(PathBuf),
+    DatadirPath(PathBuf),
+    Network(bitcoin::Network),
+    /// Path to the hwi executable, overriding the one of the configuration.
+    HwiPath(PathBuf),
+}
+
+pub fn parse_args(args: Vec<String>) -> Result<Vec<Arg>, Box<dyn Error>> {
+    let mut res = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        if arg == "--conf" {
+            if let Some(a) = args.get(i + 1) {
+                res.push(Arg::ConfigPath(PathBuf::from(a)));
+            } else {
+                return Err("missing arg to --conf".into());
+            }
+        } else if arg == "--datadir" {
+            if let Some(a) = args.get(i + 1) {
+                res.push(Arg::DatadirPath(PathBuf::from(a)));
+            } else {
+                return Err("missing arg to --datadir".into());
+            }
+        } else if arg == "--hwi" {
+            if let Some(a) = args.get(i + 1) {
+                res.push(Arg::HwiPath(PathBuf::from(a)));
+            } else {
+                return Err("missing arg to --hwi".into());
+            }
+        } else if arg.contains("--") {
+            let network = bitcoin::Network::from_str(args[i].trim_start_matches("--"))?;
+            res.push(Arg::Network(network));
+        }
+    }
+
+    Ok(res)
+}
+
+/// Location of the GUI configuration file designated by the arguments.
+#[derive(Debug, PartialEq)]
+pub enum ConfigLocation {
+    /// Configuration file given with --conf.
+    File(PathBuf),
+    /// Datadir where the configuration file of the network is expected.
+    Datadir(PathBuf, bitcoin::Network),
+}
+
+/// Path to the hwi executable given with --hwi.
+pub fn hwi_path(args: &[Arg]) -> Option<PathBuf> {
+    args.iter().find_map(|arg| match arg {
+        Arg::HwiPath(path) => Some(path.clone()),
+        _ => None,
+    })
+}
+
+pub fn config_location(args: &[Arg]) -> Result<ConfigLocation, Box<dyn Error>> {
+    let args: Vec<&Arg> = args
+        .iter()
+        .filter(|arg| !matches!(arg, Arg::HwiPath(_)))
+        .collect();
+    match args.as_slice() {
+        [] => {
+            let datadir_path = default_datadir().map_err(|_| "Failed to find default datadir")?;
+            Ok(ConfigLocation::Datadir(
+                datadir_path,
+                bitcoin::Network::Bitcoin,
+            ))
+        }
+        [Arg::Network(network)] => {
+            let datadir_path = default_datadir().map_err(|_| "Failed to find default datadir")?;
+            Ok(ConfigLocation::Datadir(datadir_path, *network))
+        }
+        [Arg::ConfigPath(path)] => Ok(ConfigLocation::File(path.clone())),
+        [Arg::DatadirPath(datadir_path)] => Ok(ConfigLocation::Datadir(
+            datadir_path.clone(),
+            bitcoin::Network::Bitcoin,
+        )),
+        [Arg::DatadirPath(datadir_path), Arg::Network(network)]
+        | [Arg::Network(network), Arg::DatadirPath(datadir_path)] => {
+            Ok(ConfigLocation::Datadir(datadir_path.clone(), *network))
+        }
+        _ => Err("Unknown args combination".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(true, parse_args(vec!["--meth".into()]).is_err());
+        assert_eq!(true, parse_args(vec!["--datadir".into()]).is_err());
+        assert_eq!(true, parse_args(vec!["--conf".into()]).is_err());
+        assert_eq!(
+            Some(vec![
+                Arg::DatadirPath(PathBuf::from(".")),
+                Arg::ConfigPath(PathBuf::from("hello.toml")),
+            ]),
+            parse_args(
+                "--datadir . --conf hello.toml"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
+        assert_eq!(
+            Some(vec![Arg::Network(bitcoin::Network::Regtest)]),
+            parse_args(vec!["--regtest".into()]).ok()
+        );
+        assert_eq!(
+            Some(vec![
+                Arg::DatadirPath(PathBuf::from("hello")),
+                Arg::Network(bitcoin::Network::Testnet)
+            ]),
+            parse_args(
+                "--datadir hello --testnet"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
+        assert_eq!(
+            Some(vec![
+                Arg::Network(bitcoin::Network::Testnet),
+                Arg::DatadirPath(PathBuf::from("hello"))
+            ]),
+            parse_args(
+                "--testnet --datadir hello"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
+    }
+
+    #[test]
+    fn test_config_location() {
+        assert_eq!(
+            ConfigLocation::File(PathBuf::from("hello.toml")),
+            config_location(&[Arg::ConfigPath(PathBuf::from("hello.toml"))]).unwrap()
+        );
+        assert_eq!(
+            ConfigLocation::Datadir(PathBuf::from("hello"), bitcoin::Network::Testnet),
+            config_location(&[
+                Arg::Network(bitcoin::Network::Testnet),
+                Arg::DatadirPath(PathBuf::from("hello"))
+            ])
+            .unwrap()
+        );
+        assert!(config_location(&[
+            Arg::DatadirPath(PathBuf::from(".")),
+            Arg::ConfigPath(PathBuf::from("hello.toml")),
+        ])
+        .is_err());
+        let args = parse_args(
+            "--datadir hello --hwi /usr/bin/hwi --testnet"
+                .split(" ")
+                .map(|a| a.to_string())
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(hwi_path(&args), Some(PathBuf::from("/usr/bin/hwi")));
+        assert_eq!(
+            ConfigLocation::Datadir(PathBuf::from("hello"), bitcoin::Network::Testnet),
+            config_location(&args).unwrap()
+        );
+        assert!(parse_args(vec!["--hwi".into()]).is_err());
+    }
+}
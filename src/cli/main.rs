@@ -0,0 +1,472 @@
+mod table;
+
+use std::{
+    error::Error,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::{Amount, OutPoint};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use serde_json::json;
+
+use revaultd::{config::Config as DaemonConfig, revault_tx::transactions::RevaultTransaction};
+
+use revault_gui::{
+    app::config::Config as GUIConfig,
+    args::{config_location, parse_args, ConfigLocation},
+    conversion::Converter,
+    daemon::{
+        client::{jsonrpc::JsonRPCClient, RevaultD},
+        model::{
+            outpoint, HistoryEvent, HistoryEventKind, ServersStatuses, SpendTx, SpendTxStatus,
+            Vault, VaultStatus, ALL_HISTORY_EVENTS,
+        },
+        Daemon,
+    },
+    loader::socket_path,
+};
+
+use table::Table;
+
+const USAGE: &str = "Usage: revault-cli [--conf <path>] [--datadir <path>] [--<network>] [--json] <command> [<args>]
+
+Commands:
+    list-vaults [<status>...]
+    history [--from <timestamp>] [--to <timestamp>] [--limit <n>] [deposit|cancel|spend...]
+    get-deposit-address
+    list-spend-txs [non_final|pending|broadcasted|confirmed|deprecated...]
+    revault <outpoint>
+    emergency --yes
+    server-status";
+
+/// Default number of events returned by the history command.
+const DEFAULT_HISTORY_LIMIT: u64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug)]
+enum Command {
+    ListVaults(Vec<VaultStatus>),
+    History {
+        kinds: Vec<HistoryEventKind>,
+        from: u32,
+        to: u32,
+        limit: u64,
+    },
+    GetDepositAddress,
+    ListSpendTxs(Vec<SpendTxStatus>),
+    Revault(OutPoint),
+    Emergency,
+    ServerStatus,
+}
+
+/// Splits the arguments into the global ones, understood by revault-gui as well,
+/// and the ones of the command which start with the command name.
+fn split_args(args: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut global = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--conf" || arg == "--datadir" {
+            global.push(arg);
+            if let Some(value) = args.next() {
+                global.push(value);
+            }
+        } else if arg.starts_with("--") {
+            global.push(arg);
+        } else {
+            let mut command = vec![arg];
+            command.extend(args);
+            return (global, command);
+        }
+    }
+    (global, Vec::new())
+}
+
+fn parse_command(args: &[String], now: u32) -> Result<Command, Box<dyn Error>> {
+    let (name, args) = args.split_first().ok_or("Missing command")?;
+    match name.as_str() {
+        "list-vaults" => {
+            let statuses = args
+                .iter()
+                .map(|s| {
+                    VaultStatus::from_str(s).map_err(|_| format!("Unknown vault status '{}'", s))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Command::ListVaults(statuses))
+        }
+        "history" => {
+            let mut kinds = Vec::new();
+            let mut from = 0;
+            let mut to = now;
+            let mut limit = DEFAULT_HISTORY_LIMIT;
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--from" => from = option_value(arg, args.next())?,
+                    "--to" => to = option_value(arg, args.next())?,
+                    "--limit" => limit = option_value(arg, args.next())?,
+                    kind => kinds.push(history_event_kind(kind)?),
+                }
+            }
+            if kinds.is_empty() {
+                kinds = ALL_HISTORY_EVENTS.to_vec();
+            }
+            Ok(Command::History {
+                kinds,
+                from,
+                to,
+                limit,
+            })
+        }
+        "get-deposit-address" => Ok(Command::GetDepositAddress),
+        "list-spend-txs" => {
+            let statuses = args
+                .iter()
+                .map(|s| spend_tx_status(s))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Command::ListSpendTxs(statuses))
+        }
+        "revault" => {
+            let outpoint = args.first().ok_or("Missing vault outpoint")?;
+            Ok(Command::Revault(OutPoint::from_str(outpoint).map_err(
+                |e| format!("Invalid outpoint '{}': {}", outpoint, e),
+            )?))
+        }
+        "emergency" => {
+            // The emergency transactions send all the funds to the emergency deep vault,
+            // the user must explicitly acknowledge it.
+            if args.iter().any(|a| a == "--yes") {
+                Ok(Command::Emergency)
+            } else {
+                Err("The emergency command broadcasts the emergency transactions of all the vaults, run it with --yes to confirm".into())
+            }
+        }
+        "server-status" => Ok(Command::ServerStatus),
+        _ => Err(format!("Unknown command '{}'", name).into()),
+    }
+}
+
+fn option_value<T: FromStr>(option: &str, value: Option<&String>) -> Result<T, Box<dyn Error>> {
+    let value = value.ok_or_else(|| format!("missing arg to {}", option))?;
+    T::from_str(value).map_err(|_| format!("Invalid value '{}' for {}", value, option).into())
+}
+
+fn history_event_kind(s: &str) -> Result<HistoryEventKind, Box<dyn Error>> {
+    match s {
+        "deposit" => Ok(HistoryEventKind::Deposit),
+        "cancel" => Ok(HistoryEventKind::Cancel),
+        "spend" => Ok(HistoryEventKind::Spend),
+        _ => Err(format!("Unknown history event kind '{}'", s).into()),
+    }
+}
+
+fn history_event_kind_name(kind: &HistoryEventKind) -> &'static str {
+    match kind {
+        HistoryEventKind::Deposit => "deposit",
+        HistoryEventKind::Cancel => "cancel",
+        HistoryEventKind::Spend => "spend",
+    }
+}
+
+fn spend_tx_status(s: &str) -> Result<SpendTxStatus, Box<dyn Error>> {
+    match s {
+        "non_final" => Ok(SpendTxStatus::NonFinal),
+        "pending" => Ok(SpendTxStatus::Pending),
+        "broadcasted" => Ok(SpendTxStatus::Broadcasted),
+        "confirmed" => Ok(SpendTxStatus::Confirmed),
+        "deprecated" => Ok(SpendTxStatus::Deprecated),
+        _ => Err(format!("Unknown spend transaction status '{}'", s).into()),
+    }
+}
+
+fn spend_tx_status_name(status: &SpendTxStatus) -> &'static str {
+    match status {
+        SpendTxStatus::NonFinal => "non_final",
+        SpendTxStatus::Pending => "pending",
+        SpendTxStatus::Broadcasted => "broadcasted",
+        SpendTxStatus::Confirmed => "confirmed",
+        SpendTxStatus::Deprecated => "deprecated",
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn amount(converter: &Converter, amount: Amount) -> String {
+    format!("{} {}", converter.converts(amount), converter.unit)
+}
+
+fn vaults_table(vaults: &[Vault], converter: &Converter) -> Table {
+    let mut table = Table::new(vec!["OUTPOINT", "STATUS", "AMOUNT", "BLOCKHEIGHT"]);
+    for vault in vaults {
+        table.push(vec![
+            outpoint(vault).to_string(),
+            vault.status.to_string(),
+            amount(converter, vault.amount),
+            vault
+                .blockheight
+                .map(|h| h.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table
+}
+
+fn history_table(events: &[HistoryEvent], converter: &Converter) -> Table {
+    let mut table = Table::new(vec!["DATE", "KIND", "TXID", "AMOUNT", "FEE", "VAULTS"]);
+    for event in events {
+        table.push(vec![
+            NaiveDateTime::from_timestamp(event.date.into(), 0)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            history_event_kind_name(&event.kind).to_string(),
+            event.txid.to_string(),
+            event
+                .amount
+                .map(|a| amount(converter, Amount::from_sat(a)))
+                .unwrap_or_else(|| "-".to_string()),
+            event
+                .miner_fee
+                .map(|a| amount(converter, Amount::from_sat(a)))
+                .unwrap_or_else(|| "-".to_string()),
+            event.vaults.len().to_string(),
+        ]);
+    }
+    table
+}
+
+fn spend_txs_table(txs: &[SpendTx]) -> Table {
+    let mut table = Table::new(vec!["TXID", "STATUS", "VAULTS", "OUTPUTS"]);
+    for tx in txs {
+        table.push(vec![
+            tx.psbt.tx().txid().to_string(),
+            spend_tx_status_name(&tx.status).to_string(),
+            tx.deposit_outpoints.len().to_string(),
+            tx.psbt.tx().output.len().to_string(),
+        ]);
+    }
+    table
+}
+
+fn server_status_table(statuses: &ServersStatuses) -> Table {
+    let mut table = Table::new(vec!["SERVER", "HOST", "REACHABLE"]);
+    table.push(vec![
+        "coordinator".to_string(),
+        statuses.coordinator.host.clone(),
+        statuses.coordinator.reachable.to_string(),
+    ]);
+    for cosigner in &statuses.cosigners {
+        table.push(vec![
+            "cosigner".to_string(),
+            cosigner.host.clone(),
+            cosigner.reachable.to_string(),
+        ]);
+    }
+    for watchtower in &statuses.watchtowers {
+        table.push(vec![
+            "watchtower".to_string(),
+            watchtower.host.clone(),
+            watchtower.reachable.to_string(),
+        ]);
+    }
+    table
+}
+
+async fn run(
+    revaultd: &dyn Daemon,
+    command: Command,
+    format: Format,
+    converter: &Converter,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::ListVaults(statuses) => {
+            let statuses = if statuses.is_empty() {
+                None
+            } else {
+                Some(statuses.as_slice())
+            };
+            let vaults = revaultd.list_vaults(statuses, None).await?;
+            match format {
+                Format::Json => print_json(&vaults)?,
+                Format::Table => print!("{}", vaults_table(&vaults, converter)),
+            }
+        }
+        Command::History {
+            kinds,
+            from,
+            to,
+            limit,
+        } => {
+            let events = revaultd.get_history(&kinds, from, to, limit).await?;
+            match format {
+                Format::Json => print_json(&events)?,
+                Format::Table => print!("{}", history_table(&events, converter)),
+            }
+        }
+        Command::GetDepositAddress => {
+            let address = revaultd.get_deposit_address().await?;
+            match format {
+                Format::Json => print_json(&json!({ "address": address }))?,
+                Format::Table => println!("{}", address),
+            }
+        }
+        Command::ListSpendTxs(statuses) => {
+            let statuses = if statuses.is_empty() {
+                None
+            } else {
+                Some(statuses.as_slice())
+            };
+            let txs = revaultd.list_spend_txs(statuses).await?;
+            match format {
+                Format::Json => print_json(&txs)?,
+                Format::Table => print!("{}", spend_txs_table(&txs)),
+            }
+        }
+        Command::Revault(outpoint) => {
+            revaultd.revault(&outpoint).await?;
+            if format == Format::Json {
+                print_json(&json!({}))?;
+            }
+        }
+        Command::Emergency => {
+            revaultd.emergency().await?;
+            if format == Format::Json {
+                print_json(&json!({}))?;
+            }
+        }
+        Command::ServerStatus => {
+            let statuses = revaultd.get_server_status().await?;
+            match format {
+                Format::Json => print_json(&statuses)?,
+                Format::Table => print!("{}", server_status_table(&statuses)),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let format = if args.iter().any(|a| a == "--json") {
+        args.retain(|a| a != "--json");
+        Format::Json
+    } else {
+        Format::Table
+    };
+
+    let (global, command) = split_args(args);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as u32;
+    let command = parse_command(&command, now)?;
+
+    let gui_config = match config_location(&parse_args(global)?)? {
+        ConfigLocation::File(path) => GUIConfig::from_file(&path)?,
+        ConfigLocation::Datadir(mut path, network) => {
+            path.push(GUIConfig::file_name(&network));
+            GUIConfig::from_file(&path)?
+        }
+    };
+    let daemon_config = DaemonConfig::from_file(Some(gui_config.revaultd_config_path.clone()))
+        .map_err(|e| format!("Failed to read revaultd configuration file: {}", e))?;
+
+    let network = daemon_config.bitcoind_config.network;
+    let path = socket_path(&daemon_config.data_dir, network)?;
+    let revaultd = RevaultD::new(JsonRPCClient::new(path));
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(
+            &revaultd,
+            command,
+            format,
+            &Converter::new(network).with_denomination(gui_config.unit.unwrap_or_default()),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            (
+                args("--datadir hello --testnet"),
+                args("history --limit 5 spend")
+            ),
+            split_args(args("--datadir hello --testnet history --limit 5 spend"))
+        );
+        assert_eq!(
+            (args("--conf list-vaults"), Vec::new()),
+            split_args(args("--conf list-vaults"))
+        );
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert!(parse_command(&[], 10).is_err());
+        assert!(parse_command(&args("unknown"), 10).is_err());
+        assert!(parse_command(&args("emergency"), 10).is_err());
+        assert!(matches!(
+            parse_command(&args("emergency --yes"), 10),
+            Ok(Command::Emergency)
+        ));
+        assert!(parse_command(&args("revault"), 10).is_err());
+        assert!(matches!(
+            parse_command(
+                &args("revault a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d:1"),
+                10
+            ),
+            Ok(Command::Revault(_))
+        ));
+        assert!(matches!(
+            parse_command(&args("list-spend-txs pending broadcasted"), 10),
+            Ok(Command::ListSpendTxs(statuses)) if statuses.len() == 2
+        ));
+        assert!(parse_command(&args("list-spend-txs signed"), 10).is_err());
+
+        match parse_command(&args("history"), 10).unwrap() {
+            Command::History {
+                kinds,
+                from,
+                to,
+                limit,
+            } => {
+                assert_eq!(kinds.len(), ALL_HISTORY_EVENTS.len());
+                assert_eq!((from, to, limit), (0, 10, DEFAULT_HISTORY_LIMIT));
+            }
+            _ => panic!("wrong command"),
+        }
+        match parse_command(&args("history --from 2 --limit 5 deposit"), 10).unwrap() {
+            Command::History {
+                kinds,
+                from,
+                to,
+                limit,
+            } => {
+                assert!(matches!(kinds.as_slice(), [HistoryEventKind::Deposit]));
+                assert_eq!((from, to, limit), (2, 10, 5));
+            }
+            _ => panic!("wrong command"),
+        }
+        assert!(parse_command(&args("history --limit"), 10).is_err());
+        assert!(parse_command(&args("history --from yesterday"), 10).is_err());
+    }
+}
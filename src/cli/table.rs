@@ -0,0 +1,66 @@
+/// Table displays rows of cells in left aligned columns under a header line.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = std::cmp::max(*width, cell.chars().count());
+            }
+        }
+
+        let headers: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        for row in std::iter::once(&headers).chain(self.rows.iter()) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_display() {
+        let mut table = Table::new(vec!["OUTPOINT", "STATUS", "AMOUNT"]);
+        table.push(vec![
+            "a:0".to_string(),
+            "active".to_string(),
+            "1".to_string(),
+        ]);
+        table.push(vec![
+            "b:1".to_string(),
+            "unvaulting".to_string(),
+            "0.5".to_string(),
+        ]);
+        assert_eq!(
+            table.to_string(),
+            "OUTPOINT  STATUS      AMOUNT\n\
+             a:0       active      1\n\
+             b:1       unvaulting  0.5\n"
+        );
+    }
+}
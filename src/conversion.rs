@@ -1,43 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use bitcoin::Network;
+use serde::{Deserialize, Serialize};
 
 /// Converter purpose is to give a Conversion from a given amount in satoshis according to its
 /// parameters.
 pub struct Converter {
     pub unit: Unit,
+    /// Last price given by the price source, used for the fiat value of the amounts.
+    pub price: Option<Price>,
+    source: Option<Arc<dyn PriceSource + Send + Sync>>,
 }
 
 impl Converter {
     pub fn new(bitcoin_network: Network) -> Self {
-        let unit = match bitcoin_network {
-            Network::Testnet => Unit::TestnetBitcoin,
-            Network::Bitcoin => Unit::Bitcoin,
-            Network::Regtest => Unit::RegtestBitcoin,
-            Network::Signet => Unit::SignetBitcoin,
-        };
-        Self { unit }
+        Self {
+            unit: Unit {
+                network: bitcoin_network,
+                denomination: Denomination::default(),
+            },
+            price: None,
+            source: None,
+        }
+    }
+
+    pub fn with_denomination(mut self, denomination: Denomination) -> Self {
+        self.unit.denomination = denomination;
+        self
+    }
+
+    pub fn with_price_source(mut self, source: Option<Box<dyn PriceSource + Send + Sync>>) -> Self {
+        self.set_price_source(source);
+        self
+    }
+
+    /// Sets the price source, its price is fetched with fetch_price.
+    pub fn set_price_source(&mut self, source: Option<Box<dyn PriceSource + Send + Sync>>) {
+        self.source = source.map(Arc::from);
     }
 
-    /// converts amount in satoshis to BTC float.
+    pub fn price_source(&self) -> Option<Arc<dyn PriceSource + Send + Sync>> {
+        self.source.clone()
+    }
+
+    /// Sets the price fetched from the source, the previous price is kept if it failed.
+    pub fn set_price(&mut self, res: Result<Price, PriceError>) {
+        match res {
+            Ok(price) => self.price = Some(price),
+            Err(e) => log::warn!("Failed to get price: {}", e),
+        }
+    }
+
+    /// converts amount in satoshis to the unit denomination.
     pub fn converts(&self, amount: bitcoin::Amount) -> String {
-        format!("{:.8}", amount.as_btc())
+        match self.unit.denomination {
+            Denomination::Btc => format!("{:.8}", amount.as_btc()),
+            Denomination::MilliBtc => format!("{:.5}", amount.as_sat() as f64 / 100_000.0),
+            Denomination::Sat => amount.as_sat().to_string(),
+        }
+    }
+
+    /// parses an amount written in the unit denomination.
+    pub fn parse(&self, amount: &str) -> Option<bitcoin::Amount> {
+        let denomination = match self.unit.denomination {
+            Denomination::Btc => bitcoin::Denomination::Bitcoin,
+            Denomination::MilliBtc => bitcoin::Denomination::MilliBitcoin,
+            Denomination::Sat => bitcoin::Denomination::Satoshi,
+        };
+        bitcoin::Amount::from_str_in(amount.trim(), denomination).ok()
+    }
+
+    /// converts amount in satoshis to its value in fiat currency, with the currency code.
+    /// Returns None if there is no price available.
+    pub fn converts_fiat(&self, amount: bitcoin::Amount) -> Option<String> {
+        self.price
+            .as_ref()
+            .map(|price| format!("{:.2} {}", amount.as_btc() * price.rate, price.currency))
     }
 }
 
-/// Unit is the bitcoin ticker according to the network used.
-pub enum Unit {
-    TestnetBitcoin,
-    RegtestBitcoin,
-    SignetBitcoin,
-    Bitcoin,
+/// Unit is the bitcoin denomination displayed with the ticker of the network used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unit {
+    pub network: Network,
+    pub denomination: Denomination,
 }
 
 impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let prefix = match self.network {
+            Network::Testnet => "t",
+            Network::Regtest => "r",
+            Network::Signet => "s",
+            Network::Bitcoin => "",
+        };
+        match self.denomination {
+            Denomination::Btc => write!(f, "{}BTC", prefix),
+            Denomination::MilliBtc => write!(f, "m{}BTC", prefix),
+            Denomination::Sat => write!(f, "{}sats", prefix),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Denomination {
+    Btc,
+    #[serde(rename = "mbtc")]
+    MilliBtc,
+    #[serde(rename = "sats")]
+    Sat,
+}
+
+impl Denomination {
+    pub const ALL: [Denomination; 3] =
+        [Denomination::Btc, Denomination::MilliBtc, Denomination::Sat];
+}
+
+impl Default for Denomination {
+    fn default() -> Self {
+        Denomination::Btc
+    }
+}
+
+impl std::fmt::Display for Denomination {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Btc => write!(f, "BTC"),
+            Self::MilliBtc => write!(f, "mBTC"),
+            Self::Sat => write!(f, "sats"),
+        }
+    }
+}
+
+/// Price of one bitcoin in a fiat currency.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Price {
+    /// Currency code, like "USD" or "EUR".
+    pub currency: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub enum PriceError {
+    ReadingFile(String),
+    Parsing(String),
+}
+
+impl std::fmt::Display for PriceError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::TestnetBitcoin => write!(f, "tBTC"),
-            Self::RegtestBitcoin => write!(f, "rBTC"),
-            Self::SignetBitcoin => write!(f, "sBTC"),
-            Self::Bitcoin => write!(f, "BTC"),
+            Self::ReadingFile(e) => write!(f, "Failed to read price file: {}", e),
+            Self::Parsing(e) => write!(f, "Failed to parse price: {}", e),
+        }
+    }
+}
+
+/// Fetches the price on a blocking thread, as the source may read a file.
+pub async fn fetch_price(source: Arc<dyn PriceSource + Send + Sync>) -> Result<Price, PriceError> {
+    tokio::task::spawn_blocking(move || source.price())
+        .await
+        .map_err(|e| PriceError::ReadingFile(e.to_string()))?
+}
+
+/// PriceSource gives the current price of one bitcoin.
+pub trait PriceSource {
+    fn price(&self) -> Result<Price, PriceError>;
+}
+
+/// StaticPrice always gives the same price.
+pub struct StaticPrice(pub Price);
+
+impl PriceSource for StaticPrice {
+    fn price(&self) -> Result<Price, PriceError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// FilePrice reads the price from a toml file kept up to date by another program:
+///
+/// ```toml
+/// currency = "EUR"
+/// rate = 30000.0
+/// ```
+pub struct FilePrice {
+    path: PathBuf,
+}
+
+impl FilePrice {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
         }
     }
 }
+
+impl PriceSource for FilePrice {
+    fn price(&self) -> Result<Price, PriceError> {
+        let content =
+            std::fs::read(&self.path).map_err(|e| PriceError::ReadingFile(e.to_string()))?;
+        toml::from_slice(&content).map_err(|e| PriceError::Parsing(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::Amount;
+
+    #[test]
+    fn test_converts() {
+        let amount = Amount::from_sat(123_456_789);
+        let converter = Converter::new(Network::Bitcoin);
+        assert_eq!(converter.converts(amount), "1.23456789");
+        assert_eq!(converter.unit.to_string(), "BTC");
+        assert_eq!(converter.converts_fiat(amount), None);
+
+        let converter = Converter::new(Network::Testnet).with_denomination(Denomination::MilliBtc);
+        assert_eq!(converter.converts(amount), "1234.56789");
+        assert_eq!(converter.unit.to_string(), "mtBTC");
+        assert_eq!(converter.parse(" 1234.56789"), Some(amount));
+        assert_eq!(converter.parse("1,2"), None);
+
+        let mut converter = Converter::new(Network::Bitcoin)
+            .with_denomination(Denomination::Sat)
+            .with_price_source(Some(Box::new(StaticPrice(Price {
+                currency: "EUR".to_string(),
+                rate: 20_000.0,
+            }))));
+        assert_eq!(converter.converts(amount), "123456789");
+        assert_eq!(converter.unit.to_string(), "sats");
+        assert_eq!(converter.converts_fiat(amount), None);
+        let price = converter.price_source().unwrap().price();
+        converter.set_price(price);
+        assert_eq!(
+            converter.converts_fiat(amount),
+            Some("24691.36 EUR".to_string())
+        );
+    }
+
+    #[test]
+    fn test_file_price() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("revault_gui_price_{}.toml", std::process::id()));
+        std::fs::write(&path, "currency = \"USD\"\nrate = 40000.5\n").unwrap();
+        let price = FilePrice::new(&path).price().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            price,
+            Price {
+                currency: "USD".to_string(),
+                rate: 40000.5,
+            }
+        );
+        assert!(FilePrice::new(&path).price().is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Child-pays-for-parent: bumps the feerate of a broadcasted spend transaction
+//! with a child transaction spending its CPFP output, which is locked by the
+//! CPFP descriptor of the managers.
+
+use bitcoin::{
+    blockdata::{
+        opcodes,
+        script::Builder,
+        transaction::{OutPoint, TxIn, TxOut},
+    },
+    secp256k1::Secp256k1,
+    util::{bip32::ChildNumber, psbt::PartiallySignedTransaction as Psbt},
+    PublicKey, Script, Transaction,
+};
+use revaultd::revault_tx::{miniscript::DescriptorPublicKey, scripts::CpfpDescriptor};
+
+/// Outputs below this value are not relayed.
+const DUST_LIMIT: u64 = 330;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CpfpError {
+    /// The spend transaction has no output at the CPFP index.
+    MissingOutput,
+    /// The derivation of the CPFP output keys is not in the spend psbt.
+    MissingDerivation,
+    /// The CPFP output is not locked by the CPFP descriptor.
+    DescriptorMismatch,
+    /// The CPFP output value cannot pay the fees.
+    InsufficientFunds {
+        available: u64,
+        needed: u64,
+    },
+    MissingSignature,
+}
+
+impl std::fmt::Display for CpfpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MissingOutput => write!(f, "Spend transaction has no CPFP output"),
+            Self::MissingDerivation => write!(f, "CPFP output derivation is unknown"),
+            Self::DescriptorMismatch => {
+                write!(f, "CPFP output is not locked by the CPFP descriptor")
+            }
+            Self::InsufficientFunds { available, needed } => write!(
+                f,
+                "CPFP output of {} sats cannot pay the {} sats of fees",
+                available, needed
+            ),
+            Self::MissingSignature => write!(f, "CPFP transaction is not signed"),
+        }
+    }
+}
+
+/// Builds the psbt of the transaction spending the CPFP output of the spend
+/// transaction to the same script, with enough fees for the package
+/// of the two transactions to reach the given feerate in sat/vbyte.
+pub fn cpfp_psbt(
+    cpfp_descriptor: &CpfpDescriptor,
+    spend_tx: &Psbt,
+    cpfp_index: usize,
+    parent_vsize: u64,
+    parent_fee: u64,
+    feerate: u64,
+) -> Result<Psbt, CpfpError> {
+    let txout = spend_tx
+        .global
+        .unsigned_tx
+        .output
+        .get(cpfp_index)
+        .ok_or(CpfpError::MissingOutput)?;
+    let bip32_derivation = spend_tx
+        .outputs
+        .get(cpfp_index)
+        .map(|output| output.bip32_derivation.clone())
+        .ok_or(CpfpError::MissingOutput)?;
+    let index = bip32_derivation
+        .values()
+        .next()
+        .and_then(|(_, path)| path.as_ref().last().copied())
+        .ok_or(CpfpError::MissingDerivation)?;
+    let witness_script = cpfp_witness_script(cpfp_descriptor, index, &txout.script_pubkey)?;
+
+    let fee = cpfp_fee(
+        parent_vsize,
+        parent_fee,
+        cpfp_vsize(witness_script.len()),
+        feerate,
+    );
+    if txout.value < fee + DUST_LIMIT {
+        return Err(CpfpError::InsufficientFunds {
+            available: txout.value,
+            needed: fee,
+        });
+    }
+
+    let tx = Transaction {
+        version: 2,
+        lock_time: 0,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(spend_tx.global.unsigned_tx.txid(), cpfp_index as u32),
+            script_sig: Script::new(),
+            // Signals replaceability, the child can be bumped again.
+            sequence: 0xFFFFFFFD,
+            witness: Vec::new(),
+        }],
+        output: vec![TxOut {
+            value: txout.value - fee,
+            script_pubkey: txout.script_pubkey.clone(),
+        }],
+    };
+
+    let mut psbt = Psbt::from_unsigned_tx(tx).expect("Transaction is unsigned");
+    psbt.inputs[0].witness_utxo = Some(txout.clone());
+    psbt.inputs[0].witness_script = Some(witness_script.clone());
+    psbt.inputs[0].bip32_derivation = bip32_derivation.clone();
+    psbt.outputs[0].witness_script = Some(witness_script);
+    psbt.outputs[0].bip32_derivation = bip32_derivation;
+    Ok(psbt)
+}
+
+/// Returns the fees the child must pay for the package to reach the feerate,
+/// at least enough to pay for itself.
+pub fn cpfp_fee(parent_vsize: u64, parent_fee: u64, child_vsize: u64, feerate: u64) -> u64 {
+    let package_fee = feerate * (parent_vsize + child_vsize);
+    std::cmp::max(
+        package_fee.saturating_sub(parent_fee),
+        feerate * child_vsize,
+    )
+}
+
+/// Estimated virtual size of the transaction spending the CPFP output
+/// with a single signature to a P2WSH output.
+pub fn cpfp_vsize(witness_script_len: usize) -> u64 {
+    // version, locktime, inputs and outputs counts, outpoint, empty script_sig,
+    // sequence, value and P2WSH script_pubkey.
+    let base = 4 + 4 + 1 + 1 + 36 + 1 + 4 + 8 + 1 + 34;
+    let script_len_size = if witness_script_len < 0xfd { 1 } else { 3 };
+    // marker and flag, number of items, empty item for the CHECKMULTISIG bug,
+    // signature and witness script.
+    let witness = 2 + 1 + 1 + 1 + 73 + script_len_size + witness_script_len;
+    ((base * 4 + witness) as u64).div_ceil(4)
+}
+
+/// Derives the 1-of-N multisig script of the CPFP descriptor at the given index,
+/// and checks that it locks the given script_pubkey.
+fn cpfp_witness_script(
+    cpfp_descriptor: &CpfpDescriptor,
+    index: ChildNumber,
+    script_pubkey: &Script,
+) -> Result<Script, CpfpError> {
+    let secp = Secp256k1::verification_only();
+    let mut keys = Vec::new();
+    for xpub in cpfp_descriptor.xpubs() {
+        match xpub {
+            DescriptorPublicKey::XPub(xkey) => {
+                let key = xkey
+                    .xkey
+                    .derive_pub(&secp, &xkey.derivation_path.child(index))
+                    .map_err(|_| CpfpError::DescriptorMismatch)?;
+                keys.push(key.public_key);
+            }
+            DescriptorPublicKey::SinglePub(single) => keys.push(single.key),
+        }
+    }
+
+    let multisig = |keys: &[PublicKey]| {
+        let mut builder = Builder::new().push_int(1);
+        for key in keys {
+            builder = builder.push_key(key);
+        }
+        builder
+            .push_int(keys.len() as i64)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script()
+    };
+
+    // The keys are in the order of the descriptor, or sorted for a sortedmulti.
+    let script = multisig(&keys);
+    if Script::new_v0_wsh(&script.wscript_hash()) == *script_pubkey {
+        return Ok(script);
+    }
+    keys.sort_by_key(|key| key.to_bytes());
+    let script = multisig(&keys);
+    if Script::new_v0_wsh(&script.wscript_hash()) == *script_pubkey {
+        return Ok(script);
+    }
+    Err(CpfpError::DescriptorMismatch)
+}
+
+/// Returns the CPFP transaction with the witness built from the signature
+/// of the psbt.
+pub fn finalize_cpfp(psbt: &Psbt) -> Result<Transaction, CpfpError> {
+    let mut tx = psbt.global.unsigned_tx.clone();
+    let input = psbt.inputs.first().ok_or(CpfpError::MissingSignature)?;
+    let sig = input
+        .partial_sigs
+        .values()
+        .next()
+        .ok_or(CpfpError::MissingSignature)?;
+    let witness_script = input
+        .witness_script
+        .as_ref()
+        .ok_or(CpfpError::DescriptorMismatch)?;
+    tx.input[0].witness = vec![Vec::new(), sig.clone(), witness_script.to_bytes()];
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::util::bip32::{DerivationPath, Fingerprint};
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    const CPFP_XPUBS: [&str; 2] = [
+        "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9",
+        "xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx",
+    ];
+
+    fn spend_psbt(descriptor: &CpfpDescriptor, value: u64) -> Psbt {
+        let index = ChildNumber::from_normal_idx(3).unwrap();
+        // The script_pubkey is built with the function under test, the test checks
+        // that the psbt is consistent with it.
+        let mut keys = Vec::new();
+        for xpub in descriptor.xpubs() {
+            if let DescriptorPublicKey::XPub(xkey) = xpub {
+                keys.push(
+                    xkey.xkey
+                        .derive_pub(&Secp256k1::verification_only(), &[index])
+                        .unwrap()
+                        .public_key,
+                );
+            }
+        }
+        let mut builder = Builder::new().push_int(1);
+        for key in &keys {
+            builder = builder.push_key(key);
+        }
+        let script = builder
+            .push_int(keys.len() as i64)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: Vec::new(),
+            output: vec![TxOut {
+                value,
+                script_pubkey: Script::new_v0_wsh(&script.wscript_hash()),
+            }],
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        let mut derivation = BTreeMap::new();
+        derivation.insert(
+            keys[0],
+            (
+                Fingerprint::default(),
+                DerivationPath::from_str("m/3").unwrap(),
+            ),
+        );
+        psbt.outputs[0].bip32_derivation = derivation;
+        psbt
+    }
+
+    #[test]
+    fn test_cpfp_psbt() {
+        let descriptor = CpfpDescriptor::new(
+            CPFP_XPUBS
+                .iter()
+                .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+                .collect(),
+        )
+        .unwrap();
+
+        let spend = spend_psbt(&descriptor, 30_000);
+        let psbt = cpfp_psbt(&descriptor, &spend, 0, 200, 200, 10).unwrap();
+        let child_vsize = cpfp_vsize(psbt.inputs[0].witness_script.as_ref().unwrap().len());
+        assert_eq!(
+            psbt.global.unsigned_tx.output[0].value,
+            30_000 - (10 * (200 + child_vsize) - 200)
+        );
+        assert_eq!(
+            psbt.global.unsigned_tx.input[0].previous_output,
+            OutPoint::new(spend.global.unsigned_tx.txid(), 0)
+        );
+
+        assert_eq!(
+            cpfp_psbt(&descriptor, &spend, 1, 200, 200, 10),
+            Err(CpfpError::MissingOutput)
+        );
+        assert!(matches!(
+            cpfp_psbt(&descriptor, &spend, 0, 200, 200, 1000),
+            Err(CpfpError::InsufficientFunds { .. })
+        ));
+        assert_eq!(finalize_cpfp(&psbt), Err(CpfpError::MissingSignature));
+    }
+
+    #[test]
+    fn test_cpfp_fee() {
+        // The parent already pays more than the package feerate.
+        assert_eq!(cpfp_fee(100, 10_000, 100, 10), 1_000);
+        assert_eq!(cpfp_fee(100, 100, 100, 10), 1_900);
+    }
+}
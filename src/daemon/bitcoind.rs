@@ -0,0 +1,147 @@
+//! Client of the bitcoind JSON-RPC interface, for the few calls revaultd
+//! does not relay (fee bumping, fee estimation, emergency broadcast): its API
+//! has no command for them, so they cannot go through the Daemon trait.
+//! The connection parameters are the ones of the revaultd configuration, and
+//! the HTTP transport is the one revaultd itself uses to reach bitcoind.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bitcoin::{consensus::encode, Transaction, Txid};
+use jsonrpc::{
+    arg,
+    simple_http::{Error as HttpError, SimpleHttpTransport},
+    Client,
+};
+use revaultd::config::BitcoindConfig;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::RevaultDError;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error code of bitcoind for a transaction already in the chain.
+const RPC_VERIFY_ALREADY_IN_CHAIN: i32 = -27;
+
+#[derive(Debug, Clone)]
+pub struct BitcoinD {
+    url: String,
+    cookie_path: PathBuf,
+}
+
+/// Part of the bitcoind getmempoolentry result.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolEntry {
+    pub vsize: u64,
+    pub fees: MempoolEntryFees,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolEntryFees {
+    /// Transaction fee in BTC.
+    pub base: f64,
+}
+
+impl MempoolEntry {
+    pub fn fee(&self) -> bitcoin::Amount {
+        bitcoin::Amount::from_btc(self.fees.base).unwrap_or_else(|_| bitcoin::Amount::from_sat(0))
+    }
+
+    /// feerate in sat/vbyte.
+    pub fn feerate(&self) -> f64 {
+        self.fee().as_sat() as f64 / self.vsize as f64
+    }
+}
+
+/// Part of the bitcoind estimatesmartfee result.
+#[derive(Debug, Deserialize)]
+struct SmartFeeEstimate {
+    /// Feerate in BTC/kvB, missing if bitcoind has not enough data.
+    feerate: Option<f64>,
+}
+
+impl BitcoinD {
+    pub fn new(config: &BitcoindConfig) -> Self {
+        Self {
+            url: format!("http://{}", config.addr),
+            cookie_path: config.cookie_path.clone(),
+        }
+    }
+
+    /// The cookie is read at each call, bitcoind writes a new one when it restarts.
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[Box<serde_json::value::RawValue>],
+    ) -> Result<T, RevaultDError> {
+        let cookie = std::fs::read_to_string(&self.cookie_path).map_err(|e| {
+            RevaultDError::Unexpected(format!("Failed to read bitcoind cookie: {}", e))
+        })?;
+        let transport = SimpleHttpTransport::builder()
+            .url(&self.url)
+            .map_err(|e| RevaultDError::Unexpected(e.to_string()))?
+            .timeout(TIMEOUT)
+            .cookie_auth(cookie.trim())
+            .build();
+        Client::with_transport(transport)
+            .call(method, params)
+            .map_err(|e| self.error(e))
+    }
+
+    fn error(&self, e: jsonrpc::Error) -> RevaultDError {
+        match e {
+            jsonrpc::Error::Rpc(e) => RevaultDError::Rpc(e.code, e.message),
+            jsonrpc::Error::Transport(e) => match e.downcast_ref::<HttpError>() {
+                Some(HttpError::HttpErrorCode(401)) => RevaultDError::Transport(
+                    Some(ErrorKind::PermissionDenied),
+                    format!(
+                        "bitcoind rejected the credentials of the cookie file {}",
+                        self.cookie_path.display()
+                    ),
+                ),
+                Some(HttpError::SocketError(e)) => {
+                    RevaultDError::Transport(Some(e.kind()), e.to_string())
+                }
+                Some(HttpError::Timeout) => RevaultDError::Transport(
+                    Some(ErrorKind::TimedOut),
+                    "bitcoind did not answer in time".to_string(),
+                ),
+                _ => RevaultDError::Transport(None, e.to_string()),
+            },
+            e => RevaultDError::Unexpected(format!("bitcoind response: {}", e)),
+        }
+    }
+
+    pub fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, RevaultDError> {
+        self.call("getmempoolentry", &[arg(txid)])
+    }
+
+    /// Returns the feerate in sat/vbyte for a confirmation within the target
+    /// number of blocks, or None if bitcoind has no estimate yet.
+    pub fn estimate_smart_fee(&self, conf_target: u16) -> Result<Option<u64>, RevaultDError> {
+        let estimate: SmartFeeEstimate = self.call("estimatesmartfee", &[arg(conf_target)])?;
+        Ok(estimate
+            .feerate
+            .map(|btc_per_kvb| (btc_per_kvb * 100_000.0).ceil() as u64))
+    }
+
+    pub fn send_raw_transaction(&self, tx: &Transaction) -> Result<Txid, RevaultDError> {
+        self.call("sendrawtransaction", &[arg(encode::serialize_hex(tx))])
+    }
+
+    /// Sends the transaction, a transaction already in the mempool or in the
+    /// chain was broadcast before and is not an error.
+    pub fn broadcast(&self, tx: &Transaction) -> Result<Txid, RevaultDError> {
+        match self.send_raw_transaction(tx) {
+            Err(RevaultDError::Rpc(code, message))
+                if code == RPC_VERIFY_ALREADY_IN_CHAIN
+                    || message.contains("txn-already-in-mempool")
+                    || message.contains("txn-already-known") =>
+            {
+                Ok(tx.txid())
+            }
+            res => res,
+        }
+    }
+}
@@ -14,42 +14,85 @@
 //
 //! Client support
 //!
-//! Support for connecting to JSONRPC servers over UNIX socets, sending requests,
-//! and parsing responses
+//! Support for connecting to JSONRPC servers over UNIX sockets, sending requests,
+//! and parsing responses.
+//! The client keeps a single connection open and matches the responses to the
+//! requests with their id, so that independent requests can run at the same time.
 //!
 
-#[cfg(windows)]
-use uds_windows::UnixStream;
-
-#[cfg(not(windows))]
-use std::os::unix::net::UnixStream;
-
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use std::{error, fmt, io};
 
+use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::{to_writer, Deserializer};
+use serde_json::Value;
+
+#[cfg(not(windows))]
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicBool, Arc, Mutex as StdMutex},
+};
+
+#[cfg(not(windows))]
+use serde_json::Deserializer;
+#[cfg(not(windows))]
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+    sync::{oneshot, Mutex},
+    task::JoinHandle,
+};
 
 use log::debug;
 
+/// Timeout of the requests, unless set otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Requests waiting for their response, by id.
+#[cfg(not(windows))]
+type Pending = Arc<StdMutex<HashMap<u64, oneshot::Sender<Response<Value>>>>>;
+
 /// A handle to a remote JSONRPC server
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct JsonRPCClient {
     sockpath: PathBuf,
     timeout: Option<Duration>,
+    next_id: AtomicU64,
+    #[cfg(not(windows))]
+    connection: Mutex<Option<Connection>>,
 }
 
+#[async_trait]
 impl super::Client for JsonRPCClient {
     type Error = Error;
-    fn request<S: Serialize + Debug, D: DeserializeOwned + Debug>(
+    async fn request<S: Serialize + Debug + Send, D: DeserializeOwned + Debug + Send>(
         &self,
         method: &str,
         params: Option<S>,
     ) -> Result<D, Self::Error> {
         self.send_request(method, params)
+            .await
+            .and_then(|res| res.into_result())
+    }
+
+    async fn request_with_timeout<
+        S: Serialize + Debug + Send,
+        D: DeserializeOwned + Debug + Send,
+    >(
+        &self,
+        method: &str,
+        params: Option<S>,
+        timeout: Option<Duration>,
+    ) -> Result<D, Self::Error> {
+        self.send_request_with_timeout(method, params, timeout)
+            .await
             .and_then(|res| res.into_result())
     }
 }
@@ -59,42 +102,53 @@ impl JsonRPCClient {
     pub fn new<P: AsRef<Path>>(sockpath: P) -> JsonRPCClient {
         JsonRPCClient {
             sockpath: sockpath.as_ref().to_path_buf(),
-            timeout: None,
+            timeout: Some(DEFAULT_TIMEOUT),
+            next_id: AtomicU64::new(0),
+            #[cfg(not(windows))]
+            connection: Mutex::new(None),
         }
     }
 
-    /// Set an optional timeout for requests
-    #[allow(dead_code)]
+    /// Set the default timeout of the requests, they never time out if None.
     pub fn set_timeout(&mut self, timeout: Option<Duration>) {
         self.timeout = timeout;
     }
 
-    /// Sends a request to a client
-    pub fn send_request<S: Serialize + Debug, D: DeserializeOwned + Debug>(
+    /// Sends a request to a client, with the default timeout.
+    pub async fn send_request<S: Serialize + Debug, D: DeserializeOwned + Debug>(
         &self,
         method: &str,
         params: Option<S>,
     ) -> Result<Response<D>, Error> {
-        // Setup connection
-        let mut stream = UnixStream::connect(&self.sockpath)?;
-        stream.set_read_timeout(self.timeout)?;
-        stream.set_write_timeout(self.timeout)?;
+        self.send_request_with_timeout(method, params, self.timeout)
+            .await
+    }
 
+    /// Sends a request to a client, failing if no response is received before
+    /// the timeout. Dropping the returned future cancels the request.
+    pub async fn send_request_with_timeout<S: Serialize + Debug, D: DeserializeOwned + Debug>(
+        &self,
+        method: &str,
+        params: Option<S>,
+        timeout: Option<Duration>,
+    ) -> Result<Response<D>, Error> {
         let request = Request {
             method,
             params,
-            id: std::process::id(),
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
             jsonrpc: "2.0",
         };
 
         debug!("Sending to revaultd: {:#?}", request);
 
-        to_writer(&mut stream, &request)?;
+        let body = serde_json::to_vec(&request)?;
+        let response = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.send(request.id, body))
+                .await
+                .map_err(|_| Error::Timeout)??,
+            None => self.send(request.id, body).await?,
+        };
 
-        let response: Response<D> = Deserializer::from_reader(&mut stream)
-            .into_iter()
-            .next()
-            .map_or(Err(Error::NoErrorOrResult), |res| Ok(res?))?;
         if response
             .jsonrpc
             .as_ref()
@@ -109,7 +163,190 @@ impl JsonRPCClient {
 
         debug!("Received from revaultd: {:#?}", response);
 
-        Ok(response)
+        Ok(Response {
+            result: response.result.map(serde_json::from_value).transpose()?,
+            error: response.error,
+            id: response.id,
+            jsonrpc: response.jsonrpc,
+        })
+    }
+
+    /// Writes the request on the connection, opening it if needed,
+    /// and waits for the response with the same id.
+    #[cfg(not(windows))]
+    async fn send(&self, id: u64, body: Vec<u8>) -> Result<Response<Value>, Error> {
+        let (sender, receiver) = oneshot::channel();
+        let _pending = {
+            let mut connection = self.connection.lock().await;
+            if connection
+                .as_ref()
+                .map_or(true, |conn| conn.closed.load(Ordering::SeqCst))
+            {
+                *connection = Some(Connection::open(&self.sockpath).await?);
+            }
+            let conn = connection.as_mut().expect("Connection was just opened");
+            let pending = PendingRequest::new(id, sender, conn.pending.clone());
+
+            // A partially written request would corrupt the stream,
+            // the connection is closed if the write is interrupted.
+            let mut guard = CloseOnDrop {
+                closed: &conn.closed,
+                armed: true,
+            };
+            if let Err(e) = conn.writer.write_all(&body).await {
+                drop(guard);
+                *connection = None;
+                return Err(e.into());
+            }
+            guard.armed = false;
+            pending
+        };
+
+        receiver.await.map_err(|_| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "connection to revaultd was lost",
+            ))
+        })
+    }
+
+    #[cfg(windows)]
+    async fn send(&self, _id: u64, body: Vec<u8>) -> Result<Response<Value>, Error> {
+        use std::io::Write;
+        let sockpath = self.sockpath.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut stream = uds_windows::UnixStream::connect(&sockpath)?;
+            stream.write_all(&body)?;
+            serde_json::Deserializer::from_reader(&mut stream)
+                .into_iter()
+                .next()
+                .map_or(Err(Error::NoErrorOrResult), |res| Ok(res?))
+        })
+        .await
+        .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::Other, e)))?
+    }
+}
+
+/// Connection to revaultd, the responses are read by a background task.
+#[cfg(not(windows))]
+#[derive(Debug)]
+struct Connection {
+    writer: OwnedWriteHalf,
+    pending: Pending,
+    closed: Arc<AtomicBool>,
+    reader: JoinHandle<()>,
+}
+
+#[cfg(not(windows))]
+impl Connection {
+    async fn open(sockpath: &Path) -> Result<Self, Error> {
+        let (reader, writer) = UnixStream::connect(sockpath).await?.into_split();
+        let pending = Pending::default();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reader = tokio::spawn(read_responses(reader, pending.clone(), closed.clone()));
+        Ok(Self {
+            writer,
+            pending,
+            closed,
+            reader,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Dropping the senders of the pending requests fails them.
+        self.reader.abort();
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// Reads the responses from the connection and hands them to the requests
+/// waiting for them, until the connection is closed.
+#[cfg(not(windows))]
+async fn read_responses(mut reader: OwnedReadHalf, pending: Pending, closed: Arc<AtomicBool>) {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 8192];
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) => {
+                debug!("revaultd closed the connection");
+                break;
+            }
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            Err(e) => {
+                debug!("Failed to read from revaultd: {}", e);
+                break;
+            }
+        }
+
+        let mut stream = Deserializer::from_slice(&buffer).into_iter::<Response<Value>>();
+        let mut consumed = 0;
+        let mut corrupted = false;
+        while let Some(res) = stream.next() {
+            match res {
+                Ok(response) => {
+                    if let Some(sender) = pending.lock().unwrap().remove(&response.id) {
+                        // The request may have been cancelled in the meantime.
+                        let _ = sender.send(response);
+                    }
+                }
+                // The rest of the response is not received yet.
+                Err(e) if e.is_eof() => break,
+                Err(e) => {
+                    debug!("Failed to parse the response of revaultd: {}", e);
+                    corrupted = true;
+                    break;
+                }
+            }
+            consumed = stream.byte_offset();
+        }
+        if corrupted {
+            break;
+        }
+        buffer.drain(..consumed);
+    }
+    closed.store(true, Ordering::SeqCst);
+    pending.lock().unwrap().clear();
+}
+
+/// Removes the request from the pending ones once the response is received
+/// or the request is cancelled.
+#[cfg(not(windows))]
+struct PendingRequest {
+    id: u64,
+    pending: Pending,
+}
+
+#[cfg(not(windows))]
+impl PendingRequest {
+    fn new(id: u64, sender: oneshot::Sender<Response<Value>>, pending: Pending) -> Self {
+        pending.lock().unwrap().insert(id, sender);
+        Self { id, pending }
+    }
+}
+
+#[cfg(not(windows))]
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Marks the connection as closed if dropped while armed.
+#[cfg(not(windows))]
+struct CloseOnDrop<'a> {
+    closed: &'a AtomicBool,
+    armed: bool,
+}
+
+#[cfg(not(windows))]
+impl<'a> Drop for CloseOnDrop<'a> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.closed.store(true, Ordering::SeqCst);
+        }
     }
 }
 
@@ -121,7 +358,7 @@ pub struct Request<'f, T: Serialize> {
     /// Parameters to the RPC call
     pub params: Option<T>,
     /// Identifier for this Request, which should appear in the response
-    pub id: u32,
+    pub id: u64,
     /// jsonrpc field, MUST be "2.0"
     pub jsonrpc: &'f str,
 }
@@ -134,7 +371,7 @@ pub struct Response<T> {
     /// An error if there is one, or null
     pub error: Option<RpcError>,
     /// Identifier for this Request, which should match that of the request
-    pub id: u32,
+    pub id: u64,
     /// jsonrpc field, MUST be "2.0"
     pub jsonrpc: Option<String>,
 }
@@ -184,6 +421,8 @@ pub enum Error {
     NonceMismatch,
     /// Response to a request had a jsonrpc field other than "2.0"
     VersionMismatch,
+    /// No response was received before the timeout
+    Timeout,
 }
 
 impl From<serde_json::Error> for Error {
@@ -213,6 +452,7 @@ impl fmt::Display for Error {
             Error::NoErrorOrResult => write!(f, "Malformed RPC response"),
             Error::NonceMismatch => write!(f, "Nonce of response did not match nonce of request"),
             Error::VersionMismatch => write!(f, "`jsonrpc` field set to non-\"2.0\""),
+            Error::Timeout => write!(f, "Request timed out"),
         }
     }
 }
@@ -237,6 +477,10 @@ impl From<Error> for super::RevaultDError {
             Error::VersionMismatch => {
                 super::RevaultDError::Transport(None, format!("transport: {}", e))
             }
+            Error::Timeout => super::RevaultDError::Transport(
+                Some(std::io::ErrorKind::TimedOut),
+                format!("transport: {}", e),
+            ),
             Error::NoErrorOrResult => super::RevaultDError::NoAnswer,
             Error::Rpc(e) => super::RevaultDError::Rpc(e.code, e.message),
         }
@@ -253,3 +497,147 @@ pub struct RpcError {
     /// Additional data specific to the error
     pub data: Option<serde_json::Value>,
 }
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// Single connection of the test server, it answers the requests with
+    /// their method as result.
+    struct Server {
+        stream: UnixStream,
+        buffer: Vec<u8>,
+    }
+
+    impl Server {
+        async fn accept(listener: &UnixListener) -> Self {
+            let (stream, _) = listener.accept().await.unwrap();
+            Self {
+                stream,
+                buffer: Vec::new(),
+            }
+        }
+
+        /// Returns the id and the method of the next request.
+        async fn request(&mut self) -> (u64, String) {
+            loop {
+                let mut stream = Deserializer::from_slice(&self.buffer).into_iter::<Value>();
+                if let Some(Ok(request)) = stream.next() {
+                    let consumed = stream.byte_offset();
+                    self.buffer.drain(..consumed);
+                    return (
+                        request["id"].as_u64().unwrap(),
+                        request["method"].as_str().unwrap().to_string(),
+                    );
+                }
+                let mut chunk = [0; 1024];
+                let n = self.stream.read(&mut chunk).await.unwrap();
+                assert!(n > 0, "client closed the connection");
+                self.buffer.extend_from_slice(&chunk[..n]);
+            }
+        }
+
+        async fn respond(&mut self, id: u64, method: &str) {
+            let response = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": method});
+            self.stream
+                .write_all(response.to_string().as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+
+    fn listen(name: &str) -> (JsonRPCClient, UnixListener) {
+        let sockpath = std::env::temp_dir().join(format!(
+            "revault_gui_jsonrpc_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_file(&sockpath);
+        let listener = UnixListener::bind(&sockpath).unwrap();
+        let mut client = JsonRPCClient::new(&sockpath);
+        client.set_timeout(Some(Duration::from_secs(1)));
+        (client, listener)
+    }
+
+    async fn call(client: &JsonRPCClient, method: &str) -> Result<String, Error> {
+        super::super::Client::request(client, method, None::<()>).await
+    }
+
+    fn pending(client: &JsonRPCClient) -> usize {
+        client
+            .connection
+            .try_lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |conn| conn.pending.lock().unwrap().len())
+    }
+
+    #[tokio::test]
+    async fn test_jsonrpc_responses_out_of_order() {
+        let (client, listener) = listen("out_of_order");
+        let server = tokio::spawn(async move {
+            let mut server = Server::accept(&listener).await;
+            let first = server.request().await;
+            let second = server.request().await;
+            server.respond(second.0, &second.1).await;
+            server.respond(first.0, &first.1).await;
+            server
+        });
+
+        let (a, b) = tokio::join!(call(&client, "a"), call(&client, "b"));
+        assert_eq!(a.unwrap(), "a");
+        assert_eq!(b.unwrap(), "b");
+        assert_eq!(pending(&client), 0);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jsonrpc_timeout_keeps_connection() {
+        let (client, listener) = listen("timeout");
+        // The server accepts a single connection, a new one would never be answered.
+        let server = tokio::spawn(async move {
+            let mut server = Server::accept(&listener).await;
+            let _slow = server.request().await;
+            let (id, method) = server.request().await;
+            server.respond(id, &method).await;
+            server
+        });
+
+        let res = client
+            .send_request_with_timeout::<(), String>("slow", None, Some(Duration::from_millis(50)))
+            .await;
+        assert!(matches!(res, Err(Error::Timeout)));
+        assert_eq!(pending(&client), 0);
+
+        assert_eq!(call(&client, "fast").await.unwrap(), "fast");
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jsonrpc_dropped_request() {
+        let (client, listener) = listen("dropped");
+        let (received, dropped_received) = oneshot::channel();
+        let server = tokio::spawn(async move {
+            let mut server = Server::accept(&listener).await;
+            let dropped = server.request().await;
+            received.send(()).unwrap();
+            let next = server.request().await;
+            // The response of the dropped request arrives late, before the next one.
+            server.respond(dropped.0, &dropped.1).await;
+            server.respond(next.0, &next.1).await;
+            server
+        });
+
+        // The request is dropped once the server received it.
+        tokio::select! {
+            _ = call(&client, "dropped") => panic!("the request was answered"),
+            _ = dropped_received => {}
+        }
+        assert_eq!(pending(&client), 0);
+
+        assert_eq!(call(&client, "next").await.unwrap(), "next");
+        assert_eq!(pending(&client), 0);
+        server.await.unwrap();
+    }
+}
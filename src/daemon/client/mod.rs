@@ -1,7 +1,9 @@
 use serde_json::json;
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use bitcoin::{base64, consensus, util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
 use log::{error, info};
 use serde::de::DeserializeOwned;
@@ -12,12 +14,27 @@ pub mod jsonrpc;
 
 use super::{model::*, Daemon, RevaultDError};
 
+/// Timeout of the requests relayed by revaultd to the coordinator, the
+/// cosigning servers or the watchtowers.
+pub const SERVERS_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[async_trait]
 pub trait Client {
     type Error: Into<RevaultDError> + Debug;
-    fn request<S: Serialize + Debug, D: DeserializeOwned + Debug>(
+    /// Sends a request, with the default timeout of the client.
+    async fn request<S: Serialize + Debug + Send, D: DeserializeOwned + Debug + Send>(
+        &self,
+        method: &str,
+        params: Option<S>,
+    ) -> Result<D, Self::Error>;
+
+    /// Sends a request, failing if no response is received before the
+    /// timeout. The request never times out if the timeout is None.
+    async fn request_with_timeout<S: Serialize + Debug + Send, D: DeserializeOwned + Debug + Send>(
         &self,
         method: &str,
         params: Option<S>,
+        timeout: Option<Duration>,
     ) -> Result<D, Self::Error>;
 }
 
@@ -32,36 +49,54 @@ impl<C: Client> RevaultD<C> {
     }
 
     /// Generic call function for RPC calls.
-    fn call<T: Serialize + Debug, U: DeserializeOwned + Debug>(
+    async fn call<T: Serialize + Debug + Send, U: DeserializeOwned + Debug + Send>(
         &self,
         method: &str,
         input: Option<T>,
     ) -> Result<U, RevaultDError> {
         info!("{}", method);
-        self.client.request(method, input).map_err(|e| {
+        self.client.request(method, input).await.map_err(|e| {
             error!("method {} failed: {:?}", method, e);
             e.into()
         })
     }
+
+    /// Call function for the RPC calls waiting for the servers.
+    async fn call_servers<T: Serialize + Debug + Send, U: DeserializeOwned + Debug + Send>(
+        &self,
+        method: &str,
+        input: Option<T>,
+    ) -> Result<U, RevaultDError> {
+        info!("{}", method);
+        self.client
+            .request_with_timeout(method, input, Some(SERVERS_TIMEOUT))
+            .await
+            .map_err(|e| {
+                error!("method {} failed: {:?}", method, e);
+                e.into()
+            })
+    }
 }
 
-impl<C: Client + Debug> Daemon for RevaultD<C> {
+#[async_trait]
+impl<C: Client + Debug + Send + Sync> Daemon for RevaultD<C> {
     fn is_external(&self) -> bool {
         true
     }
 
     /// get a new deposit address.
-    fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError> {
-        let deposit_address: DepositAddress =
-            self.call("getdepositaddress", Option::<Request>::None)?;
+    async fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError> {
+        let deposit_address: DepositAddress = self
+            .call("getdepositaddress", Option::<Request>::None)
+            .await?;
         Ok(deposit_address.address)
     }
 
-    fn get_info(&self) -> Result<GetInfoResult, RevaultDError> {
-        self.call("getinfo", Option::<Request>::None)
+    async fn get_info(&self) -> Result<GetInfoResult, RevaultDError> {
+        self.call("getinfo", Option::<Request>::None).await
     }
 
-    fn list_vaults(
+    async fn list_vaults(
         &self,
         statuses: Option<&[VaultStatus]>,
         outpoints: Option<&[OutPoint]>,
@@ -76,42 +111,47 @@ impl<C: Client + Debug> Daemon for RevaultD<C> {
             let outpoints: Vec<String> = outpoints.iter().map(|o| o.to_string()).collect();
             args.push(json!(outpoints));
         }
-        let response: ListVaultsResponse = self.call("listvaults", Some(args))?;
+        let response: ListVaultsResponse = self.call("listvaults", Some(args)).await?;
         Ok(response.vaults)
     }
 
-    fn list_onchain_transactions(
+    async fn list_onchain_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultTransactions>, RevaultDError> {
         let outpoints: Vec<String> = outpoints.iter().map(|o| o.to_string()).collect();
-        let response: ListOnchainTransactionsResponse = self.call(
-            "listonchaintransactions",
-            Some(vec![ListTransactionsRequest(outpoints)]),
-        )?;
+        let response: ListOnchainTransactionsResponse = self
+            .call(
+                "listonchaintransactions",
+                Some(vec![ListTransactionsRequest(outpoints)]),
+            )
+            .await?;
         Ok(response.onchain_transactions)
     }
 
-    fn list_presigned_transactions(
+    async fn list_presigned_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultPresignedTransactions>, RevaultDError> {
         let outpoints: Vec<String> = outpoints.iter().map(|o| o.to_string()).collect();
-        let response: ListPresignedTransactionsResponse = self.call(
-            "listpresignedtransactions",
-            Some(vec![ListTransactionsRequest(outpoints)]),
-        )?;
+        let response: ListPresignedTransactionsResponse = self
+            .call(
+                "listpresignedtransactions",
+                Some(vec![ListTransactionsRequest(outpoints)]),
+            )
+            .await?;
         Ok(response.presigned_transactions)
     }
 
-    fn get_revocation_txs(
+    async fn get_revocation_txs(
         &self,
         outpoint: &OutPoint,
     ) -> Result<RevocationTransactions, RevaultDError> {
         self.call("getrevocationtxs", Some(vec![outpoint.to_string()]))
+            .await
     }
 
-    fn set_revocation_txs(
+    async fn set_revocation_txs(
         &self,
         outpoint: &OutPoint,
         emergency_tx: &Psbt,
@@ -124,101 +164,119 @@ impl<C: Client + Debug> Daemon for RevaultD<C> {
             .iter()
             .map(|tx| base64::encode(&consensus::serialize(tx)))
             .collect();
-        let _res: serde_json::value::Value = self.call(
-            "revocationtxs",
-            Some(vec![
-                json!(outpoint.to_string()),
-                json!(cancel),
-                json!(emergency),
-                json!(emergency_unvault),
-            ]),
-        )?;
+        let _res: serde_json::value::Value = self
+            .call_servers(
+                "revocationtxs",
+                Some(vec![
+                    json!(outpoint.to_string()),
+                    json!(cancel),
+                    json!(emergency),
+                    json!(emergency_unvault),
+                ]),
+            )
+            .await?;
         Ok(())
     }
 
-    fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError> {
-        let resp: UnvaultTransaction =
-            self.call("getunvaulttx", Some(vec![outpoint.to_string()]))?;
+    async fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError> {
+        let resp: UnvaultTransaction = self
+            .call("getunvaulttx", Some(vec![outpoint.to_string()]))
+            .await?;
         Ok(resp.unvault_tx)
     }
 
-    fn set_unvault_tx(&self, outpoint: &OutPoint, unvault_tx: &Psbt) -> Result<(), RevaultDError> {
+    async fn set_unvault_tx(
+        &self,
+        outpoint: &OutPoint,
+        unvault_tx: &Psbt,
+    ) -> Result<(), RevaultDError> {
         let unvault_tx = base64::encode(&consensus::serialize(unvault_tx));
-        let _res: serde_json::value::Value =
-            self.call("unvaulttx", Some(vec![outpoint.to_string(), unvault_tx]))?;
+        let _res: serde_json::value::Value = self
+            .call_servers("unvaulttx", Some(vec![outpoint.to_string(), unvault_tx]))
+            .await?;
         Ok(())
     }
 
-    fn get_spend_tx(
+    async fn get_spend_tx(
         &self,
         inputs: &[OutPoint],
         outputs: &BTreeMap<bitcoin::Address, u64>,
         feerate: u64,
     ) -> Result<SpendTx, RevaultDError> {
-        let res: SpendTransactionResponse = self.call(
-            "getspendtx",
-            Some(vec![json!(inputs), json!(outputs), json!(feerate)]),
-        )?;
+        let res: SpendTransactionResponse = self
+            .call(
+                "getspendtx",
+                Some(vec![json!(inputs), json!(outputs), json!(feerate)]),
+            )
+            .await?;
         Ok(res.spend_tx)
     }
 
-    fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError> {
+    async fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError> {
         let spend_tx = base64::encode(&consensus::serialize(psbt));
-        let _res: serde_json::value::Value = self.call("updatespendtx", Some(vec![spend_tx]))?;
+        let _res: serde_json::value::Value = self
+            .call_servers("updatespendtx", Some(vec![spend_tx]))
+            .await?;
         Ok(())
     }
 
-    fn list_spend_txs(
+    async fn list_spend_txs(
         &self,
         statuses: Option<&[SpendTxStatus]>,
     ) -> Result<Vec<SpendTx>, RevaultDError> {
         let resp: ListSpendTransactionsResponse =
-            self.call("listspendtxs", Some(vec![statuses]))?;
+            self.call("listspendtxs", Some(vec![statuses])).await?;
         Ok(resp.spend_txs)
     }
 
-    fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError> {
-        let _res: serde_json::value::Value = self.call("delspendtx", Some(vec![txid]))?;
+    async fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError> {
+        let _res: serde_json::value::Value = self.call("delspendtx", Some(vec![txid])).await?;
         Ok(())
     }
 
-    fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError> {
-        let _res: serde_json::value::Value =
-            self.call("setspendtx", Some(vec![json!(txid), json!(priority)]))?;
+    async fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError> {
+        let _res: serde_json::value::Value = self
+            .call_servers("setspendtx", Some(vec![json!(txid), json!(priority)]))
+            .await?;
         Ok(())
     }
 
-    fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError> {
-        let _res: serde_json::value::Value =
-            self.call("revault", Some(vec![outpoint.to_string()]))?;
+    async fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError> {
+        let _res: serde_json::value::Value = self
+            .call("revault", Some(vec![outpoint.to_string()]))
+            .await?;
         Ok(())
     }
 
-    fn emergency(&self) -> Result<(), RevaultDError> {
-        let _res: serde_json::value::Value = self.call("emergency", Option::<Request>::None)?;
+    async fn emergency(&self) -> Result<(), RevaultDError> {
+        let _res: serde_json::value::Value =
+            self.call("emergency", Option::<Request>::None).await?;
         Ok(())
     }
 
+    /// The external daemon is not owned by the GUI and keeps running.
     fn stop(&mut self) -> Result<(), RevaultDError> {
-        let _res: serde_json::value::Value = self.call("stop", Option::<Request>::None)?;
         Ok(())
     }
 
-    fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError> {
-        self.call("getserverstatus", Option::<Request>::None)
+    async fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError> {
+        self.call_servers("getserverstatus", Option::<Request>::None)
+            .await
     }
 
-    fn get_history(
+    async fn get_history(
         &self,
         kind: &[HistoryEventKind],
         start: u32,
         end: u32,
         limit: u64,
     ) -> Result<Vec<HistoryEvent>, RevaultDError> {
-        let resp: GetHistoryResponse = self.call(
-            "gethistory",
-            Some(vec![json!(kind), json!(start), json!(end), json!(limit)]),
-        )?;
+        let resp: GetHistoryResponse = self
+            .call(
+                "gethistory",
+                Some(vec![json!(kind), json!(start), json!(end), json!(limit)]),
+            )
+            .await?;
         Ok(resp.events)
     }
 }
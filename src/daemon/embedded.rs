@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
 use bitcoin::{consensus::encode, util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
 
 use super::{model::*, Daemon, RevaultDError};
@@ -21,20 +22,38 @@ impl From<CommandError> for RevaultDError {
 }
 
 pub struct EmbeddedDaemon {
-    handle: Option<Mutex<DaemonHandle>>,
+    handle: Arc<Mutex<Option<DaemonHandle>>>,
 }
 
 impl EmbeddedDaemon {
     pub fn new() -> Self {
-        Self { handle: None }
+        Self {
+            handle: Arc::new(Mutex::new(None)),
+        }
     }
 
     pub fn start(&mut self, config: Config) -> Result<(), RevaultDError> {
         let handle =
             DaemonHandle::start(config).map_err(|e| RevaultDError::Start(e.to_string()))?;
-        self.handle = Some(Mutex::new(handle));
+        *self.handle.lock().unwrap() = Some(handle);
         Ok(())
     }
+
+    /// Runs the command on the blocking thread pool, the daemon control
+    /// waits for the daemon threads to answer.
+    async fn command<T, F>(&self, f: F) -> Result<T, RevaultDError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DaemonHandle) -> Result<T, RevaultDError> + Send + 'static,
+    {
+        let handle = self.handle.clone();
+        tokio::task::spawn_blocking(move || {
+            let handle = handle.lock().unwrap();
+            f(handle.as_ref().ok_or(RevaultDError::NoAnswer)?)
+        })
+        .await
+        .map_err(|e| RevaultDError::Unexpected(e.to_string()))?
+    }
 }
 
 impl std::fmt::Debug for EmbeddedDaemon {
@@ -43,110 +62,98 @@ impl std::fmt::Debug for EmbeddedDaemon {
     }
 }
 
+#[async_trait]
 impl Daemon for EmbeddedDaemon {
     fn is_external(&self) -> bool {
         false
     }
 
     fn load_config(&mut self, cfg: Config) -> Result<(), RevaultDError> {
-        if self.handle.is_none() {
+        let mut handle = self.handle.lock().unwrap();
+        if handle.is_none() {
             return Ok(());
         }
 
         let next = DaemonHandle::start(cfg).map_err(|e| RevaultDError::Start(e.to_string()))?;
-        self.handle.take().unwrap().into_inner().unwrap().shutdown();
-        self.handle = Some(Mutex::new(next));
+        handle.take().unwrap().shutdown();
+        *handle = Some(next);
         Ok(())
     }
 
     fn stop(&mut self) -> Result<(), RevaultDError> {
-        if let Some(h) = self.handle.take() {
-            let handle = h.into_inner().unwrap();
+        if let Some(handle) = self.handle.lock().unwrap().take() {
             handle.shutdown();
         }
         Ok(())
     }
 
-    fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError> {
-        Ok(self
-            .handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_deposit_address())
+    async fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError> {
+        self.command(|handle| Ok(handle.control.get_deposit_address()))
+            .await
     }
 
-    fn get_info(&self) -> Result<GetInfoResult, RevaultDError> {
-        Ok(self
-            .handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_info())
+    async fn get_info(&self) -> Result<GetInfoResult, RevaultDError> {
+        self.command(|handle| Ok(handle.control.get_info())).await
     }
 
-    fn list_vaults(
+    async fn list_vaults(
         &self,
         statuses: Option<&[VaultStatus]>,
         outpoints: Option<&[OutPoint]>,
     ) -> Result<Vec<Vault>, RevaultDError> {
-        Ok(self
-            .handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .list_vaults(statuses, outpoints))
+        let statuses = statuses.map(|s| s.to_vec());
+        let outpoints = outpoints.map(|o| o.to_vec());
+        self.command(move |handle| {
+            Ok(handle
+                .control
+                .list_vaults(statuses.as_deref(), outpoints.as_deref()))
+        })
+        .await
     }
 
-    fn list_onchain_transactions(
+    async fn list_onchain_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultTransactions>, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .list_onchain_txs(outpoints)
-            .map_err(|e| e.into())
+        let outpoints = outpoints.to_vec();
+        self.command(move |handle| {
+            handle
+                .control
+                .list_onchain_txs(&outpoints)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn list_presigned_transactions(
+    async fn list_presigned_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultPresignedTransactions>, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .list_presigned_txs(outpoints)
-            .map_err(|e| e.into())
+        let outpoints = outpoints.to_vec();
+        self.command(move |handle| {
+            handle
+                .control
+                .list_presigned_txs(&outpoints)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn get_revocation_txs(
+    async fn get_revocation_txs(
         &self,
         outpoint: &OutPoint,
     ) -> Result<RevocationTransactions, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_revocation_txs(*outpoint)
-            .map_err(|e| e.into())
+        let outpoint = *outpoint;
+        self.command(move |handle| {
+            handle
+                .control
+                .get_revocation_txs(outpoint)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn set_revocation_txs(
+    async fn set_revocation_txs(
         &self,
         outpoint: &OutPoint,
         emergency_tx: &Psbt,
@@ -165,158 +172,135 @@ impl Daemon for EmbeddedDaemon {
         let emergency_unvault_tx =
             UnvaultEmergencyTransaction::from_raw_psbt(&encode::serialize(emergency_unvault_tx))
                 .unwrap();
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .set_revocation_txs(
-                *outpoint,
-                RevocationTransactions {
-                    cancel_txs,
-                    emergency_tx,
-                    emergency_unvault_tx,
-                },
-            )
-            .map_err(|e| e.into())
+        let outpoint = *outpoint;
+        self.command(move |handle| {
+            handle
+                .control
+                .set_revocation_txs(
+                    outpoint,
+                    RevocationTransactions {
+                        cancel_txs,
+                        emergency_tx,
+                        emergency_unvault_tx,
+                    },
+                )
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_unvault_tx(*outpoint)
-            .map(|tx| tx.into_psbt())
-            .map_err(|e| e.into())
+    async fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError> {
+        let outpoint = *outpoint;
+        self.command(move |handle| {
+            handle
+                .control
+                .get_unvault_tx(outpoint)
+                .map(|tx| tx.into_psbt())
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn set_unvault_tx(&self, outpoint: &OutPoint, unvault_tx: &Psbt) -> Result<(), RevaultDError> {
+    async fn set_unvault_tx(
+        &self,
+        outpoint: &OutPoint,
+        unvault_tx: &Psbt,
+    ) -> Result<(), RevaultDError> {
         let unvault = UnvaultTransaction::from_raw_psbt(&encode::serialize(unvault_tx)).unwrap();
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .set_unvault_tx(*outpoint, unvault)
-            .map_err(|e| e.into())
+        let outpoint = *outpoint;
+        self.command(move |handle| {
+            handle
+                .control
+                .set_unvault_tx(outpoint, unvault)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn get_spend_tx(
+    async fn get_spend_tx(
         &self,
         inputs: &[OutPoint],
         outputs: &BTreeMap<bitcoin::Address, u64>,
         feerate: u64,
     ) -> Result<SpendTx, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_spend_tx(inputs, outputs, feerate)
-            .map_err(|e| e.into())
+        let inputs = inputs.to_vec();
+        let outputs = outputs.clone();
+        self.command(move |handle| {
+            handle
+                .control
+                .get_spend_tx(&inputs, &outputs, feerate)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError> {
+    async fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError> {
         let spend = SpendTransaction::from_raw_psbt(&encode::serialize(psbt)).unwrap();
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .update_spend_tx(spend)
-            .map_err(|e| e.into())
+        self.command(move |handle| handle.control.update_spend_tx(spend).map_err(|e| e.into()))
+            .await
     }
 
-    fn list_spend_txs(
+    async fn list_spend_txs(
         &self,
         statuses: Option<&[SpendTxStatus]>,
     ) -> Result<Vec<SpendTx>, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .list_spend_txs(statuses)
-            .map_err(|e| e.into())
+        let statuses = statuses.map(|s| s.to_vec());
+        self.command(move |handle| {
+            handle
+                .control
+                .list_spend_txs(statuses.as_deref())
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .del_spend_tx(txid)
-            .map_err(|e| e.into())
+    async fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError> {
+        let txid = *txid;
+        self.command(move |handle| handle.control.del_spend_tx(&txid).map_err(|e| e.into()))
+            .await
     }
 
-    fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .set_spend_tx(txid, priority)
-            .map_err(|e| e.into())
+    async fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError> {
+        let txid = *txid;
+        self.command(move |handle| {
+            handle
+                .control
+                .set_spend_tx(&txid, priority)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 
-    fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .revault(*outpoint)
-            .map_err(|e| e.into())
+    async fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError> {
+        let outpoint = *outpoint;
+        self.command(move |handle| handle.control.revault(outpoint).map_err(|e| e.into()))
+            .await
     }
 
-    fn emergency(&self) -> Result<(), RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .emergency()
-            .map_err(|e| e.into())
+    async fn emergency(&self) -> Result<(), RevaultDError> {
+        self.command(|handle| handle.control.emergency().map_err(|e| e.into()))
+            .await
     }
 
-    fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError> {
-        Ok(self
-            .handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_servers_statuses())
+    async fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError> {
+        self.command(|handle| Ok(handle.control.get_servers_statuses()))
+            .await
     }
 
-    fn get_history(
+    async fn get_history(
         &self,
         kind: &[HistoryEventKind],
         start: u32,
         end: u32,
         limit: u64,
     ) -> Result<Vec<HistoryEvent>, RevaultDError> {
-        self.handle
-            .as_ref()
-            .ok_or(RevaultDError::NoAnswer)?
-            .lock()
-            .unwrap()
-            .control
-            .get_history(start, end, limit, kind)
-            .map_err(|e| e.into())
+        let kind = kind.to_vec();
+        self.command(move |handle| {
+            handle
+                .control
+                .get_history(start, end, limit, &kind)
+                .map_err(|e| e.into())
+        })
+        .await
     }
 }
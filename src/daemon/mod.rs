@@ -1,3 +1,4 @@
+pub mod bitcoind;
 pub mod client;
 pub mod embedded;
 pub mod model;
@@ -6,6 +7,7 @@ use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::io::ErrorKind;
 
+use async_trait::async_trait;
 use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
 use revaultd::config::Config;
 
@@ -37,6 +39,13 @@ impl std::fmt::Display for RevaultDError {
     }
 }
 
+impl std::error::Error for RevaultDError {}
+
+/// Daemon is the interface of revaultd, either embedded in the GUI process
+/// or reached by its JSON-RPC API.
+/// The lifecycle methods are synchronous, the commands are asynchronous so that
+/// the screens are not blocked while waiting for their results.
+#[async_trait]
 pub trait Daemon: Debug {
     fn is_external(&self) -> bool;
 
@@ -46,32 +55,32 @@ pub trait Daemon: Debug {
 
     fn stop(&mut self) -> Result<(), RevaultDError>;
 
-    fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError>;
+    async fn get_deposit_address(&self) -> Result<bitcoin::Address, RevaultDError>;
 
-    fn get_info(&self) -> Result<GetInfoResult, RevaultDError>;
+    async fn get_info(&self) -> Result<GetInfoResult, RevaultDError>;
 
-    fn list_vaults(
+    async fn list_vaults(
         &self,
         statuses: Option<&[VaultStatus]>,
         outpoints: Option<&[OutPoint]>,
     ) -> Result<Vec<Vault>, RevaultDError>;
 
-    fn list_onchain_transactions(
+    async fn list_onchain_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultTransactions>, RevaultDError>;
 
-    fn list_presigned_transactions(
+    async fn list_presigned_transactions(
         &self,
         outpoints: &[OutPoint],
     ) -> Result<Vec<VaultPresignedTransactions>, RevaultDError>;
 
-    fn get_revocation_txs(
+    async fn get_revocation_txs(
         &self,
         outpoint: &OutPoint,
     ) -> Result<RevocationTransactions, RevaultDError>;
 
-    fn set_revocation_txs(
+    async fn set_revocation_txs(
         &self,
         outpoint: &OutPoint,
         emergency_tx: &Psbt,
@@ -79,35 +88,39 @@ pub trait Daemon: Debug {
         cancel_tx: &[Psbt; 5],
     ) -> Result<(), RevaultDError>;
 
-    fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError>;
+    async fn get_unvault_tx(&self, outpoint: &OutPoint) -> Result<Psbt, RevaultDError>;
 
-    fn set_unvault_tx(&self, outpoint: &OutPoint, unvault_tx: &Psbt) -> Result<(), RevaultDError>;
+    async fn set_unvault_tx(
+        &self,
+        outpoint: &OutPoint,
+        unvault_tx: &Psbt,
+    ) -> Result<(), RevaultDError>;
 
-    fn get_spend_tx(
+    async fn get_spend_tx(
         &self,
         inputs: &[OutPoint],
         outputs: &BTreeMap<bitcoin::Address, u64>,
         feerate: u64,
     ) -> Result<SpendTx, RevaultDError>;
 
-    fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError>;
+    async fn update_spend_tx(&self, psbt: &Psbt) -> Result<(), RevaultDError>;
 
-    fn list_spend_txs(
+    async fn list_spend_txs(
         &self,
         statuses: Option<&[SpendTxStatus]>,
     ) -> Result<Vec<SpendTx>, RevaultDError>;
 
-    fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError>;
+    async fn delete_spend_tx(&self, txid: &Txid) -> Result<(), RevaultDError>;
 
-    fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError>;
+    async fn broadcast_spend_tx(&self, txid: &Txid, priority: bool) -> Result<(), RevaultDError>;
 
-    fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError>;
+    async fn revault(&self, outpoint: &OutPoint) -> Result<(), RevaultDError>;
 
-    fn emergency(&self) -> Result<(), RevaultDError>;
+    async fn emergency(&self) -> Result<(), RevaultDError>;
 
-    fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError>;
+    async fn get_server_status(&self) -> Result<ServersStatuses, RevaultDError>;
 
-    fn get_history(
+    async fn get_history(
         &self,
         kind: &[HistoryEventKind],
         start: u32,
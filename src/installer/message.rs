@@ -1,3 +1,4 @@
+use bitcoin::util::bip32::{ExtendedPubKey, Fingerprint};
 use std::path::PathBuf;
 
 use super::Error;
@@ -51,6 +52,7 @@ pub enum DefineStakeholderXpubs {
     OurXpubEdited(String),
     StakeholderXpub(usize, ParticipantXpub),
     AddXpub,
+    ImportOurXpub(ImportXpub),
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +64,13 @@ pub enum DefineManagerXpubs {
     CosignersEnabled(bool),
     CosignerKey(usize, String),
     AddXpub,
+    ImportOurXpub(ImportXpub),
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportXpub {
+    Import,
+    Imported(Result<(Fingerprint, ExtendedPubKey), Error>),
 }
 
 #[derive(Debug, Clone)]
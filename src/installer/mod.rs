@@ -6,11 +6,20 @@ mod view;
 use iced::{Command, Element, Subscription};
 use iced_native::{window, Event};
 
-use bitcoin::hashes::hex::FromHex;
+use bitcoin::{
+    hashes::hex::FromHex,
+    util::bip32::{ExtendedPubKey, Fingerprint},
+};
 use std::io::Write;
 use std::path::PathBuf;
 
-use crate::{app::config as gui_config, installer::config::Config as DaemonConfig, revault::Role};
+use revault_hwi::enumerate::enumerate;
+
+use crate::{
+    app::config as gui_config,
+    installer::config::Config as DaemonConfig,
+    revault::{revault_derivation_path, Role},
+};
 
 pub use message::Message;
 use step::{
@@ -26,6 +35,8 @@ pub struct Installer {
     /// Context is data passed through each step.
     context: Context,
     config: DaemonConfig,
+    /// Path to the hwi executable, written to the GUI configuration.
+    hwi_path: Option<PathBuf>,
 }
 
 impl Installer {
@@ -94,6 +105,7 @@ impl Installer {
     pub fn new(
         destination_path: PathBuf,
         network: bitcoin::Network,
+        hwi_path: Option<PathBuf>,
     ) -> (Installer, Command<Message>) {
         let mut config = DaemonConfig::new();
         config.data_dir = Some(destination_path);
@@ -105,6 +117,7 @@ impl Installer {
                 current: 0,
                 steps: vec![Welcome::new(network).into(), DefineRole::new().into()],
                 context: Context::new(network),
+                hwi_path,
             },
             Command::none(),
         )
@@ -165,10 +178,42 @@ impl Installer {
             Message::Install => {
                 self.current_step().update(message);
                 return Command::perform(
-                    install(self.context.clone(), self.config.clone()),
+                    install(
+                        self.context.clone(),
+                        self.config.clone(),
+                        self.hwi_path.clone(),
+                    ),
                     Message::Installed,
                 );
             }
+            Message::DefineStakeholderXpubs(message::DefineStakeholderXpubs::ImportOurXpub(
+                message::ImportXpub::Import,
+            )) => {
+                self.current_step().update(message);
+                return Command::perform(
+                    import_xpub(self.context.network, self.hwi_path.clone()),
+                    |res| {
+                        Message::DefineStakeholderXpubs(
+                            message::DefineStakeholderXpubs::ImportOurXpub(
+                                message::ImportXpub::Imported(res),
+                            ),
+                        )
+                    },
+                );
+            }
+            Message::DefineManagerXpubs(message::DefineManagerXpubs::ImportOurXpub(
+                message::ImportXpub::Import,
+            )) => {
+                self.current_step().update(message);
+                return Command::perform(
+                    import_xpub(self.context.network, self.hwi_path.clone()),
+                    |res| {
+                        Message::DefineManagerXpubs(message::DefineManagerXpubs::ImportOurXpub(
+                            message::ImportXpub::Imported(res),
+                        ))
+                    },
+                );
+            }
             Message::Event(Event::Window(window::Event::CloseRequested)) => {
                 self.stop();
                 return Command::none();
@@ -193,7 +238,78 @@ fn append_network_suffix(name: &str, network: &bitcoin::Network) -> String {
     }
 }
 
-pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Error> {
+/// Retrieves the xpub of the connected hardware wallet at the standard Revault derivation path.
+/// The device must be the only one connected, its xpub must belong to the installer network
+/// and the fingerprint it gives must match the one found during enumeration.
+/// The devices reached with hwi are only enumerated if its path is configured,
+/// as in the GUI.
+pub async fn import_xpub(
+    network: bitcoin::Network,
+    hwi_path: Option<PathBuf>,
+) -> Result<(Fingerprint, ExtendedPubKey), Error> {
+    let mut devices = enumerate(network, hwi_path.as_deref()).await;
+    if devices.is_empty() {
+        return Err(Error::HardwareWallet(
+            "No hardware wallet connected".to_string(),
+        ));
+    }
+    if devices.len() > 1 {
+        return Err(Error::HardwareWallet(
+            "More than one hardware wallet connected, please keep only the one to import from"
+                .to_string(),
+        ));
+    }
+
+    let (descriptor, mut device) = devices.remove(0);
+    let fingerprint = device
+        .get_fingerprint()
+        .await
+        .map_err(|e| Error::HardwareWallet(e.to_string()))?;
+    if let Some(expected) = descriptor.fingerprint {
+        if expected != fingerprint {
+            return Err(Error::HardwareWallet(format!(
+                "Device gave fingerprint {} but {} was expected",
+                fingerprint, expected
+            )));
+        }
+    }
+
+    let path = revault_derivation_path(network);
+    let xpub = device
+        .get_xpub(&path)
+        .await
+        .map_err(|e| Error::HardwareWallet(e.to_string()))?;
+
+    // xpubs of test networks all share the testnet version bytes.
+    let xpub_network = if network == bitcoin::Network::Bitcoin {
+        bitcoin::Network::Bitcoin
+    } else {
+        bitcoin::Network::Testnet
+    };
+    if xpub.network != xpub_network {
+        return Err(Error::HardwareWallet(format!(
+            "Device xpub is for {} but the installer is set for {}",
+            xpub.network, network
+        )));
+    }
+
+    if xpub.depth as usize != path.as_ref().len()
+        || path.as_ref().last() != Some(&xpub.child_number)
+    {
+        return Err(Error::HardwareWallet(format!(
+            "Device xpub was not derived at {}",
+            path
+        )));
+    }
+
+    Ok((fingerprint, xpub))
+}
+
+pub async fn install(
+    ctx: Context,
+    mut cfg: DaemonConfig,
+    hwi_path: Option<PathBuf>,
+) -> Result<PathBuf, Error> {
     let datadir_path = cfg.data_dir.clone().unwrap();
     std::fs::create_dir_all(&datadir_path)
         .map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
@@ -250,14 +366,15 @@ pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Err
 
     gui_config_file
         .write_all(
-            toml::to_string(&gui_config::Config::new(
-                revaultd_config_path.canonicalize().map_err(|e| {
+            toml::to_string(
+                &gui_config::Config::new(revaultd_config_path.canonicalize().map_err(|e| {
                     Error::Unexpected(format!(
                         "Failed to canonicalize revaultd config path: {}",
                         e
                     ))
-                })?,
-            ))
+                })?)
+                .with_hwi_path(hwi_path),
+            )
             .unwrap()
             .as_bytes(),
         )
@@ -271,6 +388,7 @@ pub enum Error {
     CannotCreateDatadir(String),
     CannotCreateFile(String),
     CannotWriteToFile(String),
+    HardwareWallet(String),
     Unexpected(String),
 }
 
@@ -280,6 +398,7 @@ impl std::fmt::Display for Error {
             Self::CannotCreateDatadir(e) => write!(f, "Failed to create datadir: {}", e),
             Self::CannotWriteToFile(e) => write!(f, "Failed to write to file: {}", e),
             Self::CannotCreateFile(e) => write!(f, "Failed to create file: {}", e),
+            Self::HardwareWallet(e) => write!(f, "Hardware wallet error: {}", e),
             Self::Unexpected(e) => write!(f, "Unexpected: {}", e),
         }
     }
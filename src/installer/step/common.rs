@@ -1,3 +1,4 @@
+use bitcoin::util::bip32::Fingerprint;
 use revault_ui::component::form;
 
 use crate::installer::{message, view};
@@ -52,6 +53,11 @@ impl ParticipantXpub {
 #[derive(Clone)]
 pub struct RequiredXpub {
     pub xpub: form::Value<String>,
+    /// True while the xpub is being retrieved from a hardware wallet.
+    pub importing: bool,
+    /// Fingerprint of the device the current xpub was imported from.
+    pub imported_from: Option<Fingerprint>,
+    pub import_error: Option<String>,
 
     xpub_input: text_input::State,
 }
@@ -60,6 +66,9 @@ impl RequiredXpub {
     pub fn new() -> Self {
         Self {
             xpub: form::Value::default(),
+            importing: false,
+            imported_from: None,
+            import_error: None,
             xpub_input: text_input::State::new(),
         }
     }
@@ -67,6 +76,27 @@ impl RequiredXpub {
     pub fn update(&mut self, msg: String) {
         self.xpub.value = msg;
         self.xpub.valid = true;
+        self.imported_from = None;
+    }
+
+    pub fn import(&mut self, msg: message::ImportXpub) {
+        match msg {
+            message::ImportXpub::Import => {
+                self.importing = true;
+                self.import_error = None;
+            }
+            message::ImportXpub::Imported(res) => {
+                self.importing = false;
+                match res {
+                    Ok((fingerprint, xpub)) => {
+                        self.xpub.value = xpub.to_string();
+                        self.xpub.valid = true;
+                        self.imported_from = Some(fingerprint);
+                    }
+                    Err(e) => self.import_error = Some(e.to_string()),
+                }
+            }
+        }
     }
 
     pub fn check_validity(&mut self, network: &bitcoin::Network) {
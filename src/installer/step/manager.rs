@@ -212,6 +212,9 @@ impl Step for DefineManagerXpubs {
                 message::DefineManagerXpubs::OurXpubEdited(xpub) => {
                     self.our_xpub.update(xpub);
                 }
+                message::DefineManagerXpubs::ImportOurXpub(msg) => {
+                    self.our_xpub.import(msg);
+                }
                 message::DefineManagerXpubs::ManagerXpub(i, message::ParticipantXpub::Delete) => {
                     self.other_xpubs.remove(i);
                 }
@@ -374,6 +377,9 @@ impl Step for DefineManagerXpubs {
             &self.managers_threshold,
             &self.spending_delay,
             &self.our_xpub.xpub,
+            self.our_xpub.importing,
+            self.our_xpub.imported_from.as_ref(),
+            self.our_xpub.import_error.as_ref(),
             self.other_xpubs
                 .iter_mut()
                 .enumerate()
@@ -571,6 +571,7 @@ mod tests {
     use super::{DefineCpfpDescriptor as DefineCpfpDescriptorStep, *};
     use crate::installer::config::Config;
     use crate::installer::message::{DefineCoordinator, DefineCpfpDescriptor, ParticipantXpub, *};
+    use bitcoin::util::bip32::{ExtendedPubKey, Fingerprint};
 
     const STAKEHOLDERS_XPUBS: [&str; 4] = [
         "xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg", 
@@ -724,6 +725,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn import_our_xpub() {
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        let mut stakeholder_step = stakeholder::DefineStakeholderXpubs::new();
+        load_stakeholders_xpubs(
+            &mut stakeholder_step,
+            vec![STAKEHOLDERS_XPUBS[0].to_string()],
+        );
+        stakeholder_step.update(Message::DefineStakeholderXpubs(
+            DefineStakeholderXpubs::ImportOurXpub(ImportXpub::Import),
+        ));
+        stakeholder_step.update(Message::DefineStakeholderXpubs(
+            DefineStakeholderXpubs::ImportOurXpub(ImportXpub::Imported(Ok((
+                Fingerprint::from_str("f5acc2fd").unwrap(),
+                ExtendedPubKey::from_str(STAKEHOLDERS_XPUBS[1]).unwrap(),
+            )))),
+        ));
+
+        let mut config = Config::new();
+        assert!(stakeholder_step.apply(&mut ctx, &mut config));
+        assert_eq!(
+            config.stakeholder_config.unwrap().xpub.to_string(),
+            STAKEHOLDERS_XPUBS[1]
+        );
+
+        // A failed import keeps the xpub filled by hand.
+        let mut manager_step = manager::DefineManagerXpubs::new();
+        manager_step.load_context(&Context {
+            network: bitcoin::Network::Bitcoin,
+            cosigners_enabled: false,
+            private_noise_key: "".to_string(),
+            number_managers: 1,
+            number_cosigners: 0,
+            stakeholders_xpubs: STAKEHOLDERS_XPUBS.iter().map(|x| x.to_string()).collect(),
+        });
+        manager_step.update(Message::DefineManagerXpubs(
+            DefineManagerXpubs::OurXpubEdited(MANAGERS_XPUBS[1].to_string()),
+        ));
+        manager_step.update(Message::DefineManagerXpubs(
+            DefineManagerXpubs::ImportOurXpub(ImportXpub::Imported(Err(
+                crate::installer::Error::HardwareWallet("No hardware wallet connected".to_string()),
+            ))),
+        ));
+        load_managers_xpubs(&mut manager_step, vec![MANAGERS_XPUBS[0].to_string()]);
+        assert!(manager_step.apply(&mut ctx, &mut Config::new()));
+    }
+
     #[test]
     fn define_unvault_descriptor() {
         let mut ctx = Context::new(bitcoin::Network::Bitcoin);
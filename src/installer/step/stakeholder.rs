@@ -46,6 +46,9 @@ impl Step for DefineStakeholderXpubs {
                 message::DefineStakeholderXpubs::OurXpubEdited(xpub) => {
                     self.our_xpub.update(xpub);
                 }
+                message::DefineStakeholderXpubs::ImportOurXpub(msg) => {
+                    self.our_xpub.import(msg);
+                }
                 message::DefineStakeholderXpubs::StakeholderXpub(
                     i,
                     message::ParticipantXpub::Delete,
@@ -120,6 +123,9 @@ impl Step for DefineStakeholderXpubs {
     fn view(&mut self) -> Element<Message> {
         return self.view.render(
             &self.our_xpub.xpub,
+            self.our_xpub.importing,
+            self.our_xpub.imported_from.as_ref(),
+            self.our_xpub.import_error.as_ref(),
             self.other_xpubs
                 .iter_mut()
                 .enumerate()
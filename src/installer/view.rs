@@ -3,6 +3,7 @@ use iced::{
     Container, Element, Length, Row,
 };
 
+use bitcoin::util::bip32::Fingerprint;
 use revault_ui::{
     component::{
         button, card, form, image::revault_colored_logo, scroll, text::Text,
@@ -184,6 +185,48 @@ pub fn required_xpub<'a>(
     .into()
 }
 
+/// Button to import our xpub from a hardware wallet with the outcome of the last import.
+fn import_xpub<'a>(
+    button_state: &'a mut Button,
+    importing: bool,
+    imported_from: Option<&Fingerprint>,
+    import_error: Option<&String>,
+    on_press: Message,
+) -> Element<'a, Message> {
+    let mut import_button = button::white_card_button(
+        button_state,
+        button::button_content(
+            Some(icon::connect_device_icon()),
+            if importing {
+                "Importing..."
+            } else {
+                "Import from device"
+            },
+        ),
+    );
+    if !importing {
+        import_button = import_button.on_press(on_press);
+    }
+    let mut row = Row::new()
+        .push(import_button)
+        .spacing(10)
+        .align_items(Alignment::Center);
+    if let Some(fingerprint) = imported_from {
+        row = row.push(
+            Text::new(&format!("Imported from device #{}", fingerprint))
+                .small()
+                .success(),
+        );
+    }
+    let mut col = Column::new().push(row).spacing(10);
+    if let Some(error) = import_error {
+        col = col.push(card::alert_warning(Container::new(
+            Text::new(error).small(),
+        )));
+    }
+    col.into()
+}
+
 pub fn cosigner_key<'a>(
     key: &form::Value<String>,
     key_input: &'a mut text_input::State,
@@ -258,6 +301,7 @@ impl DefinePrivateNoiseKey {
 
 pub struct DefineStakeholderXpubsAsStakeholder {
     our_xpub_input: text_input::State,
+    import_xpub_button: Button,
     previous_button: Button,
     save_button: Button,
     add_xpub_button: Button,
@@ -268,6 +312,7 @@ impl DefineStakeholderXpubsAsStakeholder {
     pub fn new() -> Self {
         Self {
             our_xpub_input: text_input::State::new(),
+            import_xpub_button: Button::new(),
             add_xpub_button: Button::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
@@ -277,6 +322,9 @@ impl DefineStakeholderXpubsAsStakeholder {
     pub fn render<'a>(
         &'a mut self,
         our_xpub: &form::Value<String>,
+        importing: bool,
+        imported_from: Option<&Fingerprint>,
+        import_error: Option<&String>,
         other_xpubs: Vec<Element<'a, Message>>,
         warning: Option<&String>,
     ) -> Element<'a, Message> {
@@ -301,6 +349,17 @@ impl DefineStakeholderXpubsAsStakeholder {
                         .padding(10)
                         .render(),
                     )
+                    .push(import_xpub(
+                        &mut self.import_xpub_button,
+                        importing,
+                        imported_from,
+                        import_error,
+                        Message::DefineStakeholderXpubs(
+                            message::DefineStakeholderXpubs::ImportOurXpub(
+                                message::ImportXpub::Import,
+                            ),
+                        ),
+                    ))
                     .spacing(10),
             )
             .push(
@@ -544,6 +603,7 @@ pub struct DefineManagerXpubsAsManager {
     spending_delay: SpendingDelay,
     add_xpub_button: Button,
     our_xpub_input: text_input::State,
+    import_xpub_button: Button,
     scroll: scrollable::State,
     previous_button: Button,
     save_button: Button,
@@ -553,6 +613,7 @@ impl DefineManagerXpubsAsManager {
     pub fn new() -> Self {
         Self {
             our_xpub_input: text_input::State::new(),
+            import_xpub_button: Button::new(),
             add_xpub_button: Button::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
@@ -568,6 +629,9 @@ impl DefineManagerXpubsAsManager {
         managers_threshold: &form::Value<usize>,
         spending_delay: &form::Value<u32>,
         our_xpub: &form::Value<String>,
+        importing: bool,
+        imported_from: Option<&Fingerprint>,
+        import_error: Option<&String>,
         other_xpubs: Vec<Element<'a, Message>>,
         cosigners: Vec<Element<'a, Message>>,
         cosigners_enabled: bool,
@@ -589,6 +653,15 @@ impl DefineManagerXpubsAsManager {
                 .padding(10)
                 .render(),
             )
+            .push(import_xpub(
+                &mut self.import_xpub_button,
+                importing,
+                imported_from,
+                import_error,
+                Message::DefineManagerXpubs(message::DefineManagerXpubs::ImportOurXpub(
+                    message::ImportXpub::Import,
+                )),
+            ))
             .spacing(10);
 
         let mut content = Column::new()
@@ -1,5 +1,7 @@
 pub mod app;
+pub mod args;
 pub mod conversion;
+pub mod cpfp;
 pub mod daemon;
 pub mod installer;
 pub mod loader;
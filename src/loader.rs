@@ -21,6 +21,7 @@ use crate::{
 type RevaultD = client::RevaultD<client::jsonrpc::JsonRPCClient>;
 
 pub struct Loader {
+    pub gui_config_path: PathBuf,
     pub gui_config: GUIConfig,
     pub daemon_config: Config,
     pub daemon_started: bool,
@@ -51,7 +52,11 @@ pub enum Message {
 }
 
 impl Loader {
-    pub fn new(gui_config: GUIConfig, daemon_config: Config) -> (Self, Command<Message>) {
+    pub fn new(
+        gui_config_path: PathBuf,
+        gui_config: GUIConfig,
+        daemon_config: Config,
+    ) -> (Self, Command<Message>) {
         let path = socket_path(
             &daemon_config.data_dir,
             daemon_config.bitcoind_config.network,
@@ -60,6 +65,7 @@ impl Loader {
         (
             Loader {
                 daemon_config,
+                gui_config_path,
                 gui_config,
                 step: Step::Connecting,
                 should_exit: false,
@@ -220,7 +226,7 @@ async fn connect(socket_path: PathBuf) -> Result<Arc<dyn Daemon + Sync + Send>,
     let revaultd = RevaultD::new(client);
 
     debug!("Connecting to revaultd");
-    revaultd.get_info()?;
+    revaultd.get_info().await?;
     info!("Connected to revaultd");
 
     Ok(Arc::new(revaultd))
@@ -247,9 +253,9 @@ async fn sync(
     sleep: bool,
 ) -> Result<GetInfoResult, RevaultDError> {
     if sleep {
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
     }
-    revaultd.get_info()
+    revaultd.get_info().await
 }
 
 #[derive(Debug)]
@@ -280,7 +286,7 @@ impl From<RevaultDError> for Error {
 }
 
 /// default revaultd socket path is .revault/bitcoin/revaultd_rpc
-fn socket_path(
+pub fn socket_path(
     datadir: &Option<PathBuf>,
     network: bitcoin::Network,
 ) -> Result<PathBuf, ConfigError> {
@@ -1,63 +1,29 @@
-use std::{error::Error, path::PathBuf, str::FromStr};
+use std::{error::Error, path::PathBuf};
 
 use iced::{executor, Application, Command, Element, Settings, Subscription};
 extern crate serde;
 extern crate serde_json;
 
-use revault_hwi::{
-    app::revault::RevaultHWI,
-    dummysigner::{DummySigner, DUMMYSIGNER_DEFAULT_ADDRESS},
-    specter::{Specter, SPECTER_SIMULATOR_DEFAULT_ADDRESS},
-    HWIError,
-};
+use revault_hwi::{airgap::AirgapSigner, enumerate::enumerate};
 
 use revaultd::config::Config as DaemonConfig;
 
 use revault_gui::{
     app::{
         self,
-        config::{default_datadir, ConfigError},
+        config::ConfigError,
         context::{ConfigContext, Context},
+        label::LabelStore,
         menu::Menu,
         App,
     },
+    args::{config_location, hwi_path, parse_args, ConfigLocation},
     conversion::Converter,
     installer::{self, Installer},
     loader::{self, Loader},
     revault::Role,
 };
 
-#[derive(Debug, PartialEq)]
-enum Arg {
-    ConfigPath(PathBuf),
-    DatadirPath(PathBuf),
-    Network(bitcoin::Network),
-}
-
-fn parse_args(args: Vec<String>) -> Result<Vec<Arg>, Box<dyn Error>> {
-    let mut res = Vec::new();
-    for (i, arg) in args.iter().enumerate() {
-        if arg == "--conf" {
-            if let Some(a) = args.get(i + 1) {
-                res.push(Arg::ConfigPath(PathBuf::from(a)));
-            } else {
-                return Err("missing arg to --conf".into());
-            }
-        } else if arg == "--datadir" {
-            if let Some(a) = args.get(i + 1) {
-                res.push(Arg::DatadirPath(PathBuf::from(a)));
-            } else {
-                return Err("missing arg to --datadir".into());
-            }
-        } else if arg.contains("--") {
-            let network = bitcoin::Network::from_str(args[i].trim_start_matches("--"))?;
-            res.push(Arg::Network(network));
-        }
-    }
-
-    Ok(res)
-}
-
 fn log_level_from_config(config: &app::Config) -> Result<log::LevelFilter, Box<dyn Error>> {
     if let Some(level) = &config.log_level {
         match level.as_ref() {
@@ -114,8 +80,8 @@ impl Application for GUI {
 
     fn new(config: Config) -> (GUI, Command<Self::Message>) {
         match config {
-            Config::Install(config_path, network) => {
-                let (install, command) = Installer::new(config_path, network);
+            Config::Install(config_path, network, hwi_path) => {
+                let (install, command) = Installer::new(config_path, network, hwi_path);
                 (
                     Self {
                         state: State::Installer(install),
@@ -126,10 +92,10 @@ impl Application for GUI {
                     ]),
                 )
             }
-            Config::Run(cfg) => {
+            Config::Run(path, cfg) => {
                 let daemon_cfg =
                     DaemonConfig::from_file(Some(cfg.revaultd_config_path.clone())).unwrap();
-                let (loader, command) = Loader::new(cfg, daemon_cfg);
+                let (loader, command) = Loader::new(path, cfg, daemon_cfg);
                 (
                     Self {
                         state: State::Loader(loader),
@@ -156,7 +122,7 @@ impl Application for GUI {
             let cfg = app::Config::from_file(&path).unwrap();
             let daemon_cfg =
                 DaemonConfig::from_file(Some(cfg.revaultd_config_path.clone())).unwrap();
-            let (loader, command) = Loader::new(cfg, daemon_cfg);
+            let (loader, command) = Loader::new(path, cfg, daemon_cfg);
             self.state = State::Loader(loader);
             return command.map(Message::Load);
         }
@@ -174,7 +140,12 @@ impl Application for GUI {
                     Role::Manager
                 };
 
-                let converter = Converter::new(config.daemon.bitcoind_config.network);
+                let converter = Converter::new(config.daemon.bitcoind_config.network)
+                    .with_denomination(config.gui.unit.unwrap_or_default())
+                    .with_price_source(config.gui.price_source.as_ref().map(|s| s.source()));
+                let network = config.daemon.bitcoind_config.network;
+                let hwi_path = config.gui.hwi_path.clone();
+                let airgap_signer = config.gui.airgap_signer.clone();
 
                 let mut context = Context::new(
                     config,
@@ -182,9 +153,27 @@ impl Application for GUI {
                     converter,
                     role,
                     Menu::Home,
-                    Box::new(|| Box::pin(connect_hardware_wallet())),
+                    Box::new(move || {
+                        let hwi_path = hwi_path.clone();
+                        let airgap_signer = airgap_signer.clone().map(AirgapSigner::new);
+                        Box::pin(async move {
+                            let mut devices = enumerate(network, hwi_path.as_deref()).await;
+                            if let Some(signer) = airgap_signer {
+                                devices.push((signer.descriptor(), signer.into()));
+                            }
+                            devices
+                        })
+                    }),
                 );
 
+                // The labels are kept next to the GUI configuration file.
+                if let Some(dir) = loader.gui_config_path.parent() {
+                    match LabelStore::load(dir.join(LabelStore::file_name(&network))) {
+                        Ok(labels) => context.labels = labels,
+                        Err(e) => log::warn!("Failed to load the labels: {}", e),
+                    }
+                }
+                context.gui_config_path = Some(loader.gui_config_path.clone());
                 context.blockheight = info.blockheight;
                 context.managers_threshold = info.managers_threshold;
 
@@ -233,17 +222,22 @@ impl Application for GUI {
 }
 
 pub enum Config {
-    Run(app::Config),
-    Install(PathBuf, bitcoin::Network),
+    Run(PathBuf, app::Config),
+    /// The hwi path is written to the configuration created by the installer.
+    Install(PathBuf, bitcoin::Network, Option<PathBuf>),
 }
 
 impl Config {
-    pub fn new(datadir_path: PathBuf, network: bitcoin::Network) -> Result<Self, Box<dyn Error>> {
+    pub fn new(
+        datadir_path: PathBuf,
+        network: bitcoin::Network,
+        hwi_path: Option<PathBuf>,
+    ) -> Result<Self, Box<dyn Error>> {
         let mut path = datadir_path.clone();
         path.push(app::Config::file_name(&network));
         match app::Config::from_file(&path) {
-            Ok(cfg) => Ok(Config::Run(cfg)),
-            Err(ConfigError::NotFound) => Ok(Config::Install(datadir_path, network)),
+            Ok(cfg) => Ok(Config::Run(path, cfg.with_hwi_path(hwi_path))),
+            Err(ConfigError::NotFound) => Ok(Config::Install(datadir_path, network, hwi_path)),
             Err(e) => Err(format!("Failed to read configuration file: {}", e).into()),
         }
     }
@@ -251,29 +245,18 @@ impl Config {
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = parse_args(std::env::args().collect())?;
-    let config = match args.as_slice() {
-        [] => {
-            let datadir_path = default_datadir().unwrap();
-            Config::new(datadir_path, bitcoin::Network::Bitcoin)
-        }
-        [Arg::Network(network)] => {
-            let datadir_path = default_datadir().unwrap();
-            Config::new(datadir_path, network.clone())
-        }
-        [Arg::ConfigPath(path)] => Ok(Config::Run(app::Config::from_file(&path)?)),
-        [Arg::DatadirPath(datadir_path)] => {
-            Config::new(datadir_path.clone(), bitcoin::Network::Bitcoin)
+    let hwi_path = hwi_path(&args);
+    let config = match config_location(&args)? {
+        ConfigLocation::File(path) => {
+            let cfg = app::Config::from_file(&path)?;
+            Config::Run(path, cfg.with_hwi_path(hwi_path))
         }
-        [Arg::DatadirPath(datadir_path), Arg::Network(network)]
-        | [Arg::Network(network), Arg::DatadirPath(datadir_path)] => {
-            Config::new(datadir_path.clone(), network.clone())
+        ConfigLocation::Datadir(datadir_path, network) => {
+            Config::new(datadir_path, network, hwi_path)?
         }
-        _ => {
-            return Err("Unknown args combination".into());
-        }
-    }?;
+    };
 
-    let level = if let Config::Run(cfg) = &config {
+    let level = if let Config::Run(_, cfg) = &config {
         log_level_from_config(&cfg)?
     } else {
         log::LevelFilter::Info
@@ -320,73 +303,3 @@ pub fn setup_logger(log_level: log::LevelFilter) -> Result<(), fern::InitError>
 
     Ok(())
 }
-
-pub async fn connect_hardware_wallet() -> Result<Box<dyn RevaultHWI + Send>, HWIError> {
-    if let Ok(device) = DummySigner::try_connect(DUMMYSIGNER_DEFAULT_ADDRESS).await {
-        return Ok(device.into());
-    }
-    if let Ok(device) = Specter::try_connect_simulator(SPECTER_SIMULATOR_DEFAULT_ADDRESS).await {
-        return Ok(device.into());
-    }
-
-    if let Ok(device) = Specter::try_connect_serial() {
-        return Ok(device.into());
-    }
-
-    Err(HWIError::DeviceDisconnected)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_args() {
-        assert_eq!(true, parse_args(vec!["--meth".into()]).is_err());
-        assert_eq!(true, parse_args(vec!["--datadir".into()]).is_err());
-        assert_eq!(true, parse_args(vec!["--conf".into()]).is_err());
-        assert_eq!(
-            Some(vec![
-                Arg::DatadirPath(PathBuf::from(".")),
-                Arg::ConfigPath(PathBuf::from("hello.toml")),
-            ]),
-            parse_args(
-                "--datadir . --conf hello.toml"
-                    .split(" ")
-                    .map(|a| a.to_string())
-                    .collect()
-            )
-            .ok()
-        );
-        assert_eq!(
-            Some(vec![Arg::Network(bitcoin::Network::Regtest)]),
-            parse_args(vec!["--regtest".into()]).ok()
-        );
-        assert_eq!(
-            Some(vec![
-                Arg::DatadirPath(PathBuf::from("hello")),
-                Arg::Network(bitcoin::Network::Testnet)
-            ]),
-            parse_args(
-                "--datadir hello --testnet"
-                    .split(" ")
-                    .map(|a| a.to_string())
-                    .collect()
-            )
-            .ok()
-        );
-        assert_eq!(
-            Some(vec![
-                Arg::Network(bitcoin::Network::Testnet),
-                Arg::DatadirPath(PathBuf::from("hello"))
-            ]),
-            parse_args(
-                "--testnet --datadir hello"
-                    .split(" ")
-                    .map(|a| a.to_string())
-                    .collect()
-            )
-            .ok()
-        );
-    }
-}
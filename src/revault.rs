@@ -1,3 +1,5 @@
+use bitcoin::util::bip32::{ChildNumber, DerivationPath};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Role {
     Manager,
@@ -23,3 +25,20 @@ impl Role {
     pub const STAKEHOLDER_ONLY: [Role; 1] = [Role::Stakeholder];
     pub const STAKEHOLDER_AND_MANAGER: [Role; 2] = [Role::Stakeholder, Role::Manager];
 }
+
+/// Returns the standard derivation path of the Revault participant xpubs,
+/// following BIP48 for P2WSH multisig: m/48'/coin_type'/0'/2'.
+pub fn revault_derivation_path(network: bitcoin::Network) -> DerivationPath {
+    let coin_type = if network == bitcoin::Network::Bitcoin {
+        0
+    } else {
+        1
+    };
+    vec![
+        ChildNumber::Hardened { index: 48 },
+        ChildNumber::Hardened { index: coin_type },
+        ChildNumber::Hardened { index: 0 },
+        ChildNumber::Hardened { index: 2 },
+    ]
+    .into()
+}
@@ -3,7 +3,7 @@ mod utils;
 use serde_json::json;
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use utils::{fixtures::random_daemon_config, mock::Daemon, no_hardware_wallet, sandbox::Sandbox};
 
@@ -11,14 +11,17 @@ use bitcoin::{util::bip32, Address, Amount, OutPoint};
 
 use revault_gui::{
     app::{
-        config::Config as GUIConfig,
+        config::{Config as GUIConfig, NotificationsConfig},
         context::{ConfigContext, Context},
+        export::EXPORT_PAGE_SIZE,
         menu::{Menu, VaultsMenu},
-        message::Message,
+        message::{HistoryExportMessage, HistoryFilterMessage, Message},
+        notification::{self, Notification, NotificationKind},
         state::{
-            history::HISTORY_EVENT_PAGE_SIZE, DepositState, EmergencyState, HistoryState,
-            VaultsState,
+            history::{HistoryFilter, HISTORY_EVENT_PAGE_SIZE},
+            DepositState, EmergencyState, HistoryState, VaultsState,
         },
+        watcher::WatcherEvent,
     },
     conversion::Converter,
     daemon::{
@@ -363,6 +366,39 @@ async fn test_history_state_filter() {
                 },]
             })),
         ),
+        (
+            None,
+            Ok(json!(GetHistoryResponse {
+                events: vec![
+                    HistoryEvent {
+                        blockheight: 1,
+                        date: 1,
+                        txid: bitcoin::Txid::from_str(
+                            "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d"
+                        )
+                        .unwrap(),
+                        kind: HistoryEventKind::Spend,
+                        amount: Some(1_000_000),
+                        miner_fee: Some(2000),
+                        cpfp_amount: Some(3000),
+                        vaults: Vec::new()
+                    },
+                    HistoryEvent {
+                        blockheight: 0,
+                        date: 0,
+                        txid: bitcoin::Txid::from_str(
+                            "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d"
+                        )
+                        .unwrap(),
+                        kind: HistoryEventKind::Spend,
+                        amount: Some(2_000_000),
+                        miner_fee: Some(2000),
+                        cpfp_amount: Some(3000),
+                        vaults: Vec::new()
+                    },
+                ]
+            })),
+        ),
     ]);
 
     let sandbox: Sandbox<HistoryState> = Sandbox::new(HistoryState::new());
@@ -383,32 +419,68 @@ async fn test_history_state_filter() {
     let sandbox = sandbox.load(&ctx).await;
     assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
 
-    if let HistoryState::Loaded {
-        events,
-        event_kind_filter,
-        ..
-    } = sandbox.state()
-    {
-        assert!(event_kind_filter.is_none());
+    if let HistoryState::Loaded { events, filter, .. } = sandbox.state() {
+        assert_eq!(*filter, HistoryFilter::default());
         assert_eq!(events.len(), 2);
     }
 
     let sandbox = sandbox
         .update(
             &ctx,
-            Message::FilterHistoryEvents(Some(HistoryEventKind::Deposit)),
+            Message::FilterHistoryEvents(HistoryFilter {
+                kinds: vec![HistoryEventKind::Deposit],
+                ..HistoryFilter::default()
+            }),
         )
         .await;
     assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
 
+    if let HistoryState::Loaded { events, filter, .. } = sandbox.state() {
+        assert_eq!(filter.kinds, vec![HistoryEventKind::Deposit]);
+        assert_eq!(events.len(), 1);
+    }
+
+    // An invalid amount is not applied and no request is made.
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryFilter(HistoryFilterMessage::MinAmountEdited("abc".to_string())),
+        )
+        .await;
+    let sandbox = sandbox
+        .update(&ctx, Message::HistoryFilter(HistoryFilterMessage::Apply))
+        .await;
     if let HistoryState::Loaded {
-        events,
-        event_kind_filter,
+        filter,
+        filter_form,
         ..
     } = sandbox.state()
     {
-        assert_eq!(*event_kind_filter, Some(HistoryEventKind::Deposit));
-        assert_eq!(events.len(), 1);
+        assert!(!filter_form.min_amount.valid);
+        assert_eq!(filter.kinds, vec![HistoryEventKind::Deposit]);
+    }
+
+    // The amounts are filtered once the events are fetched.
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryFilter(HistoryFilterMessage::MinAmountEdited("0.015".to_string())),
+        )
+        .await;
+    let sandbox = sandbox
+        .update(&ctx, Message::HistoryFilter(HistoryFilterMessage::Apply))
+        .await;
+    if let HistoryState::Loaded { events, filter, .. } = sandbox.state() {
+        assert_eq!(filter.kinds, ALL_HISTORY_EVENTS.to_vec());
+        assert_eq!(filter.min_amount, Some(1_500_000));
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|evt| filter.matches(&evt.event))
+                .count(),
+            1
+        );
     }
 }
 
@@ -511,19 +583,22 @@ async fn test_history_state_pagination() {
     let sandbox = sandbox
         .update(
             &ctx,
-            Message::FilterHistoryEvents(Some(HistoryEventKind::Deposit)),
+            Message::FilterHistoryEvents(HistoryFilter {
+                kinds: vec![HistoryEventKind::Deposit],
+                ..HistoryFilter::default()
+            }),
         )
         .await;
     assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
 
     if let HistoryState::Loaded {
         events,
-        event_kind_filter,
+        filter,
         has_next,
         ..
     } = sandbox.state()
     {
-        assert_eq!(*event_kind_filter, Some(HistoryEventKind::Deposit));
+        assert_eq!(filter.kinds, vec![HistoryEventKind::Deposit]);
         assert_eq!(events.len(), 20);
         assert!(has_next);
     }
@@ -533,17 +608,117 @@ async fn test_history_state_pagination() {
 
     if let HistoryState::Loaded {
         events,
-        event_kind_filter,
+        filter,
         has_next,
         ..
     } = sandbox.state()
     {
-        assert_eq!(*event_kind_filter, Some(HistoryEventKind::Deposit));
+        assert_eq!(filter.kinds, vec![HistoryEventKind::Deposit]);
         assert_eq!(events.len() as u64, 25);
         assert!(!has_next);
     }
 }
 
+/// Test that the pages are fetched until a page of events matching the
+/// amounts filter is retrieved or the history is exhausted.
+#[tokio::test]
+async fn test_history_state_filter_pagination() {
+    let mut events: Vec<HistoryEvent> = Vec::new();
+    for i in 0..25 {
+        events.push(HistoryEvent {
+            blockheight: i,
+            date: i as u32,
+            txid: bitcoin::Txid::from_str(
+                "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+            )
+            .unwrap(),
+            kind: HistoryEventKind::Deposit,
+            amount: Some(if i < 20 { 1_000_000 } else { 2_000_000 }),
+            miner_fee: None,
+            cpfp_amount: None,
+            vaults: vec![bitcoin::OutPoint {
+                txid: bitcoin::Txid::from_str(
+                    "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+                )
+                .unwrap(),
+                vout: i,
+            }],
+        });
+    }
+    let daemon = Daemon::new(vec![
+        (
+            // SystemTime::now() is used, so we cannot check the request correctness for the
+            // moment.
+            None,
+            Ok(json!(GetHistoryResponse {
+                events: events[0..20].to_vec()
+            })),
+        ),
+        (
+            None,
+            Ok(json!(GetHistoryResponse {
+                events: events[0..20].to_vec()
+            })),
+        ),
+        (
+            Some(
+                json!({"method": "gethistory", "params": Some(&[json!(&ALL_HISTORY_EVENTS), json!(0 as u32), json!(19 as u32), json!(HISTORY_EVENT_PAGE_SIZE)])}),
+            ),
+            Ok(json!(GetHistoryResponse {
+                events: events[20..25].to_vec()
+            })),
+        ),
+    ]);
+
+    let sandbox: Sandbox<HistoryState> = Sandbox::new(HistoryState::new());
+
+    let client = daemon.run();
+    let ctx = Context::new(
+        ConfigContext {
+            daemon: random_daemon_config(),
+            gui: GUIConfig::new(PathBuf::from_str("revault_gui.toml").unwrap()),
+        },
+        Arc::new(RevaultD::new(client)),
+        Converter::new(bitcoin::Network::Bitcoin),
+        Role::Stakeholder,
+        Menu::History,
+        Box::new(|| Box::pin(no_hardware_wallet())),
+    );
+
+    let sandbox = sandbox.load(&ctx).await;
+    assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
+
+    // None of the first page events match, the next page is fetched.
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::FilterHistoryEvents(HistoryFilter {
+                min_amount: Some(1_500_000),
+                ..HistoryFilter::default()
+            }),
+        )
+        .await;
+    assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
+
+    if let HistoryState::Loaded {
+        events,
+        filter,
+        has_next,
+        ..
+    } = sandbox.state()
+    {
+        assert_eq!(events.len(), 25);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|evt| filter.matches(&evt.event))
+                .count(),
+            5
+        );
+        assert!(!has_next);
+    }
+}
+
 /// Test the case in which a big batch of history events with the size superior
 /// to the HISTORY_EVENT_PAGE_SIZE happened in the same block (with the same blocktime).
 #[tokio::test]
@@ -641,6 +816,118 @@ async fn test_history_state_pagination_batching() {
     }
 }
 
+#[tokio::test]
+async fn test_history_export() {
+    let mut events: Vec<HistoryEvent> = Vec::new();
+    for i in 0..150 {
+        events.push(HistoryEvent {
+            blockheight: 150 - i,
+            date: 150 - i as u32,
+            txid: bitcoin::Txid::from_str(
+                "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+            )
+            .unwrap(),
+            kind: HistoryEventKind::Deposit,
+            amount: Some(1_000_000),
+            miner_fee: None,
+            cpfp_amount: None,
+            vaults: vec![bitcoin::OutPoint {
+                txid: bitcoin::Txid::from_str(
+                    "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+                )
+                .unwrap(),
+                vout: i,
+            }],
+        });
+    }
+    let daemon = Daemon::new(vec![
+        (None, Ok(json!(GetHistoryResponse { events: Vec::new() }))),
+        (
+            // 1970-01-01 23:59:59
+            Some(
+                json!({"method": "gethistory", "params": Some(&[json!(&[HistoryEventKind::Deposit, HistoryEventKind::Spend]), json!(0 as u32), json!(86399 as u32), json!(EXPORT_PAGE_SIZE)])}),
+            ),
+            Ok(json!(GetHistoryResponse {
+                events: events[0..100].to_vec()
+            })),
+        ),
+    ]);
+
+    let sandbox: Sandbox<HistoryState> = Sandbox::new(HistoryState::new());
+
+    let client = daemon.run();
+    let ctx = Context::new(
+        ConfigContext {
+            daemon: random_daemon_config(),
+            gui: GUIConfig::new(PathBuf::from_str("revault_gui.toml").unwrap()),
+        },
+        Arc::new(RevaultD::new(client)),
+        Converter::new(bitcoin::Network::Bitcoin),
+        Role::Stakeholder,
+        Menu::History,
+        Box::new(|| Box::pin(no_hardware_wallet())),
+    );
+
+    let sandbox = sandbox.load(&ctx).await;
+    let sandbox = sandbox.update(&ctx, Message::ExportHistory).await;
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryExport(HistoryExportMessage::KindToggled(
+                HistoryEventKind::Cancel,
+                false,
+            )),
+        )
+        .await;
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryExport(HistoryExportMessage::ToEdited("1970-01-01".to_string())),
+        )
+        .await;
+    let mut path = std::env::temp_dir();
+    path.push(format!("revault_gui_history_{}.csv", std::process::id()));
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryExport(HistoryExportMessage::PathEdited(
+                path.to_string_lossy().to_string(),
+            )),
+        )
+        .await;
+    // The page is full, the state asks for the next page with the cursor set to
+    // the date of its oldest event, but the sandbox does not run this command.
+    let sandbox = sandbox
+        .update(&ctx, Message::HistoryExport(HistoryExportMessage::Start))
+        .await;
+    // The next page shares one event with the previous one and is not full,
+    // the events are written to the file.
+    let sandbox = sandbox
+        .update(
+            &ctx,
+            Message::HistoryExport(HistoryExportMessage::HistoryEvents(Ok(
+                events[99..150].to_vec()
+            ))),
+        )
+        .await;
+
+    if let HistoryState::Loaded {
+        export: Some(export),
+        ..
+    } = sandbox.state()
+    {
+        let (count, _) = export.exported.as_ref().unwrap();
+        assert_eq!(*count, 150);
+    } else {
+        panic!("History export must be opened");
+    }
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    // header and one line per event.
+    assert_eq!(csv.lines().count(), 151);
+}
+
 #[tokio::test]
 async fn test_history_state_select_event() {
     let oupoint = bitcoin::OutPoint {
@@ -729,13 +1016,8 @@ async fn test_history_state_select_event() {
     let sandbox = sandbox.load(&ctx).await;
     assert!(matches!(sandbox.state(), HistoryState::Loaded { .. }));
 
-    if let HistoryState::Loaded {
-        events,
-        event_kind_filter,
-        ..
-    } = sandbox.state()
-    {
-        assert!(event_kind_filter.is_none());
+    if let HistoryState::Loaded { events, filter, .. } = sandbox.state() {
+        assert_eq!(*filter, HistoryFilter::default());
         assert_eq!(events.len(), 2);
     }
 
@@ -753,3 +1035,77 @@ async fn test_history_state_select_event() {
         assert!(selected_event.is_none());
     }
 }
+
+/// NotificationSink records the notifications instead of raising them on the desktop.
+#[derive(Debug, Clone, Default)]
+struct NotificationSink {
+    notifications: Arc<Mutex<Vec<Notification>>>,
+}
+
+impl NotificationSink {
+    fn notifications(&self) -> Vec<Notification> {
+        self.notifications.lock().unwrap().clone()
+    }
+}
+
+impl notification::NotificationSink for NotificationSink {
+    fn notify(&self, notification: &Notification) {
+        self.notifications
+            .lock()
+            .unwrap()
+            .push(notification.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_notifications() {
+    let daemon = Daemon::new(Vec::new());
+    let client = daemon.run();
+    let mut ctx = Context::new(
+        ConfigContext {
+            daemon: random_daemon_config(),
+            gui: GUIConfig::new(PathBuf::from_str("revault_gui.toml").unwrap()),
+        },
+        Arc::new(RevaultD::new(client)),
+        Converter::new(bitcoin::Network::Bitcoin),
+        Role::Stakeholder,
+        Menu::Home,
+        Box::new(|| Box::pin(no_hardware_wallet())),
+    );
+    let sink = NotificationSink::default();
+    ctx.notification_sink = Box::new(sink.clone());
+
+    let txid =
+        bitcoin::Txid::from_str("a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d")
+            .unwrap();
+    let deposit = WatcherEvent::NewDeposit {
+        outpoint: OutPoint::new(txid, 0),
+        amount: Amount::from_sat(500),
+    };
+    ctx.notify(&[
+        deposit.clone(),
+        WatcherEvent::VaultStatusChanged {
+            outpoint: OutPoint::new(txid, 1),
+            previous: VaultStatus::Active,
+            status: VaultStatus::Unvaulting,
+        },
+    ]);
+
+    // Only the critical events are notified by default.
+    let notifications = sink.notifications();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::Unvaulting);
+    assert_eq!(ctx.notifications.unread(), 1);
+
+    ctx.config.gui.notifications = Some(NotificationsConfig {
+        desktop: false,
+        kinds: NotificationKind::ALL.to_vec(),
+    });
+    ctx.notify(&[deposit]);
+    assert_eq!(sink.notifications().len(), 1);
+    assert_eq!(ctx.notifications.unread(), 2);
+    assert_eq!(
+        ctx.notifications.notifications()[0].kind,
+        NotificationKind::Deposit
+    );
+}
@@ -15,12 +15,12 @@ use revault_gui::{
         context::{ConfigContext, Context},
         menu::Menu,
         message::Message,
-        state::RevaultVaultsState,
+        state::{RevaultVaultsState, UnvaultAlertsState},
     },
     conversion::Converter,
     daemon::{
-        client::{ListVaultsResponse, RevaultD},
-        model::{Vault, VaultStatus},
+        client::{ListOnchainTransactionsResponse, ListVaultsResponse, RevaultD},
+        model::{Vault, VaultStatus, VaultTransactions, WalletTransaction, MOVING_VAULT_STATUSES},
     },
     revault::Role,
 };
@@ -185,3 +185,122 @@ async fn test_revault_state() {
         assert_eq!(vaults.len(), 2);
     }
 }
+
+#[tokio::test]
+async fn test_unvault_alerts_state() {
+    const WALLET_TX_HEX: &str = "0200000001b4243a48b54cc360e754e0175a985a49b67cf4615d8523ec5aa46d42421cdf7d0000000000504200000280b2010000000000220020b9be8f8574f8da64bb1cb6668f6134bc4706df7936eeab8411f9d82de20a895b08280954020000000000000000";
+    let vault = |vout: u32, status: VaultStatus| Vault {
+        address: Address::from_str(
+            "tb1qkldgvljmjpxrjq2ev5qxe8dvhn0dph9q85pwtfkjeanmwdue2akqj4twxj",
+        )
+        .unwrap(),
+        amount: Amount::from_sat(500),
+        derivation_index: bip32::ChildNumber::from_normal_idx(0).unwrap(),
+        status,
+        txid: bitcoin::Txid::from_str(
+            "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+        )
+        .unwrap(),
+        vout,
+        blockheight: Some(1),
+        delegated_at: None,
+        secured_at: Some(1),
+        funded_at: Some(1),
+        moved_at: None,
+    };
+    let wallet_tx = |blockheight: Option<u32>| WalletTransaction {
+        blockheight,
+        hex: WALLET_TX_HEX.to_string(),
+        received_time: 1,
+        blocktime: blockheight,
+    };
+    let unvaulting =
+        OutPoint::from_str("a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d:0")
+            .unwrap();
+    let spending =
+        OutPoint::from_str("a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d:1")
+            .unwrap();
+
+    let daemon = Daemon::new(vec![
+        (
+            Some(json!({"method": "listvaults", "params": Some(&[
+                MOVING_VAULT_STATUSES.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
+            ])})),
+            Ok(json!(ListVaultsResponse {
+                vaults: vec![
+                    vault(0, VaultStatus::Unvaulting),
+                    vault(1, VaultStatus::Spending)
+                ]
+            })),
+        ),
+        (
+            Some(json!({"method": "listonchaintransactions", "params": [[unvaulting, spending]]})),
+            Ok(json!(ListOnchainTransactionsResponse {
+                onchain_transactions: vec![
+                    VaultTransactions {
+                        vault_outpoint: unvaulting,
+                        deposit: wallet_tx(Some(1)),
+                        unvault: Some(wallet_tx(Some(10))),
+                        spend: None,
+                        cancel: None,
+                        emergency: None,
+                        unvault_emergency: None,
+                    },
+                    VaultTransactions {
+                        vault_outpoint: spending,
+                        deposit: wallet_tx(Some(1)),
+                        unvault: Some(wallet_tx(Some(2))),
+                        spend: Some(wallet_tx(None)),
+                        cancel: None,
+                        emergency: None,
+                        unvault_emergency: None,
+                    },
+                ]
+            })),
+        ),
+        (None, Ok(json!({ "spend_txs": [] }))),
+        (
+            Some(json!({"method": "revault", "params": Some(&[unvaulting])})),
+            Ok(json!({})),
+        ),
+    ]);
+
+    let sandbox: Sandbox<UnvaultAlertsState> = Sandbox::new(UnvaultAlertsState::new());
+
+    let client = daemon.run();
+    let ctx = Context::new(
+        ConfigContext {
+            daemon: random_daemon_config(),
+            gui: GUIConfig::new(PathBuf::from_str("revault_gui.toml").unwrap()),
+        },
+        Arc::new(RevaultD::new(client)),
+        Converter::new(bitcoin::Network::Bitcoin),
+        Role::Stakeholder,
+        Menu::UnvaultAlerts,
+        Box::new(|| Box::pin(no_hardware_wallet())),
+    );
+
+    let sandbox = sandbox.load(&ctx).await;
+    if let UnvaultAlertsState::Loaded { alerts, .. } = sandbox.state() {
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts.iter().all(|alert| alert.spend_tx.is_none()));
+        assert_eq!(alerts[0].blocks_before_timelock(6, 12), Some(4));
+        assert!(alerts[0].can_cancel());
+        assert!(!alerts[1].can_cancel());
+    } else {
+        panic!("Unvault alerts are not loaded");
+    }
+
+    // A spending vault cannot be canceled, no request is sent.
+    let sandbox = sandbox.update(&ctx, Message::CancelUnvault(spending)).await;
+    let sandbox = sandbox
+        .update(&ctx, Message::CancelUnvault(unvaulting))
+        .await;
+    match sandbox.state() {
+        UnvaultAlertsState::Loaded { alerts, .. } => {
+            assert_eq!(alerts[0].vault.status, VaultStatus::Canceling);
+            assert!(alerts[0].warning.is_none());
+        }
+        _ => panic!("Unvault alerts are not loaded"),
+    }
+}
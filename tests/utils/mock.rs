@@ -1,37 +1,50 @@
+use async_trait::async_trait;
 use revault_gui::daemon::{client::Client, RevaultDError};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Value};
 use std::fmt::Debug;
-use std::sync::{
-    mpsc::{channel, Receiver, Sender},
-    Mutex,
-};
 use std::thread;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+type Request = (Value, oneshot::Sender<Result<Value, RevaultDError>>);
 
 #[derive(Debug)]
 pub struct DaemonClient {
-    transport: Mutex<(Sender<Value>, Receiver<Result<Value, RevaultDError>>)>,
+    transport: mpsc::UnboundedSender<Request>,
 }
 
+#[async_trait]
 impl Client for DaemonClient {
     type Error = RevaultDError;
-    fn request<S: Serialize + Debug, D: DeserializeOwned + Debug>(
+    async fn request<S: Serialize + Debug + Send, D: DeserializeOwned + Debug + Send>(
         &self,
         method: &str,
         params: Option<S>,
     ) -> Result<D, Self::Error> {
         let req = json!({"method": method, "params": params});
-        let connection = self.transport.lock().expect("Failed to unlock");
-        connection
-            .0
-            .send(req)
+        let (sender, receiver) = oneshot::channel();
+        self.transport
+            .send((req, sender))
             .expect("Mock client failed to send request");
-        connection
-            .1
-            .recv()
+        receiver
+            .await
             .expect("Mock client failed to receive response")
             .map(|value| serde_json::from_value(value).unwrap())
     }
+
+    /// The mock daemon answers at once, the timeout is ignored.
+    async fn request_with_timeout<
+        S: Serialize + Debug + Send,
+        D: DeserializeOwned + Debug + Send,
+    >(
+        &self,
+        method: &str,
+        params: Option<S>,
+        _timeout: Option<Duration>,
+    ) -> Result<D, Self::Error> {
+        self.request(method, params).await
+    }
 }
 
 pub struct Daemon {
@@ -44,32 +57,28 @@ impl Daemon {
     }
 
     pub fn run(self) -> DaemonClient {
-        let (client_sender, daemon_receiver) = channel();
-        let (daemon_sender, client_receiver) = channel();
+        let (client_sender, mut daemon_receiver) = mpsc::unbounded_channel::<Request>();
 
         thread::spawn(move || {
             let mut requests = self.requests.into_iter();
-            while let Ok(msg) = daemon_receiver.recv() {
+            while let Some((msg, response_sender)) = daemon_receiver.blocking_recv() {
                 let request = requests
                     .next()
                     .expect("Mock Daemon must have all requests mocked in the right order");
                 if let Some(body) = request.0 {
                     assert_eq!(body, msg);
                 }
-                daemon_sender
+                response_sender
                     .send(request.1)
                     .expect("Mock daemon failed to send response")
             }
-            // close the daemon -> client channel after
-            // the client -> daemon channel is closed.
-            // (client -> daemon channel is closed when DaemonClient is dropped)
-            drop(daemon_sender);
+            // The client -> daemon channel is closed when DaemonClient is dropped.
             // Readable with `cargo test -- --nocapture`
             println!("The daemon has stopped!");
         });
 
         DaemonClient {
-            transport: Mutex::new((client_sender, client_receiver)),
+            transport: client_sender,
         }
     }
 }
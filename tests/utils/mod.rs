@@ -2,8 +2,8 @@ pub mod fixtures;
 pub mod mock;
 pub mod sandbox;
 
-use revault_hwi::{app::revault::RevaultHWI, HWIError};
+use revault_hwi::{app::revault::RevaultHWI, DeviceDescriptor};
 
-pub async fn no_hardware_wallet() -> Result<Box<dyn RevaultHWI + Send>, HWIError> {
-    Err(HWIError::DeviceNotFound)
+pub async fn no_hardware_wallet() -> Vec<(DeviceDescriptor, Box<dyn RevaultHWI + Send>)> {
+    Vec::new()
 }
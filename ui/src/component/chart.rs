@@ -0,0 +1,189 @@
+use iced::{
+    alignment,
+    canvas::{self, Canvas, Cursor, Frame, Geometry, Path, Program, Stroke},
+    Color, Element, Length, Point, Rectangle, Size,
+};
+
+use crate::color;
+
+/// Space on the left of the plot for the values of the vertical axis.
+const AXIS_WIDTH: f32 = 80.0;
+/// Space under the plot for the labels of the horizontal axis.
+const LABELS_HEIGHT: f32 = 20.0;
+const PADDING: f32 = 10.0;
+/// Maximum number of labels drawn under the plot.
+const MAX_LABELS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Line,
+    /// The bar series are drawn side by side in each slot.
+    Bars,
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub kind: SeriesKind,
+    pub color: Color,
+    /// One value per label of the chart.
+    pub values: Vec<f64>,
+}
+
+impl Series {
+    pub fn line(color: Color, values: Vec<f64>) -> Self {
+        Self {
+            kind: SeriesKind::Line,
+            color,
+            values,
+        }
+    }
+
+    pub fn bars(color: Color, values: Vec<f64>) -> Self {
+        Self {
+            kind: SeriesKind::Bars,
+            color,
+            values,
+        }
+    }
+}
+
+/// Chart draws series of values along labels, with the iced canvas.
+pub struct Chart<'a> {
+    labels: Vec<String>,
+    series: Vec<Series>,
+    format: Box<dyn Fn(f64) -> String + 'a>,
+}
+
+impl<'a> Chart<'a> {
+    pub fn new(labels: Vec<String>) -> Self {
+        Self {
+            labels,
+            series: Vec::new(),
+            format: Box::new(|value| format!("{}", value)),
+        }
+    }
+
+    pub fn push(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Sets the formatting of the values of the vertical axis.
+    pub fn format(mut self, format: impl Fn(f64) -> String + 'a) -> Self {
+        self.format = Box::new(format);
+        self
+    }
+
+    pub fn view<T: 'static>(self, height: u16) -> Element<'a, T> {
+        Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(height))
+            .into()
+    }
+
+    /// Lowest and highest values of the vertical axis, zero is always
+    /// included so that the bars start from it.
+    fn range(&self) -> (f64, f64) {
+        let values = self.series.iter().flat_map(|s| s.values.iter().copied());
+        let (min, max) = values.fold((0.0_f64, 0.0_f64), |(min, max), v| (min.min(v), max.max(v)));
+        if max > min {
+            (min, max)
+        } else {
+            (min, min + 1.0)
+        }
+    }
+}
+
+impl<'a, T> Program<T> for Chart<'a> {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let plot = Rectangle {
+            x: AXIS_WIDTH,
+            y: PADDING,
+            width: (frame.width() - AXIS_WIDTH - PADDING).max(0.0),
+            height: (frame.height() - LABELS_HEIGHT - PADDING).max(0.0),
+        };
+        if self.labels.is_empty() || plot.width == 0.0 || plot.height == 0.0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let (min, max) = self.range();
+        let y = |value: f64| plot.y + ((max - value) / (max - min)) as f32 * plot.height;
+
+        for value in [min, (min + max) / 2.0, max] {
+            frame.stroke(
+                &Path::line(
+                    Point::new(plot.x, y(value)),
+                    Point::new(plot.x + plot.width, y(value)),
+                ),
+                Stroke::default()
+                    .with_color(color::SECONDARY)
+                    .with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                content: (self.format)(value),
+                position: Point::new(plot.x - PADDING, y(value)),
+                color: color::DARK_GREY,
+                size: 14.0,
+                horizontal_alignment: alignment::Horizontal::Right,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+
+        let slot = plot.width / self.labels.len() as f32;
+        let center = |i: usize| plot.x + slot * (i as f32 + 0.5);
+
+        let step = self.labels.len().div_ceil(MAX_LABELS);
+        for (i, label) in self.labels.iter().enumerate().step_by(step) {
+            frame.fill_text(canvas::Text {
+                content: label.clone(),
+                position: Point::new(center(i), plot.y + plot.height + LABELS_HEIGHT / 2.0),
+                color: color::DARK_GREY,
+                size: 14.0,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+
+        let bars: Vec<&Series> = self
+            .series
+            .iter()
+            .filter(|s| s.kind == SeriesKind::Bars)
+            .collect();
+        if !bars.is_empty() {
+            let group = slot * 0.8;
+            let width = group / bars.len() as f32;
+            for (k, series) in bars.iter().enumerate() {
+                for (i, value) in series.values.iter().enumerate() {
+                    let top = y(value.max(0.0));
+                    let bottom = y(value.min(0.0));
+                    frame.fill_rectangle(
+                        Point::new(center(i) - group / 2.0 + width * k as f32, top),
+                        Size::new(width, bottom - top),
+                        series.color,
+                    );
+                }
+            }
+        }
+
+        for series in self.series.iter().filter(|s| s.kind == SeriesKind::Line) {
+            let line = Path::new(|builder| {
+                for (i, value) in series.values.iter().enumerate() {
+                    if i == 0 {
+                        builder.move_to(Point::new(center(i), y(*value)));
+                    } else {
+                        builder.line_to(Point::new(center(i), y(*value)));
+                    }
+                }
+            });
+            frame.stroke(
+                &line,
+                Stroke::default().with_color(series.color).with_width(2.0),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,5 +1,6 @@
 pub mod badge;
 pub mod button;
+pub mod chart;
 pub mod form;
 pub mod image;
 pub mod notification;
@@ -129,6 +129,10 @@ pub fn warning_icon() -> Text {
     icon('\u{F33B}')
 }
 
+pub fn download_icon() -> Text {
+    icon('\u{F30A}')
+}
+
 pub fn trash_icon() -> Text {
     icon('\u{F5DE}')
 }
@@ -162,3 +166,7 @@ pub fn done_icon() -> Text {
 pub fn todo_icon() -> Text {
     icon('\u{F28A}')
 }
+
+pub fn bell_icon() -> Text {
+    icon('\u{F18A}')
+}